@@ -0,0 +1,54 @@
+use joker::track::*;
+
+use id::Id;
+use expr::Expr;
+use obj::PropKey;
+use patt::Patt;
+use fun::Fun;
+use stmt::{Script, Block};
+use types::TypeParam;
+
+// The location of a decorated class starts at its first decorator.
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct Class<Id> {
+    pub location: Option<Span>,
+    pub decorators: Vec<Decorator>,
+    pub id: Id,
+    pub type_params: Vec<TypeParam>, // only in the parser's types mode
+    pub extends: Option<Box<Expr>>,
+    pub body: Vec<ClassMember>
+}
+
+// The bool flags in Method, Get, Set and Field indicate static members.
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub enum ClassMember {
+    Constructor(Fun<PropKey>),
+    Method(Option<Span>, bool, Fun<PropKey>),
+    Get(Option<Span>, bool, PropKey, Script),
+    Set(Option<Span>, bool, PropKey, Patt<Id>, Script),
+    Field(Option<Span>, bool, PropKey, Option<Expr>),
+    StaticBlock(Block),
+    Decorated(Option<Span>, Vec<Decorator>, Box<ClassMember>) // never a Constructor or StaticBlock
+}
+
+// "@" followed by an identifier, a member chain or a call on one, or a
+// parenthesized expression (stored without the parentheses).
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct Decorator {
+    pub location: Option<Span>,
+    pub expr: Expr
+}
+
+impl ClassMember {
+    pub fn is_static(&self) -> bool {
+        match *self {
+            ClassMember::Constructor(_) => false,
+            ClassMember::Method(_, is_static, _)
+          | ClassMember::Get(_, is_static, _, _)
+          | ClassMember::Set(_, is_static, _, _, _)
+          | ClassMember::Field(_, is_static, _, _) => is_static,
+            ClassMember::StaticBlock(_) => true,
+            ClassMember::Decorated(_, _, ref member) => member.is_static()
+        }
+    }
+}
@@ -2,6 +2,7 @@ use std::error::Error as StdError;
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use joker::track::{Span, TrackingRef};
+use id::Id;
 use expr::{Expr, ExprListItem};
 use patt::{Patt, RestPatt, AssignTarget, CompoundPatt, PropPatt};
 use obj::{Prop, PropVal};
@@ -9,7 +10,11 @@ use obj::{Prop, PropVal};
 #[derive(Debug, PartialEq, Clone)]
 pub enum Error {
     InvalidAssignTarget(Option<Span>),
-    InvalidPropPatt(Option<Span>)
+    OptionalChain(Option<Span>),
+    InvalidPropPatt(Option<Span>),
+    InvalidBindingPatt(Option<Span>),
+    Parenthesized(Option<Span>),
+    RestTrailingComma(Option<Span>)
 }
 
 impl Display for Error {
@@ -22,7 +27,11 @@ impl StdError for Error {
     fn description(&self) -> &str {
         match *self {
             Error::InvalidAssignTarget(_) => "invalid assignment pattern",
+            Error::OptionalChain(_) => "optional chain in assignment pattern",
             Error::InvalidPropPatt(_) => "invalid object property in assignment pattern",
+            Error::InvalidBindingPatt(_) => "invalid binding pattern",
+            Error::Parenthesized(_) => "parenthesized expression in pattern",
+            Error::RestTrailingComma(_) => "trailing comma after rest element",
         }
     }
 
@@ -40,7 +49,9 @@ impl IntoAssignTarget for Expr {
         Ok(match self {
             Expr::Id(id)                     => AssignTarget::Id(id),
             Expr::Dot(location, obj, key)    => AssignTarget::Dot(location, obj, key),
+            Expr::PrivateDot(location, obj, key) => AssignTarget::PrivateDot(location, obj, key),
             Expr::Brack(location, obj, prop) => AssignTarget::Brack(location, obj, prop),
+            Expr::Chain(location, _)         => { return Err(Error::OptionalChain(location)); }
             _ => { return Err(Error::InvalidAssignTarget(*self.tracking_ref())); }
         })
     }
@@ -53,20 +64,28 @@ pub trait IntoAssignPatt : IntoAssignTarget {
 impl IntoAssignPatt for Expr {
     fn into_assign_patt(self) -> Result<Patt<AssignTarget>, Error> {
         Ok(match self {
-            Expr::Obj(location, props) => {
+            Expr::Obj(location, mut props) => {
+                let mut rest = None;
+                if let Some(last) = props.pop() {
+                    if let Prop::Spread(..) = last {
+                        rest = Some(Box::new(last.into_assign_rest()?));
+                    } else {
+                        props.push(last);
+                    }
+                }
                 let mut prop_patts = Vec::with_capacity(props.len());
                 for prop in props {
                     prop_patts.push(prop.into_assign_prop()?);
                 }
-                Patt::Compound(CompoundPatt::Obj(location, prop_patts))
+                Patt::Compound(CompoundPatt::Obj(location, prop_patts, rest))
             }
             Expr::Arr(location, mut exprs) => {
                 let mut patts = Vec::with_capacity(exprs.len());
                 let mut rest = None;
                 if let Some(last) = exprs.pop() {
-                    if let Some(ExprListItem::Spread(None, expr)) = last {
+                    if let Some(ExprListItem::Spread(location, expr)) = last {
                         rest = Some(Box::new(RestPatt {
-                            location: None,
+                            location: location,
                             patt: expr.into_assign_patt()?
                         }));
                     } else {
@@ -75,7 +94,7 @@ impl IntoAssignPatt for Expr {
                 }
                 for expr in exprs {
                     patts.push(match expr {
-                        Some(ExprListItem::Expr(expr)) => Some(expr.into_assign_patt()?),
+                        Some(ExprListItem::Expr(expr)) => Some(into_assign_elt(expr)?),
                         Some(ExprListItem::Spread(loc, _)) => { return Err(Error::InvalidAssignTarget(loc)); }
                         None => None
                     });
@@ -87,8 +106,17 @@ impl IntoAssignPatt for Expr {
     }
 }
 
+// AssignmentElement ::= DestructuringAssignmentTarget Initializer?
+fn into_assign_elt(expr: Expr) -> Result<Patt<AssignTarget>, Error> {
+    match expr {
+        Expr::Assign(location, patt, init) => Ok(Patt::Default(location, Box::new(patt), init)),
+        _ => expr.into_assign_patt()
+    }
+}
+
 pub trait IntoAssignProp {
     fn into_assign_prop(self) -> Result<PropPatt<AssignTarget>, Error>;
+    fn into_assign_rest(self) -> Result<RestPatt<AssignTarget>, Error>;
 }
 
 impl IntoAssignProp for Prop {
@@ -96,12 +124,186 @@ impl IntoAssignProp for Prop {
         let location = *self.tracking_ref();
         Ok(match self {
             Prop::Regular(location, key, PropVal::Init(expr)) => {
-                PropPatt::Regular(location, key, expr.into_assign_patt()?)
+                PropPatt::Regular(location, key, into_assign_elt(expr)?)
             }
             Prop::Shorthand(id) => {
                 PropPatt::Shorthand(id)
             }
+            Prop::CoverInit(location, id, init) => {
+                PropPatt::ShorthandDefault(location, id, init)
+            }
             _ => { return Err(Error::InvalidPropPatt(location)); }
         })
     }
+
+    // An object rest element has to be a simple assignment target.
+    fn into_assign_rest(self) -> Result<RestPatt<AssignTarget>, Error> {
+        let location = *self.tracking_ref();
+        match self {
+            Prop::Spread(_, expr) => {
+                Ok(RestPatt {
+                    location: location,
+                    patt: Patt::Simple(expr.into_assign_target()?)
+                })
+            }
+            _ => Err(Error::InvalidPropPatt(location))
+        }
+    }
+}
+
+pub trait IntoBindingPatt {
+    fn into_binding_patt(self) -> Result<Patt<Id>, Error>;
+    fn into_binding_elt(self) -> Result<Patt<Id>, Error>;
+}
+
+impl IntoBindingPatt for Expr {
+    fn into_binding_patt(self) -> Result<Patt<Id>, Error> {
+        Ok(match self {
+            Expr::Id(id) => Patt::Simple(id),
+            Expr::Obj(location, mut props) => {
+                let mut rest = None;
+                if let Some(last) = props.pop() {
+                    if let Prop::Spread(..) = last {
+                        rest = Some(Box::new(last.into_binding_rest()?));
+                    } else {
+                        props.push(last);
+                    }
+                }
+                let mut prop_patts = Vec::with_capacity(props.len());
+                for prop in props {
+                    prop_patts.push(prop.into_binding_prop()?);
+                }
+                Patt::Compound(CompoundPatt::Obj(location, prop_patts, rest))
+            }
+            Expr::Arr(location, mut exprs) => {
+                let mut patts = Vec::with_capacity(exprs.len());
+                let mut rest = None;
+                if let Some(last) = exprs.pop() {
+                    if let Some(ExprListItem::Spread(location, expr)) = last {
+                        rest = Some(Box::new(RestPatt {
+                            location: location,
+                            patt: expr.into_binding_patt()?
+                        }));
+                    } else {
+                        exprs.push(last);
+                    }
+                }
+                for expr in exprs {
+                    patts.push(match expr {
+                        Some(ExprListItem::Expr(expr)) => Some(expr.into_binding_elt()?),
+                        Some(ExprListItem::Spread(loc, _)) => { return Err(Error::InvalidBindingPatt(loc)); }
+                        None => None
+                    });
+                }
+                Patt::Compound(CompoundPatt::Arr(location, patts, rest))
+            }
+            _ => { return Err(Error::InvalidBindingPatt(*self.tracking_ref())); }
+        })
+    }
+
+    // BindingElement ::= BindingTarget Initializer?
+    fn into_binding_elt(self) -> Result<Patt<Id>, Error> {
+        match self {
+            Expr::Assign(location, patt, init) => {
+                Ok(Patt::Default(location, Box::new(assign_into_binding_patt(patt)?), init))
+            }
+            _ => self.into_binding_patt()
+        }
+    }
+}
+
+// An initializer in the cover grammar has already been parsed as an
+// assignment, so its target has to be converted back into a binding.
+fn assign_into_binding_patt(patt: Patt<AssignTarget>) -> Result<Patt<Id>, Error> {
+    Ok(match patt {
+        Patt::Simple(AssignTarget::Id(id)) => Patt::Simple(id),
+        Patt::Simple(target) => { return Err(Error::InvalidBindingPatt(*target.tracking_ref())); }
+        Patt::Compound(CompoundPatt::Arr(location, elts, rest)) => {
+            let mut patts = Vec::with_capacity(elts.len());
+            for elt in elts {
+                patts.push(match elt {
+                    Some(patt) => Some(assign_into_binding_patt(patt)?),
+                    None => None
+                });
+            }
+            let rest = match rest {
+                Some(rest) => {
+                    let rest = *rest;
+                    Some(Box::new(RestPatt {
+                        location: rest.location,
+                        patt: assign_into_binding_patt(rest.patt)?
+                    }))
+                }
+                None => None
+            };
+            Patt::Compound(CompoundPatt::Arr(location, patts, rest))
+        }
+        Patt::Compound(CompoundPatt::Obj(location, props, rest)) => {
+            let mut prop_patts = Vec::with_capacity(props.len());
+            for prop in props {
+                prop_patts.push(match prop {
+                    PropPatt::Regular(location, key, patt) => {
+                        PropPatt::Regular(location, key, assign_into_binding_patt(patt)?)
+                    }
+                    PropPatt::Shorthand(id) => PropPatt::Shorthand(id),
+                    PropPatt::ShorthandDefault(location, id, init) => PropPatt::ShorthandDefault(location, id, init)
+                });
+            }
+            let rest = match rest {
+                Some(rest) => {
+                    let rest = *rest;
+                    Some(Box::new(RestPatt {
+                        location: rest.location,
+                        patt: assign_into_binding_patt(rest.patt)?
+                    }))
+                }
+                None => None
+            };
+            Patt::Compound(CompoundPatt::Obj(location, prop_patts, rest))
+        }
+        Patt::Default(location, patt, init) => {
+            Patt::Default(location, Box::new(assign_into_binding_patt(*patt)?), init)
+        }
+        Patt::Typed(location, patt, ty) => {
+            Patt::Typed(location, Box::new(assign_into_binding_patt(*patt)?), ty)
+        }
+        Patt::Optional(location, patt) => {
+            Patt::Optional(location, Box::new(assign_into_binding_patt(*patt)?))
+        }
+    })
+}
+
+pub trait IntoBindingProp {
+    fn into_binding_prop(self) -> Result<PropPatt<Id>, Error>;
+    fn into_binding_rest(self) -> Result<RestPatt<Id>, Error>;
+}
+
+impl IntoBindingProp for Prop {
+    fn into_binding_prop(self) -> Result<PropPatt<Id>, Error> {
+        let location = *self.tracking_ref();
+        Ok(match self {
+            Prop::Regular(location, key, PropVal::Init(expr)) => {
+                PropPatt::Regular(location, key, expr.into_binding_elt()?)
+            }
+            Prop::Shorthand(id) => {
+                PropPatt::Shorthand(id)
+            }
+            Prop::CoverInit(location, id, init) => {
+                PropPatt::ShorthandDefault(location, id, init)
+            }
+            _ => { return Err(Error::InvalidPropPatt(location)); }
+        })
+    }
+
+    // An object rest element has to be a binding identifier.
+    fn into_binding_rest(self) -> Result<RestPatt<Id>, Error> {
+        let location = *self.tracking_ref();
+        match self {
+            Prop::Spread(_, Expr::Id(id)) => {
+                Ok(RestPatt { location: location, patt: Patt::Simple(id) })
+            }
+            Prop::Spread(_, expr) => Err(Error::InvalidBindingPatt(*expr.tracking_ref())),
+            _ => Err(Error::InvalidPropPatt(location))
+        }
+    }
 }
@@ -3,34 +3,99 @@ use joker::token::StringLiteral;
 
 use id::Id;
 use fun::Fun;
+use class::Class;
 use patt::{Patt, CompoundPatt};
 use expr::Expr;
+use obj::DotKey;
 use punc::Semi;
+use types::{Interface, TypeAlias};
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub enum Import {
-    // ES6: more import forms
-    ForEffect(Option<Span>, StringLiteral)
+    ForEffect(Option<Span>, StringLiteral),
+    Default(Option<Span>, Id, StringLiteral),
+    Namespace(Option<Span>, Option<Id>, Id, StringLiteral),
+    Named(Option<Span>, Option<Id>, Vec<ImportSpec>, StringLiteral)
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct ImportSpec {
+    pub location: Option<Span>,
+    pub imported: DotKey,
+    pub local: Id
 }
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub enum Export {
-    // ES6: more export forms
     Var(Option<Span>, Vec<Dtor>, Semi),
-    Decl(Decl)
+    Decl(Option<Span>, Decl),
+    DefaultExpr(Option<Span>, Expr, Semi),
+    DefaultFun(Option<Span>, Fun<Option<Id>>),
+    DefaultClass(Option<Span>, Class<Option<Id>>),
+    Named(Option<Span>, Vec<ExportSpec>, Semi),
+    NamedFrom(Option<Span>, Vec<ExportSpec>, StringLiteral, Semi),
+    All(Option<Span>, StringLiteral, Semi)
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct ExportSpec {
+    pub location: Option<Span>,
+    pub local: DotKey,
+    pub exported: DotKey
+}
+
+fn id_key(id: &Id) -> DotKey {
+    DotKey {
+        location: id.location,
+        value: id.name.as_ref().to_string()
+    }
+}
+
+impl Export {
+    // 15.2.3.2 Static Semantics: ExportedNames
+    pub fn exported_names(&self) -> Vec<DotKey> {
+        match *self {
+            Export::Var(_, ref dtors, _)
+          | Export::Decl(_, Decl::Let(_, ref dtors, _)) => {
+                dtors.iter().flat_map(|dtor| dtor.bound_names()).map(id_key).collect()
+            }
+            Export::Decl(_, Decl::Const(_, ref dtors, _)) => {
+                dtors.iter().flat_map(|dtor| dtor.patt.bound_names()).map(id_key).collect()
+            }
+            Export::Decl(_, Decl::Fun(ref fun)) => vec![id_key(&fun.id)],
+            Export::Decl(_, Decl::Class(ref class)) => vec![id_key(&class.id)],
+            // Interfaces and type aliases only export type names.
+            Export::Decl(_, Decl::Interface(_))
+          | Export::Decl(_, Decl::TypeAlias(_)) => vec![],
+            Export::DefaultExpr(location, _, _)
+          | Export::DefaultFun(location, _)
+          | Export::DefaultClass(location, _) => {
+                vec![DotKey { location: location, value: "default".to_string() }]
+            }
+            Export::Named(_, ref specs, _)
+          | Export::NamedFrom(_, ref specs, _, _) => {
+                specs.iter().map(|spec| spec.exported.clone()).collect()
+            }
+            Export::All(_, _, _) => vec![]
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub enum Decl {
     Fun(Fun<Id>),
+    Class(Class<Id>),
     Let(Option<Span>, Vec<Dtor>, Semi),
-    Const(Option<Span>, Vec<ConstDtor>, Semi)
+    Const(Option<Span>, Vec<ConstDtor>, Semi),
+    Interface(Interface),
+    TypeAlias(TypeAlias)
 }
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub enum Dtor {
     Simple(Option<Span>, Id, Option<Expr>),
-    Compound(Option<Span>, CompoundPatt<Id>, Expr)
+    Compound(Option<Span>, CompoundPatt<Id>, Expr),
+    Typed(Option<Span>, Patt<Id>, Option<Expr>) // the pattern is a Patt::Typed
 }
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
@@ -40,10 +105,20 @@ pub struct ConstDtor {
     pub value: Expr
 }
 
+impl Dtor {
+    pub fn bound_names(&self) -> Vec<&Id> {
+        match *self {
+            Dtor::Simple(_, ref id, _) => vec![id],
+            Dtor::Compound(_, ref patt, _) => patt.bound_names(),
+            Dtor::Typed(_, ref patt, _) => patt.bound_names()
+        }
+    }
+}
+
 pub trait DtorExt: Sized {
     fn from_simple_init(Id, Expr) -> Self;
     fn from_compound_init(CompoundPatt<Id>, Expr) -> Self;
-    fn from_init(Patt<Id>, Expr) -> Self;
+    fn from_init(Patt<Id>, Expr) -> Result<Self, Patt<Id>>;
     fn from_init_opt(Patt<Id>, Option<Expr>) -> Result<Self, Patt<Id>>;
 }
 
@@ -56,11 +131,8 @@ impl DtorExt for Dtor {
         Dtor::Simple(span(&lhs, &rhs), lhs, Some(rhs))
     }
 
-    fn from_init(lhs: Patt<Id>, rhs: Expr) -> Dtor {
-        match lhs {
-            Patt::Simple(id) => Dtor::from_simple_init(id, rhs),
-            Patt::Compound(patt) => Dtor::from_compound_init(patt, rhs)
-        }
+    fn from_init(lhs: Patt<Id>, rhs: Expr) -> Result<Dtor, Patt<Id>> {
+        Dtor::from_init_opt(lhs, Some(rhs))
     }
 
     fn from_init_opt(lhs: Patt<Id>, rhs: Option<Expr>) -> Result<Dtor, Patt<Id>> {
@@ -68,34 +140,47 @@ impl DtorExt for Dtor {
             (Patt::Simple(id), rhs) => {
                 Ok(Dtor::Simple(*id.tracking_ref(), id, rhs))
             }
-            (lhs @ Patt::Compound(_), None) => Err(lhs),
             (Patt::Compound(patt), Some(rhs)) => {
                 Ok(Dtor::from_compound_init(patt, rhs))
             }
+            (Patt::Typed(location, patt, ty), rhs) => {
+                // A typed compound pattern still needs an initializer.
+                if rhs.is_none() && !patt.is_simple() {
+                    return Err(Patt::Typed(location, patt, ty));
+                }
+                let lhs = Patt::Typed(location, patt, ty);
+                let location = match rhs {
+                    Some(ref rhs) => span(&lhs, rhs),
+                    None => location
+                };
+                Ok(Dtor::Typed(location, lhs, rhs))
+            }
+            (lhs, _) => Err(lhs)
         }
     }
 }
 
 impl DtorExt for ConstDtor {
     fn from_compound_init(lhs: CompoundPatt<Id>, rhs: Expr) -> ConstDtor {
-        ConstDtor::from_init(Patt::Compound(lhs), rhs)
+        let lhs = Patt::Compound(lhs);
+        ConstDtor { location: span(&lhs, &rhs), patt: lhs, value: rhs }
     }
 
     fn from_simple_init(lhs: Id, rhs: Expr) -> ConstDtor {
-        ConstDtor::from_init(Patt::Simple(lhs), rhs)
+        let lhs = Patt::Simple(lhs);
+        ConstDtor { location: span(&lhs, &rhs), patt: lhs, value: rhs }
     }
 
-    fn from_init(lhs: Patt<Id>, rhs: Expr) -> ConstDtor {
-        ConstDtor {
-            location: span(&lhs, &rhs),
-            patt: lhs,
-            value: rhs
+    fn from_init(lhs: Patt<Id>, rhs: Expr) -> Result<ConstDtor, Patt<Id>> {
+        match lhs {
+            lhs @ Patt::Default(..) => Err(lhs),
+            lhs => Ok(ConstDtor { location: span(&lhs, &rhs), patt: lhs, value: rhs })
         }
     }
 
     fn from_init_opt(lhs: Patt<Id>, rhs: Option<Expr>) -> Result<ConstDtor, Patt<Id>> {
         match rhs {
-            Some(rhs) => Ok(ConstDtor::from_init(lhs, rhs)),
+            Some(rhs) => ConstDtor::from_init(lhs, rhs),
             None => Err(lhs)
         }
     }
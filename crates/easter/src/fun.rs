@@ -3,6 +3,8 @@ use joker::track::*;
 use id::Id;
 use patt::{Patt, RestPatt};
 use stmt::Script;
+use expr::Expr;
+use types::{Type, TypeParam};
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub struct Params {
@@ -11,11 +13,45 @@ pub struct Params {
     pub rest: Option<RestPatt<Id>>
 }
 
+impl Params {
+    // 14.1.12 Static Semantics: IsSimpleParameterList
+    pub fn is_simple(&self) -> bool {
+        self.rest.is_none() && self.list.iter().all(|patt| patt.is_simple())
+    }
+
+    pub fn bound_names(&self) -> Vec<&Id> {
+        self.list.iter()
+            .chain(self.rest.iter().map(|rest| &rest.patt))
+            .flat_map(|patt| patt.bound_names())
+            .collect()
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub struct Fun<Id> {
     pub location: Option<Span>,
     pub id: Id,
     pub params: Params,
     pub body: Script,
-    pub generator: bool
+    pub generator: bool,
+    pub asynchronous: bool,
+    pub type_params: Vec<TypeParam>, // the rest are only set in the parser's types mode
+    pub this_type: Option<Type>,     // the annotation of a "this" parameter
+    pub ret: Option<Type>
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct Arrow {
+    pub location: Option<Span>,
+    pub params: Params,
+    pub body: ArrowBody,
+    pub asynchronous: bool,
+    pub type_params: Vec<TypeParam>, // the rest are only set in the parser's types mode
+    pub ret: Option<Type>
+}
+
+#[derive(Debug, PartialEq, Clone, Untrack)]
+pub enum ArrowBody {
+    Expr(Box<Expr>),
+    Block(Script)
 }
@@ -0,0 +1,107 @@
+use joker::track::*;
+use joker::token::StringLiteral;
+
+use expr::Expr;
+
+// An element or attribute name, which may contain "-".
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut)]
+pub struct JsxId {
+    pub location: Option<Span>,
+    pub name: String
+}
+
+impl Untrack for JsxId {
+    fn untrack(&mut self) { self.location = None; }
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub enum JsxElementName {
+    Id(JsxId),
+    Namespaced(Option<Span>, JsxId, JsxId),
+    Member(Option<Span>, Box<JsxElementName>, JsxId) // the object is an Id or a Member
+}
+
+impl JsxElementName {
+    // Do the opening and closing tags name the same element?
+    pub fn matches(&self, other: &JsxElementName) -> bool {
+        match (self, other) {
+            (&JsxElementName::Id(ref l), &JsxElementName::Id(ref r)) => l.name == r.name,
+            (&JsxElementName::Namespaced(_, ref ns_l, ref l), &JsxElementName::Namespaced(_, ref ns_r, ref r)) => {
+                ns_l.name == ns_r.name && l.name == r.name
+            }
+            (&JsxElementName::Member(_, ref obj_l, ref l), &JsxElementName::Member(_, ref obj_r, ref r)) => {
+                obj_l.matches(obj_r) && l.name == r.name
+            }
+            _ => false
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub enum JsxAttrName {
+    Id(JsxId),
+    Namespaced(Option<Span>, JsxId, JsxId)
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub enum JsxAttr {
+    Attr(Option<Span>, JsxAttrName, Option<JsxAttrValue>), // no value means true
+    Spread(Option<Span>, Expr)
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub enum JsxAttrValue {
+    String(Option<Span>, StringLiteral),
+    Expr(JsxExprContainer),
+    Element(JsxElement),
+    Fragment(JsxFragment)
+}
+
+// A braced expression. The expression is missing for "{}", which may contain
+// comments.
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct JsxExprContainer {
+    pub location: Option<Span>,
+    pub expr: Option<Expr>
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub enum JsxChild {
+    Text(Option<Span>, StringLiteral),
+    Expr(JsxExprContainer),
+    Spread(Option<Span>, Expr),
+    Element(JsxElement),
+    Fragment(JsxFragment)
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct JsxOpeningElement {
+    pub location: Option<Span>,
+    pub name: JsxElementName,
+    pub attrs: Vec<JsxAttr>,
+    pub self_closing: bool
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct JsxClosingElement {
+    pub location: Option<Span>,
+    pub name: JsxElementName
+}
+
+// A self-closing element has no children and no closing element.
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct JsxElement {
+    pub location: Option<Span>,
+    pub opening: JsxOpeningElement,
+    pub children: Vec<JsxChild>,
+    pub closing: Option<JsxClosingElement>
+}
+
+// The spans of "<>" and "</>" are kept for source maps.
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct JsxFragment {
+    pub location: Option<Span>,
+    pub opening: Option<Span>,
+    pub children: Vec<JsxChild>,
+    pub closing: Option<Span>
+}
@@ -5,6 +5,7 @@ extern crate derive;
 
 pub mod id;
 pub mod fun;
+pub mod class;
 pub mod obj;
 pub mod stmt;
 pub mod expr;
@@ -12,3 +13,7 @@ pub mod decl;
 pub mod patt;
 pub mod punc;
 pub mod cover;
+pub mod types;
+pub mod strip;
+#[cfg(feature = "jsx")]
+pub mod jsx;
@@ -1,5 +1,5 @@
 use joker::track::*;
-use joker::token::{StringLiteral, NumberLiteral};
+use joker::token::{StringLiteral, NumberLiteral, BigIntLiteral};
 
 use id::Id;
 use expr::Expr;
@@ -17,23 +17,55 @@ impl Untrack for DotKey {
     fn untrack(&mut self) { self.location = None; }
 }
 
+// A private name, without the leading "#".
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut)]
+pub struct PrivateKey {
+    pub location: Option<Span>,
+    pub value: String
+}
+
+impl Untrack for PrivateKey {
+    fn untrack(&mut self) { self.location = None; }
+}
+
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub enum Prop {
     Regular(Option<Span>, PropKey, PropVal),
     Method(Fun<PropKey>),
-    Shorthand(Id)
+    Shorthand(Id),
+    // CoverInitializedName: only valid when the object literal is
+    // reinterpreted as an assignment pattern.
+    CoverInit(Option<Span>, Id, Expr),
+    Spread(Option<Span>, Expr)
 }
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut)]
 pub enum PropKey {
     Id(Option<Span>, String),
     String(Option<Span>, StringLiteral),
-    Number(Option<Span>, NumberLiteral)
+    Number(Option<Span>, NumberLiteral),
+    BigInt(Option<Span>, BigIntLiteral),
+    Computed(Option<Span>, Box<Expr>),
+    Private(PrivateKey) // only in classes
+}
+
+impl PropKey {
+    // Is this key statically known to be the given property name?
+    pub fn is_name(&self, name: &str) -> bool {
+        match *self {
+            PropKey::Id(_, ref id) => id == name,
+            PropKey::String(_, ref lit) => lit.value == name,
+            _ => false
+        }
+    }
 }
 
 impl Untrack for PropKey {
     fn untrack(&mut self) {
         *self.tracking_mut() = None;
+        if let PropKey::Computed(_, ref mut expr) = *self {
+            expr.untrack();
+        }
     }
 }
 
@@ -112,6 +112,7 @@ pub enum BinopTag {
     Times,
     Div,
     Mod,
+    Exp,
     BitOr,
     BitXor,
     BitAnd,
@@ -140,6 +141,7 @@ impl FromStr for BinopTag {
             "*"          => BinopTag::Times,
             "/"          => BinopTag::Div,
             "%"          => BinopTag::Mod,
+            "**"         => BinopTag::Exp,
             "|"          => BinopTag::BitOr,
             "^"          => BinopTag::BitXor,
             "&"          => BinopTag::BitAnd,
@@ -169,6 +171,7 @@ impl Precedence for BinopTag {
             BinopTag::Times      => 11,
             BinopTag::Div        => 11,
             BinopTag::Mod        => 11,
+            BinopTag::Exp        => 12,
             BinopTag::BitOr      => 4,
             BinopTag::BitXor     => 5,
             BinopTag::BitAnd     => 6,
@@ -199,6 +202,7 @@ impl Display for BinopTag {
             BinopTag::Times      => "*",
             BinopTag::Div        => "/",
             BinopTag::Mod        => "%",
+            BinopTag::Exp        => "**",
             BinopTag::BitOr      => "|",
             BinopTag::BitXor     => "^",
             BinopTag::BitAnd     => "&",
@@ -211,7 +215,8 @@ impl Display for BinopTag {
 #[derive(Debug, Eq, PartialEq, Clone)]
 pub enum LogopTag {
     Or,
-    And
+    And,
+    Coalesce
 }
 
 impl FromStr for LogopTag {
@@ -221,6 +226,7 @@ impl FromStr for LogopTag {
         Ok(match s {
             "||" => LogopTag::Or,
             "&&" => LogopTag::And,
+            "??" => LogopTag::Coalesce,
             _    => { return Err(()); }
         })
     }
@@ -229,8 +235,9 @@ impl FromStr for LogopTag {
 impl Precedence for LogopTag {
     fn precedence(&self) -> u32 {
         match *self {
-            LogopTag::Or  => 2,
-            LogopTag::And => 3
+            LogopTag::Coalesce => 1,
+            LogopTag::Or       => 2,
+            LogopTag::And      => 3
         }
     }
 }
@@ -240,8 +247,9 @@ pub type Logop = Op<LogopTag>;
 impl Display for LogopTag {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         fmt.write_str(match *self {
-            LogopTag::Or  => "||",
-            LogopTag::And => "&&"
+            LogopTag::Or       => "||",
+            LogopTag::And      => "&&",
+            LogopTag::Coalesce => "??"
         })
     }
 }
@@ -258,7 +266,11 @@ pub enum AssopTag {
     URShiftEq,
     BitOrEq,
     BitXorEq,
-    BitAndEq
+    BitAndEq,
+    ExpEq,
+    AndEq,
+    OrEq,
+    CoalesceEq
 }
 
 impl FromStr for AssopTag {
@@ -277,6 +289,10 @@ impl FromStr for AssopTag {
             "|="   => AssopTag::BitOrEq,
             "^="   => AssopTag::BitXorEq,
             "&="   => AssopTag::BitAndEq,
+            "**="  => AssopTag::ExpEq,
+            "&&="  => AssopTag::AndEq,
+            "||="  => AssopTag::OrEq,
+            "??="  => AssopTag::CoalesceEq,
             _      => { return Err(()); }
         })
     }
@@ -291,17 +307,21 @@ pub type Assop = Op<AssopTag>;
 impl Display for AssopTag {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         fmt.write_str(match *self {
-            AssopTag::PlusEq    => "+=",
-            AssopTag::MinusEq   => "-=",
-            AssopTag::TimesEq   => "*=",
-            AssopTag::DivEq     => "/=",
-            AssopTag::ModEq     => "%=",
-            AssopTag::LShiftEq  => "<<=",
-            AssopTag::RShiftEq  => ">>=",
-            AssopTag::URShiftEq => ">>>=",
-            AssopTag::BitOrEq   => "|=",
-            AssopTag::BitXorEq  => "^=",
-            AssopTag::BitAndEq  => "&="
+            AssopTag::PlusEq     => "+=",
+            AssopTag::MinusEq    => "-=",
+            AssopTag::TimesEq    => "*=",
+            AssopTag::DivEq      => "/=",
+            AssopTag::ModEq      => "%=",
+            AssopTag::LShiftEq   => "<<=",
+            AssopTag::RShiftEq   => ">>=",
+            AssopTag::URShiftEq  => ">>>=",
+            AssopTag::BitOrEq    => "|=",
+            AssopTag::BitXorEq   => "^=",
+            AssopTag::BitAndEq   => "&=",
+            AssopTag::ExpEq      => "**=",
+            AssopTag::AndEq      => "&&=",
+            AssopTag::OrEq       => "||=",
+            AssopTag::CoalesceEq => "??="
         })
     }
 }
@@ -320,6 +340,7 @@ impl ToOp for Token {
                 TokenData::Star                               => BinopTag::Times,
                 TokenData::Slash                              => BinopTag::Div,
                 TokenData::Mod                                => BinopTag::Mod,
+                TokenData::StarStar                           => BinopTag::Exp,
                 TokenData::Plus                               => BinopTag::Plus,
                 TokenData::Minus                              => BinopTag::Minus,
                 TokenData::LShift                             => BinopTag::LShift,
@@ -349,6 +370,7 @@ impl ToOp for Token {
             tag: match self.value {
                 TokenData::LogicalAnd => LogopTag::And,
                 TokenData::LogicalOr  => LogopTag::Or,
+                TokenData::Coalesce   => LogopTag::Coalesce,
                 _ => { return None; }
             }
         })
@@ -358,17 +380,21 @@ impl ToOp for Token {
         Some(Op {
             location: Some(self.location),
             tag: match self.value {
-                TokenData::PlusAssign    => AssopTag::PlusEq,
-                TokenData::MinusAssign   => AssopTag::MinusEq,
-                TokenData::StarAssign    => AssopTag::TimesEq,
-                TokenData::SlashAssign   => AssopTag::DivEq,
-                TokenData::ModAssign     => AssopTag::ModEq,
-                TokenData::LShiftAssign  => AssopTag::LShiftEq,
-                TokenData::RShiftAssign  => AssopTag::RShiftEq,
-                TokenData::URShiftAssign => AssopTag::URShiftEq,
-                TokenData::BitAndAssign  => AssopTag::BitAndEq,
-                TokenData::BitOrAssign   => AssopTag::BitOrEq,
-                TokenData::BitXorAssign  => AssopTag::BitXorEq,
+                TokenData::PlusAssign       => AssopTag::PlusEq,
+                TokenData::MinusAssign      => AssopTag::MinusEq,
+                TokenData::StarAssign       => AssopTag::TimesEq,
+                TokenData::SlashAssign      => AssopTag::DivEq,
+                TokenData::ModAssign        => AssopTag::ModEq,
+                TokenData::LShiftAssign     => AssopTag::LShiftEq,
+                TokenData::RShiftAssign     => AssopTag::RShiftEq,
+                TokenData::URShiftAssign    => AssopTag::URShiftEq,
+                TokenData::BitAndAssign     => AssopTag::BitAndEq,
+                TokenData::BitOrAssign      => AssopTag::BitOrEq,
+                TokenData::BitXorAssign     => AssopTag::BitXorEq,
+                TokenData::StarStarAssign   => AssopTag::ExpEq,
+                TokenData::LogicalAndAssign => AssopTag::AndEq,
+                TokenData::LogicalOrAssign  => AssopTag::OrEq,
+                TokenData::CoalesceAssign   => AssopTag::CoalesceEq,
                 _ => { return None; }
             }
         })
@@ -6,6 +6,7 @@ use expr::Expr;
 use decl::{Decl, Dtor, ConstDtor, Import, Export};
 use patt::{Patt, AssignTarget};
 use punc::Semi;
+use fun::Fun;
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub enum Stmt {
@@ -27,16 +28,30 @@ pub enum Stmt {
     For(Option<Span>, Option<Box<ForHead>>, Option<Expr>, Option<Expr>, Box<Stmt>),
     ForIn(Option<Span>, Box<ForInHead>, Expr, Box<Stmt>),
     ForOf(Option<Span>, Box<ForOfHead>, Expr, Box<Stmt>),
-    Debugger(Option<Span>, Semi)
+    ForAwait(Option<Span>, Box<ForOfHead>, Expr, Box<Stmt>),
+    Debugger(Option<Span>, Semi),
+    Fun(Fun<Id>) // Annex B: an if clause or labelled function declaration
 }
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub struct Body<Item> {
     pub location: Option<Span>,
+    pub hashbang: Option<Hashbang>, // only at the start of a script or module
     pub dirs: Vec<Dir>,
     pub items: Vec<Item>
 }
 
+// The "#!" line at the start of a script or module, without the "#!".
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut)]
+pub struct Hashbang {
+    pub location: Option<Span>,
+    pub value: String
+}
+
+impl Untrack for Hashbang {
+    fn untrack(&mut self) { self.location = None; }
+}
+
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub struct Block {
     pub location: Option<Span>,
@@ -108,7 +123,7 @@ pub enum ForOfHead {
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub struct Catch {
     pub location: Option<Span>,
-    pub param: Patt<Id>,
+    pub param: Option<Patt<Id>>,
     pub body: Block
 }
 
@@ -127,9 +142,11 @@ pub struct Dir {
 }
 
 impl Dir {
+    // The directive's source text without the quotes, so that an escaped
+    // "use strict" isn't mistaken for the real thing.
     pub fn pragma(&self) -> &str {
         if let Some(ref source) = self.string.source {
-            source
+            &source[1..source.len() - 1]
         } else {
             &self.string.value
         }
@@ -0,0 +1,503 @@
+use id::Id;
+use expr::{Expr, ExprListItem, Template};
+use stmt::{Stmt, Block, StmtListItem, ForHead, ForInHead, ForOfHead, Catch, Case, Script, Module, ModItem};
+use decl::{Decl, Dtor, ConstDtor, Export};
+use patt::{Patt, CompoundPatt, PropPatt, RestPatt, AssignTarget};
+use fun::{Fun, Params, Arrow, ArrowBody};
+use class::{Class, ClassMember, Decorator};
+use obj::{Prop, PropKey, PropVal};
+#[cfg(feature = "jsx")]
+use jsx::{JsxElement, JsxOpeningElement, JsxAttr, JsxAttrValue, JsxExprContainer, JsxChild, JsxFragment};
+
+// Removes the type annotations, interfaces and type aliases of the parser's
+// types mode, leaving a plain JavaScript tree. Typed declarators become
+// ordinary declarators, and the spans of the remaining nodes are kept.
+pub trait StripTypes {
+    fn strip_types(self) -> Self;
+}
+
+impl<T: StripTypes> StripTypes for Box<T> {
+    fn strip_types(self) -> Box<T> {
+        Box::new((*self).strip_types())
+    }
+}
+
+impl<T: StripTypes> StripTypes for Option<T> {
+    fn strip_types(self) -> Option<T> {
+        self.map(StripTypes::strip_types)
+    }
+}
+
+impl<T: StripTypes> StripTypes for Vec<T> {
+    fn strip_types(self) -> Vec<T> {
+        self.into_iter().map(StripTypes::strip_types).collect()
+    }
+}
+
+impl StripTypes for Id {
+    fn strip_types(self) -> Id { self }
+}
+
+fn is_type_decl(decl: &Decl) -> bool {
+    match *decl {
+        Decl::Interface(_) | Decl::TypeAlias(_) => true,
+        _ => false
+    }
+}
+
+fn strip_items(items: Vec<StmtListItem>) -> Vec<StmtListItem> {
+    items.into_iter().filter_map(|item| match item {
+        StmtListItem::Decl(ref decl) if is_type_decl(decl) => None,
+        item => Some(item.strip_types())
+    }).collect()
+}
+
+impl StripTypes for Script {
+    fn strip_types(self) -> Script {
+        Script { items: strip_items(self.items), ..self }
+    }
+}
+
+impl StripTypes for Module {
+    fn strip_types(self) -> Module {
+        let items = self.items.into_iter().filter_map(|item| match item {
+            ModItem::StmtListItem(StmtListItem::Decl(ref decl))
+          | ModItem::Export(Export::Decl(_, ref decl)) if is_type_decl(decl) => None,
+            ModItem::StmtListItem(item) => Some(ModItem::StmtListItem(item.strip_types())),
+            ModItem::Export(export) => Some(ModItem::Export(export.strip_types())),
+            item => Some(item)
+        }).collect();
+        Module { items: items, ..self }
+    }
+}
+
+impl StripTypes for StmtListItem {
+    fn strip_types(self) -> StmtListItem {
+        match self {
+            StmtListItem::Decl(decl) => StmtListItem::Decl(decl.strip_types()),
+            StmtListItem::Stmt(stmt) => StmtListItem::Stmt(stmt.strip_types())
+        }
+    }
+}
+
+impl StripTypes for Block {
+    fn strip_types(self) -> Block {
+        Block { location: self.location, items: strip_items(self.items) }
+    }
+}
+
+impl StripTypes for Stmt {
+    fn strip_types(self) -> Stmt {
+        match self {
+            Stmt::Block(block) => Stmt::Block(block.strip_types()),
+            Stmt::Var(location, dtors, semi) => Stmt::Var(location, dtors.strip_types(), semi),
+            Stmt::Expr(location, expr, semi) => Stmt::Expr(location, expr.strip_types(), semi),
+            Stmt::If(location, test, cons, alt) => {
+                Stmt::If(location, test.strip_types(), cons.strip_types(), alt.strip_types())
+            }
+            Stmt::Label(location, id, body) => Stmt::Label(location, id, body.strip_types()),
+            Stmt::With(location, obj, body) => Stmt::With(location, obj.strip_types(), body.strip_types()),
+            Stmt::Switch(location, disc, cases) => Stmt::Switch(location, disc.strip_types(), cases.strip_types()),
+            Stmt::Return(location, arg, semi) => Stmt::Return(location, arg.strip_types(), semi),
+            Stmt::Throw(location, arg, semi) => Stmt::Throw(location, arg.strip_types(), semi),
+            Stmt::Try(location, body, catch, finally) => {
+                Stmt::Try(location, body.strip_types(), catch.strip_types(), finally.strip_types())
+            }
+            Stmt::While(location, test, body) => Stmt::While(location, test.strip_types(), body.strip_types()),
+            Stmt::DoWhile(location, body, test, semi) => Stmt::DoWhile(location, body.strip_types(), test.strip_types(), semi),
+            Stmt::For(location, head, test, update, body) => {
+                Stmt::For(location, head.strip_types(), test.strip_types(), update.strip_types(), body.strip_types())
+            }
+            Stmt::ForIn(location, head, obj, body) => {
+                Stmt::ForIn(location, head.strip_types(), obj.strip_types(), body.strip_types())
+            }
+            Stmt::ForOf(location, head, iter, body) => {
+                Stmt::ForOf(location, head.strip_types(), iter.strip_types(), body.strip_types())
+            }
+            Stmt::ForAwait(location, head, iter, body) => {
+                Stmt::ForAwait(location, head.strip_types(), iter.strip_types(), body.strip_types())
+            }
+            Stmt::Fun(fun) => Stmt::Fun(fun.strip_types()),
+            stmt @ Stmt::Empty(_)
+          | stmt @ Stmt::Break(..)
+          | stmt @ Stmt::Cont(..)
+          | stmt @ Stmt::Debugger(..) => stmt
+        }
+    }
+}
+
+impl StripTypes for Case {
+    fn strip_types(self) -> Case {
+        Case { location: self.location, test: self.test.strip_types(), body: strip_items(self.body) }
+    }
+}
+
+impl StripTypes for Catch {
+    fn strip_types(self) -> Catch {
+        Catch { location: self.location, param: self.param.strip_types(), body: self.body.strip_types() }
+    }
+}
+
+impl StripTypes for ForHead {
+    fn strip_types(self) -> ForHead {
+        match self {
+            ForHead::Var(location, dtors) => ForHead::Var(location, dtors.strip_types()),
+            ForHead::Let(location, dtors) => ForHead::Let(location, dtors.strip_types()),
+            ForHead::Const(location, dtors) => ForHead::Const(location, dtors.strip_types()),
+            ForHead::Expr(location, expr) => ForHead::Expr(location, expr.strip_types())
+        }
+    }
+}
+
+impl StripTypes for ForInHead {
+    fn strip_types(self) -> ForInHead {
+        match self {
+            ForInHead::VarInit(location, id, init) => ForInHead::VarInit(location, id, init.strip_types()),
+            ForInHead::Var(location, patt) => ForInHead::Var(location, patt.strip_types()),
+            ForInHead::Let(location, patt) => ForInHead::Let(location, patt.strip_types()),
+            ForInHead::Const(location, patt) => ForInHead::Const(location, patt.strip_types()),
+            ForInHead::Patt(patt) => ForInHead::Patt(patt.strip_types())
+        }
+    }
+}
+
+impl StripTypes for ForOfHead {
+    fn strip_types(self) -> ForOfHead {
+        match self {
+            ForOfHead::Var(location, patt) => ForOfHead::Var(location, patt.strip_types()),
+            ForOfHead::Let(location, patt) => ForOfHead::Let(location, patt.strip_types()),
+            ForOfHead::Const(location, patt) => ForOfHead::Const(location, patt.strip_types()),
+            ForOfHead::Patt(patt) => ForOfHead::Patt(patt.strip_types())
+        }
+    }
+}
+
+impl StripTypes for Export {
+    fn strip_types(self) -> Export {
+        match self {
+            Export::Var(location, dtors, semi) => Export::Var(location, dtors.strip_types(), semi),
+            Export::Decl(location, decl) => Export::Decl(location, decl.strip_types()),
+            Export::DefaultExpr(location, expr, semi) => Export::DefaultExpr(location, expr.strip_types(), semi),
+            Export::DefaultFun(location, fun) => Export::DefaultFun(location, fun.strip_types()),
+            Export::DefaultClass(location, class) => Export::DefaultClass(location, class.strip_types()),
+            export => export
+        }
+    }
+}
+
+impl StripTypes for Decl {
+    fn strip_types(self) -> Decl {
+        match self {
+            Decl::Fun(fun) => Decl::Fun(fun.strip_types()),
+            Decl::Class(class) => Decl::Class(class.strip_types()),
+            Decl::Let(location, dtors, semi) => Decl::Let(location, dtors.strip_types(), semi),
+            Decl::Const(location, dtors, semi) => Decl::Const(location, dtors.strip_types(), semi),
+            // These are dropped by the enclosing statement list.
+            decl @ Decl::Interface(_)
+          | decl @ Decl::TypeAlias(_) => decl
+        }
+    }
+}
+
+impl StripTypes for Dtor {
+    fn strip_types(self) -> Dtor {
+        match self {
+            Dtor::Simple(location, id, init) => Dtor::Simple(location, id, init.strip_types()),
+            Dtor::Compound(location, patt, init) => Dtor::Compound(location, patt.strip_types(), init.strip_types()),
+            Dtor::Typed(location, patt, init) => {
+                match (patt.strip_types(), init.strip_types()) {
+                    (Patt::Simple(id), init) => Dtor::Simple(location, id, init),
+                    (Patt::Compound(patt), Some(init)) => Dtor::Compound(location, patt, init),
+                    _ => unreachable!("ill-formed typed declarator")
+                }
+            }
+        }
+    }
+}
+
+impl StripTypes for ConstDtor {
+    fn strip_types(self) -> ConstDtor {
+        ConstDtor { location: self.location, patt: self.patt.strip_types(), value: self.value.strip_types() }
+    }
+}
+
+impl<T: StripTypes> StripTypes for Patt<T> {
+    fn strip_types(self) -> Patt<T> {
+        match self {
+            Patt::Simple(simple) => Patt::Simple(simple.strip_types()),
+            Patt::Compound(patt) => Patt::Compound(patt.strip_types()),
+            Patt::Default(location, patt, init) => Patt::Default(location, patt.strip_types(), init.strip_types()),
+            Patt::Typed(_, patt, _)
+          | Patt::Optional(_, patt) => (*patt).strip_types()
+        }
+    }
+}
+
+impl<T: StripTypes> StripTypes for CompoundPatt<T> {
+    fn strip_types(self) -> CompoundPatt<T> {
+        match self {
+            CompoundPatt::Arr(location, elts, rest) => CompoundPatt::Arr(location, elts.strip_types(), rest.strip_types()),
+            CompoundPatt::Obj(location, props, rest) => CompoundPatt::Obj(location, props.strip_types(), rest.strip_types())
+        }
+    }
+}
+
+impl<T: StripTypes> StripTypes for PropPatt<T> {
+    fn strip_types(self) -> PropPatt<T> {
+        match self {
+            PropPatt::Regular(location, key, patt) => PropPatt::Regular(location, key.strip_types(), patt.strip_types()),
+            PropPatt::Shorthand(id) => PropPatt::Shorthand(id),
+            PropPatt::ShorthandDefault(location, id, init) => PropPatt::ShorthandDefault(location, id, init.strip_types())
+        }
+    }
+}
+
+impl<T: StripTypes> StripTypes for RestPatt<T> {
+    fn strip_types(self) -> RestPatt<T> {
+        RestPatt { location: self.location, patt: self.patt.strip_types() }
+    }
+}
+
+impl StripTypes for AssignTarget {
+    fn strip_types(self) -> AssignTarget {
+        match self {
+            AssignTarget::Id(id) => AssignTarget::Id(id),
+            AssignTarget::Dot(location, obj, key) => AssignTarget::Dot(location, obj.strip_types(), key),
+            AssignTarget::PrivateDot(location, obj, key) => AssignTarget::PrivateDot(location, obj.strip_types(), key),
+            AssignTarget::Brack(location, obj, prop) => AssignTarget::Brack(location, obj.strip_types(), prop.strip_types())
+        }
+    }
+}
+
+impl<T: StripTypes> StripTypes for Fun<T> {
+    fn strip_types(self) -> Fun<T> {
+        Fun {
+            id: self.id.strip_types(),
+            params: self.params.strip_types(),
+            body: self.body.strip_types(),
+            type_params: Vec::new(),
+            this_type: None,
+            ret: None,
+            ..self
+        }
+    }
+}
+
+impl StripTypes for Params {
+    fn strip_types(self) -> Params {
+        Params { location: self.location, list: self.list.strip_types(), rest: self.rest.strip_types() }
+    }
+}
+
+impl StripTypes for Arrow {
+    fn strip_types(self) -> Arrow {
+        let body = match self.body {
+            ArrowBody::Expr(expr) => ArrowBody::Expr(expr.strip_types()),
+            ArrowBody::Block(body) => ArrowBody::Block(body.strip_types())
+        };
+        Arrow {
+            location: self.location,
+            params: self.params.strip_types(),
+            body: body,
+            asynchronous: self.asynchronous,
+            type_params: Vec::new(),
+            ret: None
+        }
+    }
+}
+
+impl<T: StripTypes> StripTypes for Class<T> {
+    fn strip_types(self) -> Class<T> {
+        Class {
+            location: self.location,
+            decorators: self.decorators.strip_types(),
+            id: self.id.strip_types(),
+            type_params: Vec::new(),
+            extends: self.extends.strip_types(),
+            body: self.body.strip_types()
+        }
+    }
+}
+
+impl StripTypes for ClassMember {
+    fn strip_types(self) -> ClassMember {
+        match self {
+            ClassMember::Constructor(fun) => ClassMember::Constructor(fun.strip_types()),
+            ClassMember::Method(location, is_static, fun) => ClassMember::Method(location, is_static, fun.strip_types()),
+            ClassMember::Get(location, is_static, key, body) => {
+                ClassMember::Get(location, is_static, key.strip_types(), body.strip_types())
+            }
+            ClassMember::Set(location, is_static, key, param, body) => {
+                ClassMember::Set(location, is_static, key.strip_types(), param.strip_types(), body.strip_types())
+            }
+            ClassMember::Field(location, is_static, key, init) => {
+                ClassMember::Field(location, is_static, key.strip_types(), init.strip_types())
+            }
+            ClassMember::StaticBlock(block) => ClassMember::StaticBlock(block.strip_types()),
+            ClassMember::Decorated(location, decorators, member) => {
+                ClassMember::Decorated(location, decorators.strip_types(), member.strip_types())
+            }
+        }
+    }
+}
+
+impl StripTypes for Decorator {
+    fn strip_types(self) -> Decorator {
+        Decorator { location: self.location, expr: self.expr.strip_types() }
+    }
+}
+
+impl StripTypes for PropKey {
+    fn strip_types(self) -> PropKey {
+        match self {
+            PropKey::Computed(location, expr) => PropKey::Computed(location, expr.strip_types()),
+            key => key
+        }
+    }
+}
+
+impl StripTypes for Prop {
+    fn strip_types(self) -> Prop {
+        match self {
+            Prop::Regular(location, key, val) => Prop::Regular(location, key.strip_types(), val.strip_types()),
+            Prop::Method(fun) => Prop::Method(fun.strip_types()),
+            Prop::Shorthand(id) => Prop::Shorthand(id),
+            Prop::CoverInit(location, id, init) => Prop::CoverInit(location, id, init.strip_types()),
+            Prop::Spread(location, expr) => Prop::Spread(location, expr.strip_types())
+        }
+    }
+}
+
+impl StripTypes for PropVal {
+    fn strip_types(self) -> PropVal {
+        match self {
+            PropVal::Init(expr) => PropVal::Init(expr.strip_types()),
+            PropVal::Get(location, body) => PropVal::Get(location, body.strip_types()),
+            PropVal::Set(location, param, body) => PropVal::Set(location, param.strip_types(), body.strip_types())
+        }
+    }
+}
+
+impl StripTypes for ExprListItem {
+    fn strip_types(self) -> ExprListItem {
+        match self {
+            ExprListItem::Expr(expr) => ExprListItem::Expr(expr.strip_types()),
+            ExprListItem::Spread(location, expr) => ExprListItem::Spread(location, expr.strip_types())
+        }
+    }
+}
+
+impl StripTypes for Template {
+    fn strip_types(self) -> Template {
+        Template { location: self.location, strings: self.strings, exprs: self.exprs.strip_types() }
+    }
+}
+
+impl StripTypes for Expr {
+    fn strip_types(self) -> Expr {
+        match self {
+            Expr::Arr(location, elts) => Expr::Arr(location, elts.strip_types()),
+            Expr::Obj(location, props) => Expr::Obj(location, props.strip_types()),
+            Expr::Fun(fun) => Expr::Fun(fun.strip_types()),
+            Expr::Class(class) => Expr::Class(class.strip_types()),
+            Expr::Arrow(arrow) => Expr::Arrow(arrow.strip_types()),
+            Expr::Seq(location, exprs) => Expr::Seq(location, exprs.strip_types()),
+            Expr::Unop(location, op, arg) => Expr::Unop(location, op, arg.strip_types()),
+            Expr::Binop(location, op, left, right) => Expr::Binop(location, op, left.strip_types(), right.strip_types()),
+            Expr::Logop(location, op, left, right) => Expr::Logop(location, op, left.strip_types(), right.strip_types()),
+            Expr::PrivateIn(location, key, obj) => Expr::PrivateIn(location, key, obj.strip_types()),
+            Expr::PreInc(location, arg) => Expr::PreInc(location, arg.strip_types()),
+            Expr::PostInc(location, arg) => Expr::PostInc(location, arg.strip_types()),
+            Expr::PreDec(location, arg) => Expr::PreDec(location, arg.strip_types()),
+            Expr::PostDec(location, arg) => Expr::PostDec(location, arg.strip_types()),
+            Expr::Assign(location, patt, right) => Expr::Assign(location, patt.strip_types(), right.strip_types()),
+            Expr::BinAssign(location, op, left, right) => Expr::BinAssign(location, op, left.strip_types(), right.strip_types()),
+            Expr::Cond(location, test, cons, alt) => Expr::Cond(location, test.strip_types(), cons.strip_types(), alt.strip_types()),
+            Expr::Yield(location, arg) => Expr::Yield(location, arg.strip_types()),
+            Expr::YieldDelegate(location, arg) => Expr::YieldDelegate(location, arg.strip_types()),
+            Expr::Await(location, arg) => Expr::Await(location, arg.strip_types()),
+            Expr::Call(location, callee, args) => Expr::Call(location, callee.strip_types(), args.strip_types()),
+            Expr::New(location, ctor, args) => Expr::New(location, ctor.strip_types(), args.strip_types()),
+            Expr::Dot(location, obj, key) => Expr::Dot(location, obj.strip_types(), key),
+            Expr::PrivateDot(location, obj, key) => Expr::PrivateDot(location, obj.strip_types(), key),
+            Expr::Brack(location, obj, prop) => Expr::Brack(location, obj.strip_types(), prop.strip_types()),
+            Expr::Chain(location, expr) => Expr::Chain(location, expr.strip_types()),
+            Expr::OptionalCall(location, callee, args) => Expr::OptionalCall(location, callee.strip_types(), args.strip_types()),
+            Expr::OptionalDot(location, obj, key) => Expr::OptionalDot(location, obj.strip_types(), key),
+            Expr::OptionalPrivateDot(location, obj, key) => Expr::OptionalPrivateDot(location, obj.strip_types(), key),
+            Expr::OptionalBrack(location, obj, prop) => Expr::OptionalBrack(location, obj.strip_types(), prop.strip_types()),
+            Expr::Import(location, arg) => Expr::Import(location, arg.strip_types()),
+            Expr::Template(template) => Expr::Template(template.strip_types()),
+            Expr::TaggedTemplate(location, tag, template) => Expr::TaggedTemplate(location, tag.strip_types(), template.strip_types()),
+            Expr::As(_, expr, _)
+          | Expr::TypeArgs(_, expr, _) => (*expr).strip_types(),
+            #[cfg(feature = "jsx")]
+            Expr::JsxElement(elt) => Expr::JsxElement(elt.strip_types()),
+            #[cfg(feature = "jsx")]
+            Expr::JsxFragment(frag) => Expr::JsxFragment(frag.strip_types()),
+            expr => expr
+        }
+    }
+}
+
+#[cfg(feature = "jsx")]
+impl StripTypes for JsxElement {
+    fn strip_types(self) -> JsxElement {
+        let opening = JsxOpeningElement { attrs: self.opening.attrs.strip_types(), ..self.opening };
+        JsxElement {
+            location: self.location,
+            opening: opening,
+            children: self.children.strip_types(),
+            closing: self.closing
+        }
+    }
+}
+
+#[cfg(feature = "jsx")]
+impl StripTypes for JsxFragment {
+    fn strip_types(self) -> JsxFragment {
+        JsxFragment { children: self.children.strip_types(), ..self }
+    }
+}
+
+#[cfg(feature = "jsx")]
+impl StripTypes for JsxAttr {
+    fn strip_types(self) -> JsxAttr {
+        match self {
+            JsxAttr::Attr(location, name, value) => JsxAttr::Attr(location, name, value.strip_types()),
+            JsxAttr::Spread(location, expr) => JsxAttr::Spread(location, expr.strip_types())
+        }
+    }
+}
+
+#[cfg(feature = "jsx")]
+impl StripTypes for JsxAttrValue {
+    fn strip_types(self) -> JsxAttrValue {
+        match self {
+            JsxAttrValue::Expr(container) => JsxAttrValue::Expr(container.strip_types()),
+            JsxAttrValue::Element(elt) => JsxAttrValue::Element(elt.strip_types()),
+            JsxAttrValue::Fragment(frag) => JsxAttrValue::Fragment(frag.strip_types()),
+            value => value
+        }
+    }
+}
+
+#[cfg(feature = "jsx")]
+impl StripTypes for JsxExprContainer {
+    fn strip_types(self) -> JsxExprContainer {
+        JsxExprContainer { location: self.location, expr: self.expr.strip_types() }
+    }
+}
+
+#[cfg(feature = "jsx")]
+impl StripTypes for JsxChild {
+    fn strip_types(self) -> JsxChild {
+        match self {
+            JsxChild::Expr(container) => JsxChild::Expr(container.strip_types()),
+            JsxChild::Spread(location, expr) => JsxChild::Spread(location, expr.strip_types()),
+            JsxChild::Element(elt) => JsxChild::Element(elt.strip_types()),
+            JsxChild::Fragment(frag) => JsxChild::Fragment(frag.strip_types()),
+            child => child
+        }
+    }
+}
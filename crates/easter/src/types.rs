@@ -0,0 +1,88 @@
+use joker::track::*;
+use joker::token::{StringLiteral, NumberLiteral};
+
+use id::Id;
+use obj::{DotKey, PropKey};
+use punc::Semi;
+
+// Type annotations are only produced by the parser's types mode. Keyword
+// types like "number" and "any" are plain names.
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub enum Type {
+    Ref(Option<Span>, TypeName, Vec<Type>), // the type arguments, if any
+    Query(Option<Span>, TypeName),          // "typeof" TypeName
+    Void(Option<Span>),
+    Null(Option<Span>),
+    This(Option<Span>),
+    True(Option<Span>),
+    False(Option<Span>),
+    String(Option<Span>, StringLiteral),
+    Number(Option<Span>, NumberLiteral),
+    Arr(Option<Span>, Box<Type>),
+    Tuple(Option<Span>, Vec<Type>),
+    Obj(Option<Span>, Vec<TypeMember>),
+    Fun(FunType),
+    Union(Option<Span>, Vec<Type>),
+    Intersection(Option<Span>, Vec<Type>),
+    Const(Option<Span>) // only as the type of an "as" expression
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub enum TypeName {
+    Id(Id),
+    Qualified(Option<Span>, Box<TypeName>, DotKey)
+}
+
+// A type parameter of a generic function, interface or type alias.
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct TypeParam {
+    pub location: Option<Span>,
+    pub id: Id,
+    pub bound: Option<Type>,  // "extends" Type
+    pub default: Option<Type> // "=" Type
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct FunType {
+    pub location: Option<Span>,
+    pub type_params: Vec<TypeParam>,
+    pub params: Vec<FunTypeParam>,
+    pub rest: Option<Box<FunTypeParam>>,
+    pub ret: Box<Type>
+}
+
+// A parameter without an annotation has type "any".
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct FunTypeParam {
+    pub location: Option<Span>,
+    pub id: Id,
+    pub optional: bool,
+    pub annotation: Option<Type>
+}
+
+// The bool flags in Prop and Method indicate optional members.
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub enum TypeMember {
+    Prop(Option<Span>, PropKey, bool, Type),
+    Method(Option<Span>, PropKey, bool, FunType),
+    Index(Option<Span>, Id, Type, Type), // "[" Id ":" Type "]" ":" Type
+    Call(FunType)
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct Interface {
+    pub location: Option<Span>,
+    pub id: Id,
+    pub type_params: Vec<TypeParam>,
+    pub extends: Vec<Type>,
+    pub body: Vec<TypeMember>
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct TypeAlias {
+    pub location: Option<Span>,
+    pub id: Id,
+    pub type_params: Vec<TypeParam>,
+    pub ty: Type,
+    pub semi: Semi
+}
@@ -0,0 +1,88 @@
+use easter::class::{Class, ClassMember};
+use easter::obj::{PropKey, PrivateKey};
+use easter::stmt::Block;
+use unjson::ty::Object;
+use unjson::{ExtractField, Unjson};
+
+use tag::{Tag, TagOf};
+use result::{Result, Map};
+use error::{Error, string_error, array_error, node_type_error};
+use node::ExtractNode;
+use obj::IntoObj;
+use fun::IntoFun;
+
+pub trait IntoClass<Id> {
+    fn into_class(self, Id) -> Result<Class<Id>>;
+}
+
+impl<Id> IntoClass<Id> for Object {
+    fn into_class(mut self, id: Id) -> Result<Class<Id>> {
+        let extends = self.extract_expr_opt("superClass")?.map(Box::new);
+        let list = self.extract_object("body")?.extract_array("body")?;
+        let objs = list.map(|v| v.into_object().map_err(Error::Json))?;
+        Ok(Class {
+            location: None,
+            decorators: Vec::new(),
+            id: id,
+            type_params: Vec::new(),
+            extends: extends,
+            body: objs.map(|o| o.into_class_member())?
+        })
+    }
+}
+
+trait IntoClassMember {
+    fn into_class_member(self) -> Result<ClassMember>;
+}
+
+impl IntoClassMember for Object {
+    fn into_class_member(mut self) -> Result<ClassMember> {
+        let tag = self.tag()?;
+        if tag == Tag::StaticBlock {
+            return Ok(ClassMember::StaticBlock(Block {
+                location: None,
+                items: self.extract_stmt_list("body")?
+            }));
+        }
+        let computed = self.extract_bool("computed")?;
+        let key = self.extract_object("key")?.into_class_element_name(computed)?;
+        let is_static = self.extract_bool("static")?;
+        match tag {
+            Tag::MethodDefinition => { }
+            Tag::PropertyDefinition => {
+                return Ok(ClassMember::Field(None, is_static, key, self.extract_expr_opt("value")?));
+            }
+            _ => { return node_type_error("class element", tag); }
+        }
+        let mut val = self.extract_object("value")?;
+        let kind = self.extract_string("kind")?;
+        Ok(match &kind[..] {
+            "constructor" => ClassMember::Constructor(val.into_fun(key)?),
+            "method" => ClassMember::Method(None, is_static, val.into_fun(key)?),
+            "get" => ClassMember::Get(None, is_static, key, val.extract_object("body")?.extract_script("body")?),
+            "set" => {
+                let fun = val.into_fun(())?;
+                let params = fun.params.list;
+                if params.len() != 1 {
+                    return array_error(1, params.len());
+                }
+                let param = params.into_iter().next().unwrap();
+                ClassMember::Set(None, is_static, key, param, fun.body)
+            }
+            _ => { return string_error("'constructor', 'method', 'get', or 'set'", kind); }
+        })
+    }
+}
+
+trait IntoClassElementName {
+    fn into_class_element_name(self, computed: bool) -> Result<PropKey>;
+}
+
+impl IntoClassElementName for Object {
+    fn into_class_element_name(mut self, computed: bool) -> Result<PropKey> {
+        if !computed && self.tag()? == Tag::PrivateIdentifier {
+            return Ok(PropKey::Private(PrivateKey { location: None, value: self.extract_string("name")? }));
+        }
+        self.into_prop_key(computed)
+    }
+}
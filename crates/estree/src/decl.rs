@@ -1,10 +1,19 @@
-use easter::decl::{Dtor, ConstDtor, DtorExt};
+use easter::decl::{Dtor, ConstDtor, DtorExt, Import, ImportSpec, Export, ExportSpec};
+use easter::obj::DotKey;
 use easter::patt::Patt;
+use easter::punc::Semi;
+use easter::stmt::{Stmt, StmtListItem, ModItem};
 use unjson::ty::Object;
+use unjson::{Unjson, ExtractField};
 
-use result::Result;
-use error::Error;
+use tag::{Tag, TagOf};
+use result::{Result, Map};
+use error::{Error, node_type_error};
 use node::ExtractNode;
+use stmt::IntoStmt;
+use expr::IntoExpr;
+use fun::IntoFun;
+use class::IntoClass;
 
 pub trait IntoDecl {
     fn into_dtor(self) -> Result<Dtor>;
@@ -13,6 +22,9 @@ pub trait IntoDecl {
 impl IntoDecl for Object {
     fn into_dtor(mut self) -> Result<Dtor> {
         let lhs = self.extract_patt("id")?;
+        if let Patt::Default(..) = lhs {
+            return Err(Error::DefaultPatternInDeclarator(lhs));
+        }
         let init = self.extract_expr_opt("init")?;
         Dtor::from_init_opt(lhs, init).map_err(Error::UninitializedPattern)
     }
@@ -35,7 +47,136 @@ impl IntoConst for Vec<Dtor> {
                 Dtor::Compound(_, compound, expr) => {
                     ConstDtor::from_compound_init(compound, expr)
                 }
+                Dtor::Typed(_, patt, Some(expr)) => {
+                    ConstDtor::from_init(patt, expr).map_err(Error::DefaultPatternInDeclarator)?
+                }
+                Dtor::Typed(_, patt, None) => {
+                    return Err(Error::UninitializedPattern(patt));
+                }
             })
         }).collect()
     }
-}
\ No newline at end of file
+}
+
+pub trait IntoModItem {
+    fn into_mod_item(self) -> Result<ModItem>;
+}
+
+impl IntoModItem for Object {
+    fn into_mod_item(self) -> Result<ModItem> {
+        Ok(match self.tag()? {
+            Tag::ImportDeclaration => ModItem::Import(self.into_import()?),
+            Tag::ExportNamedDeclaration
+          | Tag::ExportDefaultDeclaration
+          | Tag::ExportAllDeclaration => ModItem::Export(self.into_export()?),
+            _ => ModItem::StmtListItem(self.into_stmt_list_item()?)
+        })
+    }
+}
+
+fn id_key(mut obj: Object) -> Result<DotKey> {
+    match obj.tag()? {
+        Tag::Identifier => Ok(DotKey { location: None, value: obj.extract_string("name")? }),
+        tag => node_type_error("identifier", tag)
+    }
+}
+
+trait IntoImport {
+    fn into_import(self) -> Result<Import>;
+}
+
+// ESTree has no separate node for "import {} from ...", so an import with
+// no specifiers converts to an import for effect.
+impl IntoImport for Object {
+    fn into_import(mut self) -> Result<Import> {
+        let source = self.extract_string_literal("source")?;
+        let list = self.extract_array("specifiers")?;
+        let specifiers = list.map(|v| v.into_object().map_err(Error::Json))?;
+        let mut default = None;
+        let mut namespace = None;
+        let mut specs = Vec::new();
+        for mut spec in specifiers {
+            match spec.tag()? {
+                Tag::ImportDefaultSpecifier => { default = Some(spec.extract_id("local")?); }
+                Tag::ImportNamespaceSpecifier if specs.is_empty() => {
+                    namespace = Some(spec.extract_id("local")?);
+                }
+                Tag::ImportSpecifier if namespace.is_none() => {
+                    specs.push(ImportSpec {
+                        location: None,
+                        imported: id_key(spec.extract_object("imported")?)?,
+                        local: spec.extract_id("local")?
+                    });
+                }
+                tag => { return node_type_error("import specifier", tag); }
+            }
+        }
+        Ok(match (default, namespace) {
+            (None, None) if specs.is_empty() => Import::ForEffect(None, source),
+            (Some(default), None) if specs.is_empty() => Import::Default(None, default, source),
+            (default, Some(namespace)) => Import::Namespace(None, default, namespace, source),
+            (default, None) => Import::Named(None, default, specs, source)
+        })
+    }
+}
+
+trait IntoExport {
+    fn into_export(self) -> Result<Export>;
+}
+
+impl IntoExport for Object {
+    fn into_export(mut self) -> Result<Export> {
+        Ok(match self.tag()? {
+            Tag::ExportNamedDeclaration => {
+                if let Some(decl) = self.extract_object_opt("declaration")? {
+                    return match decl.into_stmt_list_item()? {
+                        StmtListItem::Stmt(Stmt::Var(_, dtors, semi)) => Ok(Export::Var(None, dtors, semi)),
+                        StmtListItem::Decl(decl) => Ok(Export::Decl(None, decl)),
+                        StmtListItem::Stmt(_) => node_type_error("declaration", Tag::ExportNamedDeclaration)
+                    };
+                }
+                let list = self.extract_array("specifiers")?;
+                let objs = list.map(|v| v.into_object().map_err(Error::Json))?;
+                let specs = objs.map(|mut spec| {
+                    match spec.tag()? {
+                        Tag::ExportSpecifier => Ok(ExportSpec {
+                            location: None,
+                            local: id_key(spec.extract_object("local")?)?,
+                            exported: id_key(spec.extract_object("exported")?)?
+                        }),
+                        tag => node_type_error("export specifier", tag)
+                    }
+                })?;
+                match self.extract_string_literal_opt("source")? {
+                    Some(source) => Export::NamedFrom(None, specs, source, Semi::Explicit(None)),
+                    None => Export::Named(None, specs, Semi::Explicit(None))
+                }
+            }
+            Tag::ExportDefaultDeclaration => {
+                let mut decl = self.extract_object("declaration")?;
+                match decl.tag()? {
+                    Tag::FunctionDeclaration => {
+                        let id = decl.extract_id_opt("id")?;
+                        Export::DefaultFun(None, decl.into_fun(id)?)
+                    }
+                    Tag::ClassDeclaration => {
+                        let id = decl.extract_id_opt("id")?;
+                        Export::DefaultClass(None, decl.into_class(id)?)
+                    }
+                    _ => Export::DefaultExpr(None, decl.into_expr()?, Semi::Explicit(None))
+                }
+            }
+            Tag::ExportAllDeclaration => {
+                // "export * as ns from" isn't supported. (The "exported" field
+                // was added in ES2020, so older trees leave it out.)
+                if self.contains_key("exported") {
+                    if let Some(obj) = self.extract_object_opt("exported")? {
+                        return node_type_error("export all declaration without a name", obj.tag()?);
+                    }
+                }
+                Export::All(None, self.extract_string_literal("source")?, Semi::Explicit(None))
+            }
+            tag => { return node_type_error("export declaration", tag); }
+        })
+    }
+}
@@ -16,7 +16,8 @@ pub enum Error {
     NodeTypeMismatch(&'static str, Tag),
     UnexpectedInitializer(Expr),
     InvalidLHS(&'static str),
-    UninitializedPattern(Patt<Id>)
+    UninitializedPattern(Patt<Id>),
+    DefaultPatternInDeclarator(Patt<Id>)
 }
 
 impl Display for Error {
@@ -40,11 +41,17 @@ impl Display for Error {
             &Error::UninitializedPattern(ref patt) => {
                 let ty = match *patt {
                     Patt::Compound(CompoundPatt::Arr(_, _, _)) => "array",
-                    Patt::Compound(CompoundPatt::Obj(_, _)) => "object",
-                    Patt::Simple(_) => "constant"
+                    Patt::Compound(CompoundPatt::Obj(_, _, _)) => "object",
+                    Patt::Simple(_) => "constant",
+                    Patt::Default(_, _, _) => "default",
+                    Patt::Typed(_, _, _) => "typed",
+                    Patt::Optional(_, _) => "optional"
                 };
                 fmt.write_fmt(format_args!("uninitialized {} pattern in declarator", ty))
             }
+            &Error::DefaultPatternInDeclarator(_) => {
+                fmt.write_fmt(format_args!("assignment pattern as declarator id"))
+            }
         }
     }
 }
@@ -1,19 +1,22 @@
 use serde_json::value::Value;
-use easter::expr::{Expr, ExprListItem};
-use easter::obj::DotKey;
+use easter::expr::{Expr, ExprListItem, Template, TemplateString};
+use easter::obj::{DotKey, PrivateKey};
 use easter::id::IdExt;
 use easter::punc::{Unop, Binop, Assop, Logop};
 use unjson::ty::{Object, TyOf};
-use unjson::ExtractField;
-use joker::token::RegExpLiteral;
+use unjson::{ExtractField, Unjson};
+use joker::token::{RegExpLiteral, TemplatePart};
 
 use tag::{Tag, TagOf};
 use id::IntoId;
-use result::Result;
-use error::{string_error, node_type_error, type_error};
+use result::{Result, Map};
+use error::{Error, string_error, node_type_error, type_error, field_error};
 use node::ExtractNode;
-use fun::IntoFun;
-use lit::{IntoStringLiteral, IntoNumberLiteral};
+use fun::{IntoFun, IntoArrow};
+use class::IntoClass;
+use lit::{IntoStringLiteral, IntoNumberLiteral, IntoBigIntLiteral};
+#[cfg(feature = "jsx")]
+use jsx::IntoJsx;
 
 pub trait IntoExpr {
     fn into_expr(self) -> Result<Expr>;
@@ -33,9 +36,13 @@ impl IntoExpr for Object {
                     Ok(op) => op,
                     Err(_) => { return string_error("binary operator", str); }
                 };
-                let left = self.extract_expr("left")?;
-                let right = self.extract_expr("right")?;
-                Expr::Binop(None, op, Box::new(left), Box::new(right))
+                let mut left = self.extract_object("left")?;
+                let right = Box::new(self.extract_expr("right")?);
+                if left.tag()? == Tag::PrivateIdentifier && str == "in" {
+                    let key = PrivateKey { location: None, value: left.extract_string("name")? };
+                    return Ok(Expr::PrivateIn(None, key, right));
+                }
+                Expr::Binop(None, op, Box::new(left.into_expr()?), right)
             }
             Tag::AssignmentExpression => {
                 let str = self.extract_string("operator")?;
@@ -84,20 +91,43 @@ impl IntoExpr for Object {
             }
             Tag::MemberExpression => {
                 let obj = Box::new(self.extract_expr("object")?);
+                let optional = self.extract_flag("optional")?;
                 if self.extract_bool("computed")? {
                     let prop = Box::new(self.extract_expr("property")?);
-                    Expr::Brack(None, obj, prop)
+                    if optional {
+                        Expr::OptionalBrack(None, obj, prop)
+                    } else {
+                        Expr::Brack(None, obj, prop)
+                    }
                 } else {
-                    let id = self.extract_object("property")?.into_id()?;
+                    let mut prop = self.extract_object("property")?;
+                    if prop.tag()? == Tag::PrivateIdentifier {
+                        let key = PrivateKey { location: None, value: prop.extract_string("name")? };
+                        return Ok(if optional {
+                            Expr::OptionalPrivateDot(None, obj, key)
+                        } else {
+                            Expr::PrivateDot(None, obj, key)
+                        });
+                    }
+                    let id = prop.into_id()?;
                     let key = DotKey { location: None, value: id.name.into_string() };
-                    Expr::Dot(None, obj, key)
+                    if optional {
+                        Expr::OptionalDot(None, obj, key)
+                    } else {
+                        Expr::Dot(None, obj, key)
+                    }
                 }
             }
             Tag::CallExpression => {
                 let callee = Box::new(self.extract_expr("callee")?);
                 let args = self.extract_expr_list("arguments")?;
-                Expr::Call(None, callee, args)
+                if self.extract_flag("optional")? {
+                    Expr::OptionalCall(None, callee, args)
+                } else {
+                    Expr::Call(None, callee, args)
+                }
             }
+            Tag::ChainExpression => Expr::Chain(None, Box::new(self.extract_expr("expression")?)),
             Tag::NewExpression => {
                 let callee = Box::new(self.extract_expr("callee")?);
                 let args = self.extract_expr_list("arguments")?;
@@ -112,6 +142,18 @@ impl IntoExpr for Object {
                 let fun = self.into_fun(id)?;
                 Expr::Fun(fun)
             }
+            Tag::ArrowFunctionExpression => Expr::Arrow(self.into_arrow()?),
+            Tag::ClassExpression => {
+                let id = self.extract_id_opt("id")?;
+                let class = self.into_class(id)?;
+                Expr::Class(class)
+            }
+            Tag::TemplateLiteral => Expr::Template(self.into_template()?),
+            Tag::TaggedTemplateExpression => {
+                let tag = Box::new(self.extract_expr("tag")?);
+                let template = self.extract_object("quasi")?.into_template()?;
+                Expr::TaggedTemplate(None, tag, template)
+            }
             Tag::SequenceExpression => {
                 let exprs = self.extract_exprs("expressions")?;
                 Expr::Seq(None, exprs)
@@ -126,15 +168,37 @@ impl IntoExpr for Object {
                 let alt = Box::new(self.extract_expr("alternate")?);
                 Expr::Cond(None, test, cons, alt)
             }
+            Tag::YieldExpression => {
+                let arg = self.extract_expr_opt("argument")?.map(Box::new);
+                match (self.extract_bool("delegate")?, arg) {
+                    (true, Some(arg)) => Expr::YieldDelegate(None, arg),
+                    (true, None) => { return field_error("argument"); }
+                    (false, arg) => Expr::Yield(None, arg)
+                }
+            }
+            Tag::AwaitExpression => {
+                let arg = self.extract_expr("argument")?;
+                Expr::Await(None, Box::new(arg))
+            }
             Tag::ThisExpression => Expr::This(None),
+            Tag::Super => Expr::Super(None),
             Tag::MetaProperty => {
                 let meta = self.extract_id("meta")?.name;
                 let prop = self.extract_id("property")?.name;
                 match (meta.as_ref(), prop.as_ref()) {
                     ("new", "target") => Expr::NewTarget(None),
-                    (meta, prop) => { return string_error("new.target", format!("{}.{}", meta, prop)); }
+                    ("import", "meta") => Expr::ImportMeta(None),
+                    (meta, prop) => { return string_error("new.target or import.meta", format!("{}.{}", meta, prop)); }
                 }
             }
+            Tag::ImportExpression => {
+                let source = self.extract_expr("source")?;
+                Expr::Import(None, Box::new(source))
+            }
+            #[cfg(feature = "jsx")]
+            Tag::JSXElement => Expr::JsxElement(Box::new(self.into_jsx_element()?)),
+            #[cfg(feature = "jsx")]
+            Tag::JSXFragment => Expr::JsxFragment(Box::new(self.into_jsx_fragment()?)),
             _ => { return node_type_error("expression", tag); }
         })
     }
@@ -149,6 +213,9 @@ impl IntoExpr for Object {
     }
 
     fn into_lit(mut self) -> Result<Expr> {
+        if self.contains_key("bigint") {
+            return Ok(Expr::BigInt(None, self.extract_string("bigint")?.into_bigint_literal()));
+        }
         let json = self.extract_field("value")?;
         Ok(match json {
             Value::Null if !self.contains_key("regex") => Expr::Null(None),
@@ -171,3 +238,29 @@ impl IntoExpr for Object {
         })
     }
 }
+
+trait IntoTemplate {
+    fn into_template(self) -> Result<Template>;
+}
+
+impl IntoTemplate for Object {
+    fn into_template(mut self) -> Result<Template> {
+        let list = self.extract_array("quasis")?;
+        let objs = list.map(|v| v.into_object().map_err(Error::Json))?;
+        let strings = objs.map(|mut o| {
+            let mut value = o.extract_object("value")?;
+            Ok(TemplateString {
+                location: None,
+                value: TemplatePart {
+                    cooked: value.extract_string_opt("cooked")?,
+                    raw: value.extract_string("raw")?
+                }
+            })
+        })?;
+        Ok(Template {
+            location: None,
+            strings: strings,
+            exprs: self.extract_exprs("expressions")?
+        })
+    }
+}
@@ -1,10 +1,27 @@
-use easter::fun::Fun;
+use easter::fun::{Fun, Arrow, ArrowBody};
 use unjson::ty::Object;
 use unjson::ExtractField;
 
 use result::Result;
 use node::ExtractNode;
 
+pub trait IntoArrow {
+    fn into_arrow(self) -> Result<Arrow>;
+}
+
+impl IntoArrow for Object {
+    fn into_arrow(mut self) -> Result<Arrow> {
+        let asynchronous = self.extract_flag("async")?;
+        let params = self.extract_params("params")?;
+        let body = if self.extract_bool("expression")? {
+            ArrowBody::Expr(Box::new(self.extract_expr("body")?))
+        } else {
+            ArrowBody::Block(self.extract_object("body")?.extract_script("body")?)
+        };
+        Ok(Arrow { location: None, params: params, body: body, asynchronous: asynchronous, type_params: Vec::new(), ret: None })
+    }
+}
+
 pub trait IntoFun<Id> {
     fn into_fun(self, Id) -> Result<Fun<Id>>;
 }
@@ -12,9 +29,20 @@ pub trait IntoFun<Id> {
 impl<Id> IntoFun<Id> for Object {
     fn into_fun(mut self, id: Id) -> Result<Fun<Id>> {
         let generator = self.extract_bool_opt("generator")?.unwrap_or(false);
+        let asynchronous = self.extract_flag("async")?;
         let params = self.extract_params("params")?;
         let mut obj = self.extract_object("body")?;
         let body = obj.extract_script("body")?;
-        Ok(Fun { location: None, id: id, params: params, body: body, generator: generator })
+        Ok(Fun {
+            location: None,
+            id: id,
+            params: params,
+            body: body,
+            generator: generator,
+            asynchronous: asynchronous,
+            type_params: Vec::new(),
+            this_type: None,
+            ret: None
+        })
     }
 }
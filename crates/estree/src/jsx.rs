@@ -0,0 +1,138 @@
+use easter::jsx::{JsxId, JsxElementName, JsxAttr, JsxAttrName, JsxAttrValue, JsxExprContainer, JsxChild};
+use easter::jsx::{JsxOpeningElement, JsxClosingElement, JsxElement, JsxFragment};
+use unjson::ty::Object;
+use unjson::{ExtractField, Unjson};
+
+use tag::{Tag, TagOf};
+use result::{Result, Map};
+use error::{Error, node_type_error};
+use node::ExtractNode;
+use expr::IntoExpr;
+use lit::IntoStringLiteral;
+
+pub trait IntoJsx {
+    fn into_jsx_element(self) -> Result<JsxElement>;
+    fn into_jsx_fragment(self) -> Result<JsxFragment>;
+}
+
+impl IntoJsx for Object {
+    fn into_jsx_element(mut self) -> Result<JsxElement> {
+        let mut opening = self.extract_object("openingElement")?;
+        let name = opening.extract_object("name")?.into_jsx_element_name()?;
+        let attrs = opening.extract_objects("attributes")?.map(|o| o.into_jsx_attr())?;
+        let self_closing = opening.extract_bool("selfClosing")?;
+        let closing = match self.extract_object_opt("closingElement")? {
+            Some(mut closing) => Some(JsxClosingElement {
+                location: None,
+                name: closing.extract_object("name")?.into_jsx_element_name()?
+            }),
+            None => None
+        };
+        Ok(JsxElement {
+            location: None,
+            opening: JsxOpeningElement {
+                location: None,
+                name: name,
+                attrs: attrs,
+                self_closing: self_closing
+            },
+            children: self.extract_objects("children")?.map(|o| o.into_jsx_child())?,
+            closing: closing
+        })
+    }
+
+    fn into_jsx_fragment(mut self) -> Result<JsxFragment> {
+        Ok(JsxFragment {
+            location: None,
+            opening: None,
+            children: self.extract_objects("children")?.map(|o| o.into_jsx_child())?,
+            closing: None
+        })
+    }
+}
+
+trait IntoJsxNode {
+    fn into_jsx_id(self) -> Result<JsxId>;
+    fn into_jsx_element_name(self) -> Result<JsxElementName>;
+    fn into_jsx_attr(self) -> Result<JsxAttr>;
+    fn into_jsx_expr_container(self) -> Result<JsxExprContainer>;
+    fn into_jsx_child(self) -> Result<JsxChild>;
+    fn extract_objects(&mut self, &'static str) -> Result<Vec<Object>>;
+}
+
+impl IntoJsxNode for Object {
+    fn into_jsx_id(mut self) -> Result<JsxId> {
+        match self.tag()? {
+            Tag::JSXIdentifier => Ok(JsxId { location: None, name: self.extract_string("name")? }),
+            tag => node_type_error("JSX identifier", tag)
+        }
+    }
+
+    fn into_jsx_element_name(mut self) -> Result<JsxElementName> {
+        Ok(match self.tag()? {
+            Tag::JSXIdentifier => JsxElementName::Id(self.into_jsx_id()?),
+            Tag::JSXNamespacedName => {
+                let namespace = self.extract_object("namespace")?.into_jsx_id()?;
+                let name = self.extract_object("name")?.into_jsx_id()?;
+                JsxElementName::Namespaced(None, namespace, name)
+            }
+            Tag::JSXMemberExpression => {
+                let object = self.extract_object("object")?.into_jsx_element_name()?;
+                let property = self.extract_object("property")?.into_jsx_id()?;
+                JsxElementName::Member(None, Box::new(object), property)
+            }
+            tag => { return node_type_error("JSX element name", tag); }
+        })
+    }
+
+    fn into_jsx_attr(mut self) -> Result<JsxAttr> {
+        match self.tag()? {
+            Tag::JSXAttribute => { }
+            Tag::JSXSpreadAttribute => { return Ok(JsxAttr::Spread(None, self.extract_expr("argument")?)); }
+            tag => { return node_type_error("JSX attribute", tag); }
+        }
+        let mut name = self.extract_object("name")?;
+        let name = match name.tag()? {
+            Tag::JSXNamespacedName => {
+                let namespace = name.extract_object("namespace")?.into_jsx_id()?;
+                JsxAttrName::Namespaced(None, namespace, name.extract_object("name")?.into_jsx_id()?)
+            }
+            _ => JsxAttrName::Id(name.into_jsx_id()?)
+        };
+        let value = match self.extract_object_opt("value")? {
+            Some(mut value) => Some(match value.tag()? {
+                Tag::Literal => JsxAttrValue::String(None, value.extract_string("value")?.into_string_literal()),
+                Tag::JSXExpressionContainer => JsxAttrValue::Expr(value.into_jsx_expr_container()?),
+                Tag::JSXElement => JsxAttrValue::Element(value.into_jsx_element()?),
+                Tag::JSXFragment => JsxAttrValue::Fragment(value.into_jsx_fragment()?),
+                tag => { return node_type_error("JSX attribute value", tag); }
+            }),
+            None => None
+        };
+        Ok(JsxAttr::Attr(None, name, value))
+    }
+
+    // An empty container holds a JSXEmptyExpression.
+    fn into_jsx_expr_container(mut self) -> Result<JsxExprContainer> {
+        let expr = self.extract_object("expression")?;
+        Ok(JsxExprContainer {
+            location: None,
+            expr: if expr.tag()? == Tag::JSXEmptyExpression { None } else { Some(expr.into_expr()?) }
+        })
+    }
+
+    fn into_jsx_child(mut self) -> Result<JsxChild> {
+        Ok(match self.tag()? {
+            Tag::JSXText => JsxChild::Text(None, self.extract_string("value")?.into_string_literal()),
+            Tag::JSXExpressionContainer => JsxChild::Expr(self.into_jsx_expr_container()?),
+            Tag::JSXSpreadChild => JsxChild::Spread(None, self.extract_expr("expression")?),
+            Tag::JSXElement => JsxChild::Element(self.into_jsx_element()?),
+            Tag::JSXFragment => JsxChild::Fragment(self.into_jsx_fragment()?),
+            tag => { return node_type_error("JSX child", tag); }
+        })
+    }
+
+    fn extract_objects(&mut self, name: &'static str) -> Result<Vec<Object>> {
+        self.extract_array(name)?.map(|v| v.into_object().map_err(Error::Json))
+    }
+}
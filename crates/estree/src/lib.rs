@@ -12,17 +12,20 @@ mod expr;
 mod id;
 mod node;
 mod fun;
+mod class;
 mod patt;
 mod obj;
 mod decl;
 mod prog;
 mod lit;
+#[cfg(feature = "jsx")]
+mod jsx;
 
 use serde::de::Error;
 use serde::de::{Deserialize, Deserializer};
 use easter::stmt::Script;
 use unjson::ty::Object;
-pub use prog::IntoScript;
+pub use prog::{IntoScript, IntoModule};
 
 pub struct ESTreeScript(Script);
 
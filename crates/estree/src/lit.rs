@@ -1,4 +1,4 @@
-use joker::token::{StringLiteral, NumberLiteral, NumberSource};
+use joker::token::{StringLiteral, NumberLiteral, NumberSource, BigIntLiteral, Radix, CharCase};
 
 pub trait IntoStringLiteral {
     fn into_string_literal(self) -> StringLiteral;
@@ -43,3 +43,37 @@ impl IntoNumberLiteral for f64 {
         }
     }
 }
+
+pub trait IntoBigIntLiteral {
+    fn into_bigint_literal(self) -> BigIntLiteral;
+}
+
+// The `bigint` field of an ESTree literal holds the source digits without
+// the "n" suffix, including any radix prefix.
+impl IntoBigIntLiteral for String {
+    fn into_bigint_literal(self) -> BigIntLiteral {
+        let radix = {
+            let mut chars = self.chars();
+            match (chars.next(), chars.next()) {
+                (Some('0'), Some(flag)) => {
+                    let cc = if flag.is_lowercase() { CharCase::LowerCase } else { CharCase::UpperCase };
+                    match flag {
+                        'x' | 'X' => Some(Radix::Hex(cc)),
+                        'o' | 'O' => Some(Radix::Oct(Some(cc))),
+                        'b' | 'B' => Some(Radix::Bin(cc)),
+                        _ => None
+                    }
+                }
+                _ => None
+            }
+        };
+        let digits = match radix {
+            Some(_) => self[2..].to_string(),
+            None => self
+        };
+        BigIntLiteral {
+            radix: radix,
+            digits: digits
+        }
+    }
+}
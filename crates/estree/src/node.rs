@@ -2,22 +2,24 @@ use unjson::ty::Object;
 use unjson::{ExtractField, Unjson};
 use easter::id::Id;
 use easter::expr::{Expr, ExprListItem};
-use easter::stmt::{Stmt, Block, StmtListItem, Case, Catch, Script};
+use easter::stmt::{Stmt, Block, StmtListItem, ModItem, Case, Catch, Script, Module};
 use easter::patt::{Patt, RestPatt, AssignTarget};
 use easter::obj::Prop;
 use easter::decl::Dtor;
-use easter::cover::{IntoAssignTarget, IntoAssignPatt};
+use easter::cover::IntoAssignTarget;
 use easter::fun::Params;
+use joker::token::StringLiteral;
 
 use tag::{Tag, TagOf};
-use error::Error;
+use error::{Error, field_error, node_type_error};
 use result::{Result, Map};
 use id::IntoId;
 use stmt::IntoStmt;
 use expr::IntoExpr;
 use patt::IntoPatt;
 use obj::IntoObj;
-use decl::IntoDecl;
+use decl::{IntoDecl, IntoModItem};
+use lit::IntoStringLiteral;
 
 pub trait ExtractNode {
     fn extract_id(&mut self, &'static str) -> Result<Id>;
@@ -40,6 +42,10 @@ pub trait ExtractNode {
     fn extract_case_list(&mut self, &'static str) -> Result<Vec<Case>>;
     fn extract_catch_opt(&mut self, &'static str) -> Result<Option<Catch>>;
     fn extract_script(&mut self, &'static str) -> Result<Script>;
+    fn extract_module(&mut self, &'static str) -> Result<Module>;
+    fn extract_string_literal(&mut self, &'static str) -> Result<StringLiteral>;
+    fn extract_string_literal_opt(&mut self, &'static str) -> Result<Option<StringLiteral>>;
+    fn extract_flag(&mut self, &'static str) -> Result<bool>;
 }
 
 fn split_prefix<T, F>(v: &mut Vec<T>, mut p: F) -> Vec<T>
@@ -72,8 +78,7 @@ impl ExtractNode for Object {
     }
 
     fn extract_assign_patt(&mut self, name: &'static str) -> Result<Patt<AssignTarget>> {
-        let expr = self.extract_expr(name)?;
-        match expr.into_assign_patt() {
+        match self.extract_object(name)?.into_assign_patt() {
             Ok(patt) => Ok(patt),
             _ => Err(Error::InvalidLHS(name))
         }
@@ -192,9 +197,59 @@ impl ExtractNode for Object {
                          .collect();
         Ok(Script {
             location: None,
+            hashbang: None,
+            dirs: prolog,
+            items: items
+        })
+    }
+
+    fn extract_module(&mut self, name: &'static str) -> Result<Module> {
+        let list = self.extract_array(name)?;
+        let objs = list.map(|v| v.into_object().map_err(Error::Json))?;
+        let mut list = objs.map(|o| o.into_mod_item())?;
+        let items = split_prefix(&mut list, |item| match *item {
+            ModItem::StmtListItem(ref item) => item.is_directive(),
+            _ => false
+        });
+        let prolog = list.iter()
+                         .filter_map(|item| match *item {
+                             ModItem::StmtListItem(ref item) => item.to_directive(),
+                             _ => None
+                         })
+                         .collect();
+        Ok(Module {
+            location: None,
+            hashbang: None,
             dirs: prolog,
             items: items
         })
     }
 
+    fn extract_string_literal(&mut self, name: &'static str) -> Result<StringLiteral> {
+        match self.extract_string_literal_opt(name)? {
+            Some(lit) => Ok(lit),
+            None => field_error(name)
+        }
+    }
+
+    fn extract_string_literal_opt(&mut self, name: &'static str) -> Result<Option<StringLiteral>> {
+        let mut obj = match self.extract_object_opt(name)? {
+            Some(obj) => obj,
+            None => { return Ok(None); }
+        };
+        match obj.tag()? {
+            Tag::Literal => Ok(Some(obj.extract_string("value")?.into_string_literal())),
+            tag => node_type_error("string literal", tag)
+        }
+    }
+
+    // Flags added by later editions of ESTree (like "async" or "optional")
+    // are treated as false when they're missing.
+    fn extract_flag(&mut self, name: &'static str) -> Result<bool> {
+        if !self.contains_key(name) {
+            return Ok(false);
+        }
+        Ok(self.extract_bool_opt(name)?.unwrap_or(false))
+    }
+
 }
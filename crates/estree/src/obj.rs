@@ -13,18 +13,22 @@ use fun::IntoFun;
 
 pub trait IntoObj {
     fn into_prop(self) -> Result<Prop>;
-    fn into_prop_key(self) -> Result<PropKey>;
+    fn into_prop_key(self, computed: bool) -> Result<PropKey>;
 }
 
 impl IntoObj for Object {
     fn into_prop(mut self) -> Result<Prop> {
+        if self.tag()? == Tag::SpreadElement {
+            return Ok(Prop::Spread(None, self.extract_expr("argument")?));
+        }
+        let computed = self.extract_bool("computed")?;
         let key = self.extract_object("key")?;
         let mut val = self.extract_object("value")?;
         let kind = self.extract_string("kind")?;
         let val = match &kind[..] {
             "init" => {
                 if self.extract_bool("method")? {
-                    let fun = val.into_fun(key.into_prop_key()?)?;
+                    let fun = val.into_fun(key.into_prop_key(computed)?)?;
                     return Ok(Prop::Method(fun))
                 } else if self.extract_bool("shorthand")? {
                     return Ok(Prop::Shorthand(key.into_id()?));
@@ -44,10 +48,13 @@ impl IntoObj for Object {
             }
             _ => { return type_error("'init', 'get', or 'set'", Ty::String); }
         };
-        Ok(Prop::Regular(None, key.into_prop_key()?, val))
+        Ok(Prop::Regular(None, key.into_prop_key(computed)?, val))
     }
 
-    fn into_prop_key(self) -> Result<PropKey> {
+    fn into_prop_key(self, computed: bool) -> Result<PropKey> {
+        if computed {
+            return Ok(PropKey::Computed(None, Box::new(self.into_expr()?)));
+        }
         if self.tag()? == Tag::Identifier {
             let id = self.into_id()?;
             return Ok(PropKey::Id(None, id.name.into_string()));
@@ -55,7 +62,8 @@ impl IntoObj for Object {
         match self.into_lit()? {
             Expr::Number(_, lit) => Ok(PropKey::Number(None, lit)),
             Expr::String(_, lit) => Ok(PropKey::String(None, lit)),
-            _ => { return type_error("identifier, number literal, or string literal", Ty::Object); }
+            Expr::BigInt(_, lit) => Ok(PropKey::BigInt(None, lit)),
+            _ => { return type_error("identifier, number literal, bigint literal, or string literal", Ty::Object); }
         }
     }
 }
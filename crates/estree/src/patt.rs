@@ -1,16 +1,108 @@
-use easter::patt::Patt;
+use easter::patt::{Patt, CompoundPatt, PropPatt, RestPatt, AssignTarget};
 use easter::id::{Id, IdExt};
+use easter::cover::IntoAssignPatt;
 use unjson::ty::Object;
+use unjson::{ExtractField, Unjson};
 
+use tag::{Tag, TagOf};
 use id::IntoId;
+use expr::IntoExpr;
+use obj::IntoObj;
+use node::ExtractNode;
 use result::Result;
+use error::{Error, node_type_error};
 
 pub trait IntoPatt {
     fn into_patt(self) -> Result<Patt<Id>>;
+    fn into_assign_patt(self) -> Result<Patt<AssignTarget>>;
 }
 
 impl IntoPatt for Object {
-    fn into_patt(self) -> Result<Patt<Id>> {
-        self.into_id().map(|id| id.into_patt())
+    fn into_patt(mut self) -> Result<Patt<Id>> {
+        match self.tag()? {
+            Tag::ArrayPattern | Tag::ObjectPattern => {
+                into_compound_patt(self, &|obj| obj.into_patt()).map(Patt::Compound)
+            }
+            Tag::AssignmentPattern => {
+                let patt = self.extract_object("left")?.into_patt()?;
+                let init = self.extract_expr("right")?;
+                Ok(Patt::Default(None, Box::new(patt), Box::new(init)))
+            }
+            _ => self.into_id().map(|id| id.into_patt())
+        }
     }
+
+    fn into_assign_patt(mut self) -> Result<Patt<AssignTarget>> {
+        match self.tag()? {
+            Tag::ArrayPattern | Tag::ObjectPattern => {
+                into_compound_patt(self, &|obj| obj.into_assign_patt()).map(Patt::Compound)
+            }
+            Tag::AssignmentPattern => {
+                let patt = self.extract_object("left")?.into_assign_patt()?;
+                let init = self.extract_expr("right")?;
+                Ok(Patt::Default(None, Box::new(patt), Box::new(init)))
+            }
+            _ => {
+                let expr = self.into_expr()?;
+                expr.into_assign_patt().map_err(|_| Error::InvalidLHS("left"))
+            }
+        }
+    }
+}
+
+fn into_compound_patt<T, F>(mut obj: Object, into_patt: &F) -> Result<CompoundPatt<T>>
+  where F: Fn(Object) -> Result<Patt<T>>
+{
+    Ok(match obj.tag()? {
+        Tag::ArrayPattern => {
+            let list = obj.extract_array("elements")?;
+            let mut elts = Vec::with_capacity(list.len());
+            let mut rest = None;
+            for v in list {
+                match v.into_object_opt()? {
+                    None => { elts.push(None); }
+                    Some(mut elt) => {
+                        if elt.tag()? == Tag::RestElement {
+                            rest = Some(Box::new(RestPatt {
+                                location: None,
+                                patt: into_patt(elt.extract_object("argument")?)?
+                            }));
+                        } else {
+                            elts.push(Some(into_patt(elt)?));
+                        }
+                    }
+                }
+            }
+            CompoundPatt::Arr(None, elts, rest)
+        }
+        Tag::ObjectPattern => {
+            let list = obj.extract_array("properties")?;
+            let mut props = Vec::with_capacity(list.len());
+            let mut rest = None;
+            for v in list {
+                let mut prop = v.into_object()?;
+                if prop.tag()? == Tag::RestElement {
+                    rest = Some(Box::new(RestPatt {
+                        location: None,
+                        patt: into_patt(prop.extract_object("argument")?)?
+                    }));
+                    continue;
+                }
+                let computed = prop.extract_bool("computed")?;
+                let key = prop.extract_object("key")?;
+                let mut val = prop.extract_object("value")?;
+                props.push(if prop.extract_bool("shorthand")? {
+                    if val.tag()? == Tag::AssignmentPattern {
+                        PropPatt::ShorthandDefault(None, key.into_id()?, val.extract_expr("right")?)
+                    } else {
+                        PropPatt::Shorthand(key.into_id()?)
+                    }
+                } else {
+                    PropPatt::Regular(None, key.into_prop_key(computed)?, into_patt(val)?)
+                });
+            }
+            CompoundPatt::Obj(None, props, rest)
+        }
+        tag => { return node_type_error("pattern", tag); }
+    })
 }
@@ -1,5 +1,6 @@
 use unjson::ty::Object;
-use easter::stmt::Script;
+use unjson::ExtractField;
+use easter::stmt::{Script, Module, Hashbang};
 use result::Result;
 use node::ExtractNode;
 
@@ -9,6 +10,28 @@ pub trait IntoScript {
 
 impl IntoScript for Object {
     fn into_script(mut self) -> Result<Script> {
-        self.extract_script("body")
+        let mut script = self.extract_script("body")?;
+        if self.contains_key("hashbang") {
+            script.hashbang = self.extract_string_opt("hashbang")?.map(|value| {
+                Hashbang { location: None, value: value }
+            });
+        }
+        Ok(script)
+    }
+}
+
+pub trait IntoModule {
+    fn into_module(self) -> Result<Module>;
+}
+
+impl IntoModule for Object {
+    fn into_module(mut self) -> Result<Module> {
+        let mut module = self.extract_module("body")?;
+        if self.contains_key("hashbang") {
+            module.hashbang = self.extract_string_opt("hashbang")?.map(|value| {
+                Hashbang { location: None, value: value }
+            });
+        }
+        Ok(module)
     }
 }
@@ -2,7 +2,6 @@ use easter::stmt::{Stmt, Block, ForHead, ForInHead, ForOfHead, StmtListItem, Cas
 use easter::decl::Decl;
 use easter::punc::Semi;
 use easter::patt::Patt;
-use easter::cover::IntoAssignPatt;
 use unjson::ty::Object;
 use unjson::{Unjson, ExtractField};
 
@@ -10,6 +9,8 @@ use tag::{Tag, TagOf};
 use decl::IntoConst;
 use expr::IntoExpr;
 use fun::IntoFun;
+use class::IntoClass;
+use patt::IntoPatt;
 use error::{Error, string_error, array_error, node_type_error};
 use result::Result;
 use node::ExtractNode;
@@ -65,13 +66,7 @@ impl IntoForInHead for Object {
                     (_, _, _) => { return string_error("var or let", kind); }
                 }
             }
-            _ => {
-                let expr = self.into_expr()?;
-                match expr.into_assign_patt() {
-                    Ok(patt) => ForInHead::Patt(patt),
-                    _ => { return Err(Error::InvalidLHS("left")); }
-                }
-            }
+            _ => ForInHead::Patt(self.into_assign_patt()?)
         })
     }
 }
@@ -98,13 +93,7 @@ impl IntoForOfHead for Object {
                     _ => { return string_error("var or let", kind); }
                 }
             },
-            _ => {
-                let expr = self.into_expr()?;
-                match expr.into_assign_patt() {
-                    Ok(patt) => ForOfHead::Patt(patt),
-                    _ => { return Err(Error::InvalidLHS("left")); }
-                }
-            }
+            _ => ForOfHead::Patt(self.into_assign_patt()?)
         })
     }
 }
@@ -121,11 +110,21 @@ fn into_stmt_list_item(mut this: Object, allow_decl: bool) -> Result<StmtListIte
     let tag = this.tag()?;
     Ok(StmtListItem::Stmt(match tag {
         Tag::FunctionDeclaration => {
+            let id = this.extract_id("id")?;
+            let fun = this.into_fun(id)?;
+            // Annex B allows a function declaration as an if clause or the
+            // body of a labelled statement.
+            if !allow_decl {
+                return Ok(StmtListItem::Stmt(Stmt::Fun(fun)));
+            }
+            return Ok(StmtListItem::Decl(Decl::Fun(fun)));
+        }
+        Tag::ClassDeclaration => {
             if !allow_decl {
                 return node_type_error("statement", tag);
             }
             let id = this.extract_id("id")?;
-            return Ok(StmtListItem::Decl(Decl::Fun(this.into_fun(id)?)));
+            return Ok(StmtListItem::Decl(Decl::Class(this.into_class(id)?)));
         }
         Tag::VariableDeclaration => {
             let dtors = this.extract_dtor_list("declarations")?;
@@ -188,7 +187,11 @@ fn into_stmt_list_item(mut this: Object, allow_decl: bool) -> Result<StmtListIte
             let left = this.extract_object("left")?.into_for_of_head()?;
             let right = this.extract_expr("right")?;
             let body = this.extract_stmt("body")?;
-            Stmt::ForOf(None, Box::new(left), right, Box::new(body))
+            if this.extract_flag("await")? {
+                Stmt::ForAwait(None, Box::new(left), right, Box::new(body))
+            } else {
+                Stmt::ForOf(None, Box::new(left), right, Box::new(body))
+            }
         }
         Tag::BlockStatement => {
             Stmt::Block(this.into_block()?)
@@ -259,7 +262,10 @@ impl IntoStmt for Object {
     }
 
     fn into_catch(mut self) -> Result<Catch> {
-        let param = self.extract_patt("param")?;
+        let param = match self.extract_object_opt("param")? {
+            Some(obj) => Some(obj.into_patt()?),
+            None => None
+        };
         let body = self.extract_block("body")?;
         Ok(Catch { location: None, param: param, body: body })
     }
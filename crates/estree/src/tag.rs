@@ -22,16 +22,36 @@ pub enum Tag {
     NewExpression,
     ArrayExpression,
     FunctionExpression,
+    ClassExpression,
+    ArrowFunctionExpression,
+    TemplateLiteral,
+    TaggedTemplateExpression,
     SequenceExpression,
     ObjectExpression,
     ConditionalExpression,
+    YieldExpression,
+    AwaitExpression,
+    ChainExpression,
     ThisExpression,
+    Super,
     MetaProperty,
+    ImportExpression,
+    PrivateIdentifier,
 
     SpreadElement,
     RestElement,
+    Property,
+
+    ArrayPattern,
+    ObjectPattern,
+    AssignmentPattern,
+
+    MethodDefinition,
+    PropertyDefinition,
+    StaticBlock,
 
     FunctionDeclaration,
+    ClassDeclaration,
     VariableDeclaration,
 
     EmptyStatement,
@@ -51,7 +71,29 @@ pub enum Tag {
     WithStatement,
     ThrowStatement,
     DebuggerStatement,
-    TryStatement
+    TryStatement,
+
+    ImportDeclaration,
+    ImportSpecifier,
+    ImportDefaultSpecifier,
+    ImportNamespaceSpecifier,
+
+    ExportNamedDeclaration,
+    ExportDefaultDeclaration,
+    ExportAllDeclaration,
+    ExportSpecifier,
+
+    JSXElement,
+    JSXFragment,
+    JSXIdentifier,
+    JSXNamespacedName,
+    JSXMemberExpression,
+    JSXAttribute,
+    JSXSpreadAttribute,
+    JSXExpressionContainer,
+    JSXEmptyExpression,
+    JSXSpreadChild,
+    JSXText
 }
 
 impl Display for Tag {
@@ -77,15 +119,33 @@ impl FromStr for Tag {
             "NewExpression"         => Tag::NewExpression,
             "ArrayExpression"       => Tag::ArrayExpression,
             "FunctionExpression"    => Tag::FunctionExpression,
+            "ClassExpression"       => Tag::ClassExpression,
+            "ArrowFunctionExpression" => Tag::ArrowFunctionExpression,
+            "TemplateLiteral"       => Tag::TemplateLiteral,
+            "TaggedTemplateExpression" => Tag::TaggedTemplateExpression,
             "SequenceExpression"    => Tag::SequenceExpression,
             "ObjectExpression"      => Tag::ObjectExpression,
             "ConditionalExpression" => Tag::ConditionalExpression,
+            "YieldExpression"       => Tag::YieldExpression,
+            "AwaitExpression"       => Tag::AwaitExpression,
+            "ChainExpression"       => Tag::ChainExpression,
             "ThisExpression"        => Tag::ThisExpression,
+            "Super"                 => Tag::Super,
             "MetaProperty"          => Tag::MetaProperty,
+            "ImportExpression"      => Tag::ImportExpression,
+            "PrivateIdentifier"     => Tag::PrivateIdentifier,
             "FunctionDeclaration"   => Tag::FunctionDeclaration,
+            "ClassDeclaration"      => Tag::ClassDeclaration,
             "VariableDeclaration"   => Tag::VariableDeclaration,
             "SpreadElement"         => Tag::SpreadElement,
             "RestElement"           => Tag::RestElement,
+            "Property"              => Tag::Property,
+            "ArrayPattern"          => Tag::ArrayPattern,
+            "ObjectPattern"         => Tag::ObjectPattern,
+            "AssignmentPattern"     => Tag::AssignmentPattern,
+            "MethodDefinition"      => Tag::MethodDefinition,
+            "PropertyDefinition"    => Tag::PropertyDefinition,
+            "StaticBlock"           => Tag::StaticBlock,
             "EmptyStatement"        => Tag::EmptyStatement,
             "ExpressionStatement"   => Tag::ExpressionStatement,
             "IfStatement"           => Tag::IfStatement,
@@ -104,6 +164,25 @@ impl FromStr for Tag {
             "ThrowStatement"        => Tag::ThrowStatement,
             "DebuggerStatement"     => Tag::DebuggerStatement,
             "TryStatement"          => Tag::TryStatement,
+            "ImportDeclaration"     => Tag::ImportDeclaration,
+            "ImportSpecifier"       => Tag::ImportSpecifier,
+            "ImportDefaultSpecifier" => Tag::ImportDefaultSpecifier,
+            "ImportNamespaceSpecifier" => Tag::ImportNamespaceSpecifier,
+            "ExportNamedDeclaration" => Tag::ExportNamedDeclaration,
+            "ExportDefaultDeclaration" => Tag::ExportDefaultDeclaration,
+            "ExportAllDeclaration"  => Tag::ExportAllDeclaration,
+            "ExportSpecifier"       => Tag::ExportSpecifier,
+            "JSXElement"            => Tag::JSXElement,
+            "JSXFragment"           => Tag::JSXFragment,
+            "JSXIdentifier"         => Tag::JSXIdentifier,
+            "JSXNamespacedName"     => Tag::JSXNamespacedName,
+            "JSXMemberExpression"   => Tag::JSXMemberExpression,
+            "JSXAttribute"          => Tag::JSXAttribute,
+            "JSXSpreadAttribute"    => Tag::JSXSpreadAttribute,
+            "JSXExpressionContainer" => Tag::JSXExpressionContainer,
+            "JSXEmptyExpression"    => Tag::JSXEmptyExpression,
+            "JSXSpreadChild"        => Tag::JSXSpreadChild,
+            "JSXText"               => Tag::JSXText,
             _ => { return Err(Error::InvalidTypeTag(String::from(s))); }
         })
     }
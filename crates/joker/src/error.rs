@@ -2,6 +2,8 @@ use std::error::Error as StdError;
 use std::fmt;
 use std::fmt::{Display, Formatter};
 use word::Reserved;
+use track::Span;
+use regexp::RegExpError;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Error {
@@ -10,6 +12,7 @@ pub enum Error {
     UnterminatedRegExp(Option<char>),
     MissingExponent(Option<char>),
     UnterminatedString(Option<char>),
+    UnterminatedTemplate,
     MissingBinaryDigits,
     MissingOctalDigits,
     MissingHexDigits,
@@ -18,16 +21,25 @@ pub enum Error {
     IllegalUnicode(u32),
     IdAfterNumber(char),
     DigitAfterNumber(char),
-    ReservedWordWithEscapes(Reserved)
+    RepeatedSeparator,
+    TrailingSeparator,
+    LeadingZeroSeparator,
+    ReservedWordWithEscapes(Reserved),
+    InvalidRegExp(Span, RegExpError),
+    #[cfg(feature = "jsx")]
+    IllegalJsxTextChar(char)
 }
 
 impl Display for Error {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         match *self {
+            #[cfg(feature = "jsx")]
+            Error::IllegalJsxTextChar(ref ch) => fmt.write_fmt(format_args!("{}: {:?}", self.description(), *ch)),
             Error::IllegalChar(ref ch)  |
             Error::InvalidDigit(ref ch) => fmt.write_fmt(format_args!("{}: {:?}", self.description(), *ch)),
             Error::ReservedWordWithEscapes(ref word) => fmt.write_fmt(format_args!("{}: {:?}", self.description(), word)),
             Error::IllegalUnicode(ref u) => fmt.write_fmt(format_args!("{}: \\u{{{:04x}}}", self.description(), u)),
+            Error::InvalidRegExp(_, ref err) => fmt.write_fmt(format_args!("{}: {}", self.description(), err)),
             _ => fmt.write_str(self.description()),
         }
     }
@@ -41,6 +53,7 @@ impl StdError for Error {
             Error::UnterminatedRegExp(_) => "unterminated regexp literal",
             Error::MissingExponent(_) => "missing exponent",
             Error::UnterminatedString(_) => "unterminated string",
+            Error::UnterminatedTemplate => "unterminated template literal",
             Error::MissingBinaryDigits => "missing binary digits",
             Error::MissingOctalDigits => "missing octal digits",
             Error::MissingHexDigits => "missing hex digits",
@@ -49,7 +62,13 @@ impl StdError for Error {
             Error::IllegalUnicode(_) => "illegal code unit",
             Error::IdAfterNumber(_) => "identifier starts immediately after numeric literal",
             Error::DigitAfterNumber(_) => "numeric literal starts immediately after previous numeric literal",
+            Error::RepeatedSeparator => "only one underscore is allowed as a numeric separator",
+            Error::TrailingSeparator => "numeric separator must be followed by a digit",
+            Error::LeadingZeroSeparator => "numeric separator after a leading zero",
             Error::ReservedWordWithEscapes(_) => "reserved word with escapes",
+            Error::InvalidRegExp(_, _) => "invalid regular expression",
+            #[cfg(feature = "jsx")]
+            Error::IllegalJsxTextChar(_) => "character must be escaped in JSX text",
         }
     }
 
@@ -1,11 +1,15 @@
 use std::char;
 
 use track::*;
-use token::{Token, TokenData, Exp, CharCase, Sign, NumberSource, Radix, StringLiteral, RegExpLiteral};
+use token::{Token, TokenData, Exp, CharCase, Sign, NumberSource, Radix, BigIntLiteral, StringLiteral, RegExpLiteral, TemplatePart};
 use word::{Map as WordMap, Word};
 
 use char::ESCharExt;
+use regexp;
+use regexp::RegExpError;
 use reader::Reader;
+#[cfg(feature = "jsx")]
+use jsx;
 use error::Error;
 use result::Result;
 use std::collections::VecDeque;
@@ -33,11 +37,26 @@ impl SpanTracker {
     }
 }
 
+struct Checkpoint {
+    lookahead: Vec<Token>,
+    cursor: usize,
+    posn: Posn
+}
+
 pub struct Lexer<I> {
     reader: Reader<I>,
     lookahead: VecDeque<Token>,
+    checkpoints: Vec<Checkpoint>,
+    replay: Vec<(Token, Posn)>, // tokens lexed since the outermost checkpoint
+    cursor: usize,              // the next token in replay to hand out again
+    replay_posn: Option<Posn>,  // the position to report while replaying
     wordmap: WordMap,
-    empty_line: bool
+    empty_line: bool,
+    html_comments: bool,
+    html_comment: Option<Span>,
+    decorators: bool,
+    regexp_annex_b: bool,
+    hashbang: Option<(Span, String)>
 }
 
 impl<I> Lexer<I> where I: Iterator<Item=char> {
@@ -47,16 +66,49 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
         Lexer {
             reader: Reader::new(chars),
             lookahead: VecDeque::with_capacity(2),
+            checkpoints: Vec::new(),
+            replay: Vec::new(),
+            cursor: 0,
+            replay_posn: None,
             wordmap: WordMap::new(),
-            empty_line: true
+            empty_line: true,
+            html_comments: true,
+            html_comment: None,
+            decorators: false,
+            regexp_annex_b: true,
+            hashbang: None
         }
     }
 
+    // Whether the Annex B "<!--" and "-->" comments are recognized. They are
+    // by default; the module goal and non-web hosts turn them off.
+    pub fn set_html_comments(&mut self, allow: bool) {
+        self.html_comments = allow;
+    }
+
+    // Whether regular expressions without the u flag use the Annex B
+    // pattern grammar. They do by default, in either goal.
+    pub fn set_regexp_annex_b(&mut self, allow: bool) {
+        self.regexp_annex_b = allow;
+    }
+
+    // Whether "@" is lexed as a punctuator, for decorators. Otherwise it's
+    // an illegal character, as in standard JavaScript.
+    pub fn set_decorators(&mut self, allow: bool) {
+        self.decorators = allow;
+    }
+
+    // The "<!--" or "-->" that started the first HTML-like comment, if
+    // any have been skipped so far.
+    pub fn html_comment(&self) -> Option<Span> {
+        self.html_comment
+    }
+
     // public methods
 
     pub fn peek_token(&mut self, operator: bool) -> Result<&Token> {
         if self.lookahead.is_empty() {
-            let token = self.read_next_token(operator)?;
+            let token = self.read_recorded_token(operator)?;
             self.lookahead.push_front(token);
         }
         Ok(self.lookahead.front().unwrap())
@@ -78,19 +130,87 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
     pub fn read_token(&mut self, operator: bool) -> Result<Token> {
         match self.lookahead.pop_front() {
             Some(token) => Ok(token),
-            None => self.read_next_token(operator)
+            None => self.read_recorded_token(operator)
         }
     }
 
+    // Starts a speculative parse. Every token lexed from here on is kept, so
+    // that rewind() can hand them all out again, in the form they were lexed
+    // and with the positions they were lexed at. Checkpoints can nest.
+    pub fn checkpoint(&mut self) {
+        let checkpoint = Checkpoint {
+            lookahead: self.lookahead.iter().cloned().collect(),
+            cursor: self.cursor,
+            posn: self.posn()
+        };
+        self.checkpoints.push(checkpoint);
+    }
+
+    // Ends the innermost speculative parse, keeping the tokens it consumed.
+    pub fn commit(&mut self) {
+        self.checkpoints.pop().expect("No checkpoint to commit");
+        self.trim_replay();
+    }
+
+    // Ends the innermost speculative parse, giving back the tokens it consumed.
+    pub fn rewind(&mut self) {
+        let checkpoint = self.checkpoints.pop().expect("No checkpoint to rewind to");
+        self.lookahead = VecDeque::from(checkpoint.lookahead);
+        self.lookahead.reserve(2);
+        self.cursor = checkpoint.cursor;
+        self.replay_posn = if self.cursor < self.replay.len() { Some(checkpoint.posn) } else { None };
+        self.trim_replay();
+    }
+
+    fn read_recorded_token(&mut self, operator: bool) -> Result<Token> {
+        if self.cursor < self.replay.len() {
+            let (token, posn) = self.replay[self.cursor].clone();
+            self.cursor += 1;
+            // The last token in the replay is the last one the reader lexed.
+            self.replay_posn = if self.cursor < self.replay.len() { Some(posn) } else { None };
+            self.trim_replay();
+            return Ok(token);
+        }
+        let token = self.read_next_token(operator)?;
+        if !self.checkpoints.is_empty() {
+            self.replay.push((token.clone(), self.reader.curr_posn()));
+            self.cursor += 1;
+        }
+        Ok(token)
+    }
+
+    // Forgets the replay once nothing can rewind into it or is still in it.
+    fn trim_replay(&mut self) {
+        if self.checkpoints.is_empty() && self.cursor == self.replay.len() {
+            self.replay.clear();
+            self.cursor = 0;
+        }
+    }
+
+    // The "#!" comment at the start of the input, if there was one, without
+    // the "#!". It's read along with the first token.
+    pub fn take_hashbang(&mut self) -> Option<(Span, String)> {
+        self.hashbang.take()
+    }
+
     pub fn unread_token(&mut self, token: Token) {
         debug_assert!(self.lookahead.len() < self.lookahead.capacity(), "Lookahead buffer is full");
         self.lookahead.push_front(token)
     }
 
+    // Resumes lexing a template literal after the '}' that closes a
+    // substitution. The '}' must be the most recently read token.
+    pub fn read_template_continuation(&mut self, rbrace: Token) -> Result<Token> {
+        debug_assert!(rbrace.value == TokenData::RBrace);
+        debug_assert!(self.lookahead.is_empty(), "Lookahead buffer is not empty");
+        let span = SpanTracker { start: rbrace.location.start };
+        self.read_template_chars(span, TokenData::TemplateTail, TokenData::TemplateMiddle)
+    }
+
     // source location
 
     pub fn posn(&self) -> Posn {
-        self.reader.curr_posn()
+        self.replay_posn.unwrap_or_else(|| self.reader.curr_posn())
     }
 
     fn start(&self) -> SpanTracker {
@@ -224,6 +344,25 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
         self.skip_until(&|ch| ch.is_es_newline());
     }
 
+    fn skip_html_comment(&mut self, prefix_len: usize) {
+        let start = self.posn();
+        for _ in 0..prefix_len {
+            self.skip();
+        }
+        if self.html_comment.is_none() {
+            self.html_comment = Some(Span { start: start, end: self.posn() });
+        }
+        self.skip_until(&|ch| ch.is_es_newline());
+    }
+
+    fn read_hashbang(&mut self) {
+        let start = self.posn();
+        self.skip2();
+        let mut s = String::new();
+        self.read_into_until(&mut s, &|ch| ch.is_es_newline());
+        self.hashbang = Some((Span { start: start, end: self.posn() }, s));
+    }
+
     fn skip_block_comment(&mut self) -> Result<bool> {
         self.skip2();
         let mut found_newline = false;
@@ -248,11 +387,23 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
         let mut s = String::new();
         self.reread('/');
         self.read_until_with(&|ch| ch == '/', &mut |this| { this.read_regexp_char(&mut s) })?;
+        if self.peek().is_none() {
+            return Err(Error::UnterminatedRegExp(None));
+        }
         self.reread('/');
+        let flags_start = self.posn();
         let flags = self.read_word_parts()?;
+        if flags.had_escape() {
+            let location = Span { start: flags_start, end: self.posn() };
+            return Err(Error::InvalidRegExp(location, RegExpError::EscapedFlag));
+        }
+        let flags: Vec<char> = flags.text.chars().collect();
+        let unicode = regexp::parse_flags(&flags, flags_start)?.unicode;
+        let pattern_start = Posn { offset: span.start.offset + 1, line: span.start.line, column: span.start.column + 1 };
+        regexp::parse_pattern(&s, unicode, self.regexp_annex_b, pattern_start)?;
         Ok(span.end(self, TokenData::RegExp(RegExpLiteral {
             pattern: s,
-            flags: flags.text.chars().collect()
+            flags: flags
         })))
     }
 
@@ -278,6 +429,9 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
     fn read_regexp_class(&mut self, s: &mut String) -> Result<()> {
         s.push(self.reread('['));
         self.read_until_with(&|ch| ch == ']', &mut |this| { this.read_regexp_class_char(s) })?;
+        if self.peek().is_none() {
+            return Err(Error::UnterminatedRegExp(None));
+        }
         s.push(self.reread(']'));
         Ok(())
     }
@@ -285,15 +439,34 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
     fn read_regexp_class_char(&mut self, s: &mut String) -> Result<()> {
         match self.peek() {
             Some('\\') => self.read_regexp_backslash(s),
+            Some(ch) if ch.is_es_newline() => Err(Error::UnterminatedRegExp(Some(ch))),
             Some(ch) => { s.push(self.reread(ch)); Ok(()) }
             None => Err(Error::UnterminatedRegExp(None))
         }
     }
 
-    fn read_decimal_digits(&mut self) -> String {
+    // Reads the remaining digits of a numeric literal, dropping any numeric
+    // separators. A separator must sit between two digits.
+    fn read_digits_into<F>(&mut self, s: &mut String, pred: &F) -> Result<()>
+      where F: Fn(char) -> bool
+    {
+        loop {
+            self.read_into_until(s, &|ch| !pred(ch));
+            if s.is_empty() || self.peek() != Some('_') {
+                return Ok(());
+            }
+            match self.reader.peek(1) {
+                Some('_') => { return Err(Error::RepeatedSeparator); }
+                Some(ch) if pred(ch) => { self.skip(); }
+                _ => { return Err(Error::TrailingSeparator); }
+            }
+        }
+    }
+
+    fn read_decimal_digits(&mut self) -> Result<String> {
         let mut s = String::new();
-        self.read_into_until(&mut s, &|ch| !ch.is_es_dec_digit());
-        s
+        self.read_digits_into(&mut s, &|ch| ch.is_es_dec_digit())?;
+        Ok(s)
     }
 
     fn read_exp_part(&mut self) -> Result<Option<Exp>> {
@@ -313,50 +486,57 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
             None => { return Err(Error::MissingExponent(None)); }
             _ => ()
         }
-        Ok(Some(Exp { e: e, sign: sign, value: self.read_decimal_digits() }))
+        Ok(Some(Exp { e: e, sign: sign, value: self.read_decimal_digits()? }))
     }
 
-    fn read_decimal_int(&mut self) -> String {
+    fn read_decimal_int(&mut self) -> Result<String> {
+        if self.peek() != Some('0') {
+            return self.read_decimal_digits();
+        }
+        // Separators are not allowed in a literal with a leading zero:
+        // 0 itself, legacy octals, or NonOctalDecimalIntegerLiterals.
         let mut s = String::new();
         self.read_into_until(&mut s, &|ch| !ch.is_es_dec_digit());
-        s
+        if self.peek() == Some('_') {
+            return Err(Error::LeadingZeroSeparator);
+        }
+        Ok(s)
     }
 
-    fn read_radix_int<F, G>(&mut self, radix: u32, pred: &F, cons: &G, missing_digits: Error) -> Result<Token>
+    fn read_radix_int<F, G>(&mut self, pred: &F, cons: &G, missing_digits: Error) -> Result<Token>
       where F: Fn(char) -> bool,
-            G: Fn(CharCase, String) -> TokenData
+            G: Fn(CharCase) -> Radix
     {
         debug_assert!(self.peek() == Some('0'));
         debug_assert!(self.reader.peek(1).map_or(false, |ch| ch.is_alphabetic()));
         let span = self.start();
         let mut s = String::new();
         self.skip();
-        let flag = if self.read().is_lowercase() {
+        let radix = cons(if self.read().is_lowercase() {
             CharCase::LowerCase
         } else {
             CharCase::UpperCase
+        });
+        self.read_digit_into(&mut s, radix.value(), pred, missing_digits)?;
+        self.read_digits_into(&mut s, pred)?;
+        let value = if self.matches('n') {
+            TokenData::BigInt(BigIntLiteral { radix: Some(radix), digits: s })
+        } else {
+            NumberSource::RadixInt(radix, s).into_token_data()
         };
-        self.read_digit_into(&mut s, radix, pred, missing_digits)?;
-        self.read_into_until(&mut s, &|ch| !pred(ch));
-        Ok(span.end(self, cons(flag, s)))
+        Ok(span.end(self, value))
     }
 
     fn read_hex_int(&mut self) -> Result<Token> {
-        self.read_radix_int(16, &|ch| ch.is_es_hex_digit(), &|cc, s| {
-            NumberSource::RadixInt(Radix::Hex(cc), s).into_token_data()
-        }, Error::MissingHexDigits)
+        self.read_radix_int(&|ch| ch.is_es_hex_digit(), &Radix::Hex, Error::MissingHexDigits)
     }
 
     fn read_oct_int(&mut self) -> Result<Token> {
-        self.read_radix_int(8, &|ch| ch.is_es_oct_digit(), &|cc, s| {
-            NumberSource::RadixInt(Radix::Oct(Some(cc)), s).into_token_data()
-        }, Error::MissingOctalDigits)
+        self.read_radix_int(&|ch| ch.is_es_oct_digit(), &|cc| Radix::Oct(Some(cc)), Error::MissingOctalDigits)
     }
 
     fn read_bin_int(&mut self) -> Result<Token> {
-        self.read_radix_int(2, &|ch| ch.is_es_bin_digit(), &|cc, s| {
-            NumberSource::RadixInt(Radix::Bin(cc), s).into_token_data()
-        }, Error::MissingBinaryDigits)
+        self.read_radix_int(&|ch| ch.is_es_bin_digit(), &Radix::Bin, Error::MissingBinaryDigits)
     }
 
     fn read_number(&mut self) -> Result<Token> {
@@ -367,20 +547,22 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
             (Some('.'), _) => {
                 let span = self.start();
                 self.skip();
-                let frac = self.read_decimal_digits();
+                let frac = self.read_decimal_digits()?;
                 let exp = self.read_exp_part()?;
                 Ok(span.end(self, NumberSource::Float(None, Some(frac), exp).into_token_data()))
             }
             (Some(ch), _) => {
                 debug_assert!(ch.is_es_dec_digit());
                 let span = self.start();
-                let s = self.read_decimal_int();
+                let s = self.read_decimal_int()?;
                 let value = if ch == '0' && s.len() > 1 && s.chars().skip(1).all(|ch| ch.is_es_oct_digit()) {
-                    NumberSource::RadixInt(Radix::Oct(None), s)
+                    NumberSource::RadixInt(Radix::Oct(None), s).into_token_data()
+                } else if (ch != '0' || s.len() == 1) && self.matches('n') {
+                    TokenData::BigInt(BigIntLiteral { radix: None, digits: s })
                 } else {
                     let (dot, frac) = if self.matches('.') {
                         (true, Some(match self.peek() {
-                            Some(ch) if ch.is_es_dec_digit() => self.read_decimal_digits(),
+                            Some(ch) if ch.is_es_dec_digit() => self.read_decimal_digits()?,
                             _ => String::from("")
                         }))
                     } else {
@@ -388,12 +570,12 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
                     };
                     let exp = self.read_exp_part()?;
                     if dot {
-                        NumberSource::Float(Some(s), frac, exp)
+                        NumberSource::Float(Some(s), frac, exp).into_token_data()
                     } else {
-                        NumberSource::DecimalInt(s, exp)
+                        NumberSource::DecimalInt(s, exp).into_token_data()
                     }
                 };
-                Ok(span.end(self, value.into_token_data()))
+                Ok(span.end(self, value))
             }
             (None, _) => { panic!("read_number() called at EOF"); }
         }?;
@@ -490,8 +672,9 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
             }
             Some('u') => {
                 source.push(self.reread('u'));
+                // A lone surrogate can't be stored in a Rust string.
                 let code = self.read_unicode_escape_seq(source)?;
-                value.push(char::from_u32(code).unwrap_or('?'));
+                value.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
             }
             Some(ch) if ch.is_es_newline() => {
                 self.read_newline_into(source);
@@ -505,6 +688,91 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
         Ok(())
     }
 
+    fn read_template(&mut self) -> Result<Token> {
+        let span = self.start();
+        self.reread('`');
+        self.read_template_chars(span, TokenData::NoSubstTemplate, TokenData::TemplateHead)
+    }
+
+    fn read_template_chars<F, G>(&mut self, span: SpanTracker, end: F, subst: G) -> Result<Token>
+      where F: FnOnce(TemplatePart) -> TokenData,
+            G: FnOnce(TemplatePart) -> TokenData
+    {
+        let mut raw = String::new();
+        let mut cooked = Some(String::new());
+        let value = loop {
+            match self.peek2() {
+                (Some('`'), _) => {
+                    self.skip();
+                    break end(TemplatePart { cooked: cooked, raw: raw });
+                }
+                (Some('$'), Some('{')) => {
+                    self.skip2();
+                    break subst(TemplatePart { cooked: cooked, raw: raw });
+                }
+                (Some('\\'), _) => { self.read_template_escape(&mut raw, &mut cooked); }
+                (Some(ch), _) => {
+                    let ch = if ch.is_es_newline() { self.read_template_newline() } else { self.reread(ch) };
+                    raw.push(ch);
+                    if let Some(ref mut s) = cooked {
+                        s.push(ch);
+                    }
+                }
+                (None, _) => { return Err(Error::UnterminatedTemplate); }
+            }
+        };
+        Ok(span.end(self, value))
+    }
+
+    // 11.8.6.1 Static Semantics: TV and TRV
+    // Both <CR><LF> and <CR> are normalized to <LF>.
+    fn read_template_newline(&mut self) -> char {
+        match self.read() {
+            '\r' => { self.matches('\n'); '\n' }
+            ch => ch
+        }
+    }
+
+    // An invalid escape leaves the cooked string undefined, which is only
+    // allowed in tagged templates.
+    fn read_template_escape(&mut self, raw: &mut String, cooked: &mut Option<String>) {
+        raw.push(self.reread('\\'));
+        let value = match self.peek() {
+            Some('0') if !self.reader.peek(1).map_or(false, |ch| ch.is_es_dec_digit()) => {
+                raw.push(self.reread('0'));
+                Some('\0')
+            }
+            Some(ch) if ch.is_es_dec_digit() => None,
+            Some('x') => {
+                raw.push(self.reread('x'));
+                match (self.read_hex_digit_into(raw), self.read_hex_digit_into(raw)) {
+                    (Ok(hi), Ok(lo)) => char::from_u32((hi << 4) + lo),
+                    _ => None
+                }
+            }
+            Some('u') => {
+                raw.push(self.reread('u'));
+                match self.read_unicode_escape_seq(raw) {
+                    Ok(code) if code <= 0x10ffff => Some(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER)),
+                    _ => None
+                }
+            }
+            Some(ch) if ch.is_es_newline() => {
+                raw.push(self.read_template_newline());
+                return;
+            }
+            Some(ch) => {
+                raw.push(self.reread(ch));
+                Some(ch.unescape())
+            }
+            None => { return; } // error will be reported from caller
+        };
+        match value {
+            Some(ch) => { if let Some(ref mut s) = *cooked { s.push(ch); } }
+            None => { *cooked = None; }
+        }
+    }
+
     fn read_digit_into<F>(&mut self, s: &mut String, radix: u32, pred: &F, missing_digits: Error) -> Result<u32>
       where F: Fn(char) -> bool
     {
@@ -542,6 +810,14 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
         Ok(span.end(self, self.wordmap.tokenize(s)?))
     }
 
+    fn read_private_name(&mut self) -> Result<Token> {
+        debug_assert!(self.peek() == Some('#'));
+        let span = self.start();
+        self.skip();
+        let s = self.read_word_parts()?;
+        Ok(span.end(self, TokenData::PrivateName(s.text)))
+    }
+
     fn read_word_escape(&mut self, s: &mut Word) -> Result<()> {
         s.set_had_escape();
         match self.peek() {
@@ -586,6 +862,11 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
         let mut pair;
         let mut found_newline = false;
 
+        // A hashbang comment is only allowed at the very start of the input.
+        if self.posn().offset == 0 && self.peek2() == (Some('#'), Some('!')) {
+            self.read_hashbang();
+        }
+
         // Skip whitespace and comments.
         loop {
             pair = self.peek2();
@@ -600,16 +881,16 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
                 (Some('/'), Some('*')) => {
                     found_newline = self.skip_block_comment()? || found_newline;
                 }
-                (Some('<'), Some('!')) => {
+                (Some('<'), Some('!')) if self.html_comments => {
                     if self.reader.peek(2) == Some('-') && self.reader.peek(3) == Some('-') {
-                        self.skip_line_comment(4);
+                        self.skip_html_comment(4);
                     } else {
                         break;
                     }
                 }
-                (Some('-'), Some('-')) => {
+                (Some('-'), Some('-')) if self.html_comments => {
                     if self.empty_line && self.reader.peek(2) == Some('>') {
-                        self.skip_line_comment(3);
+                        self.skip_html_comment(3);
                     } else {
                         break;
                     }
@@ -665,6 +946,7 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
             (Some('='), Some('='))                       => {
                 Ok(self.read_punc2_3('=', TokenData::Eq, TokenData::StrictEq))
             }
+            (Some('='), Some('>'))                       => Ok(self.read_punc2(TokenData::Arrow)),
             (Some('='), _)                               => Ok(self.read_punc(TokenData::Assign)),
             (Some('+'), Some('+'))                       => Ok(self.read_punc2(TokenData::Inc)),
             (Some('+'), Some('='))                       => {
@@ -676,6 +958,9 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
                 Ok(self.read_punc2(TokenData::MinusAssign))
             }
             (Some('-'), _)                               => Ok(self.read_punc(TokenData::Minus)),
+            (Some('*'), Some('*'))                       => {
+                Ok(self.read_punc2_3('=', TokenData::StarStar, TokenData::StarStarAssign))
+            }
             (Some('*'), Some('='))                       => {
                 Ok(self.read_punc2(TokenData::StarAssign))
             }
@@ -689,14 +974,14 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
             }
             (Some('^'), _)                               => Ok(self.read_punc(TokenData::BitXor)),
             (Some('&'), Some('&'))                       => {
-                Ok(self.read_punc2(TokenData::LogicalAnd))
+                Ok(self.read_punc2_3('=', TokenData::LogicalAnd, TokenData::LogicalAndAssign))
             }
             (Some('&'), Some('='))                       => {
                 Ok(self.read_punc2(TokenData::BitAndAssign))
             }
             (Some('&'), _)                               => Ok(self.read_punc(TokenData::BitAnd)),
             (Some('|'), Some('|'))                       => {
-                Ok(self.read_punc2(TokenData::LogicalOr))
+                Ok(self.read_punc2_3('=', TokenData::LogicalOr, TokenData::LogicalOrAssign))
             }
             (Some('|'), Some('='))                       => {
                 Ok(self.read_punc2(TokenData::BitOrAssign))
@@ -707,11 +992,24 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
                 Ok(self.read_punc2_3('=', TokenData::NEq, TokenData::StrictNEq))
             }
             (Some('!'), _)                               => Ok(self.read_punc(TokenData::Bang)),
+            (Some('?'), Some('?'))                       => {
+                Ok(self.read_punc2_3('=', TokenData::Coalesce, TokenData::CoalesceAssign))
+            }
+            (Some('?'), Some('.'))                       => {
+                // "?." followed by a digit is a conditional: a?.5:b
+                Ok(match self.reader.peek(2) {
+                    Some(ch) if ch.is_es_dec_digit() => self.read_punc(TokenData::Question),
+                    _ => self.read_punc2(TokenData::QuestionDot)
+                })
+            }
             (Some('?'), _)                               => Ok(self.read_punc(TokenData::Question)),
             (Some('"'), _) | (Some('\''), _)             => self.read_string(),
+            (Some('`'), _)                               => self.read_template(),
             (Some(ch), _) if ch.is_es_dec_digit()        => self.read_number(),
             (Some(ch), _) if ch.is_es_identifier_start() => self.read_word(),
             (Some('\\'), _)                              => self.read_word(),
+            (Some('#'), Some(ch)) if ch == '\\' || ch.is_es_identifier_start() => self.read_private_name(),
+            (Some('@'), _) if self.decorators            => Ok(self.read_punc(TokenData::At)),
             (Some(ch), _)                                => Err(Error::IllegalChar(ch)),
             (None, _)                                    => {
                 let here = self.posn();
@@ -723,6 +1021,120 @@ impl<I> Lexer<I> where I: Iterator<Item=char> {
     }
 }
 
+// JSX lexing modes. Inside a JSX element the parser picks the mode for each
+// token, switching back to the ordinary mode for the expressions in braces.
+// The lookahead buffer is shared, so the parser must only switch modes when
+// it's empty or holds a token that reads the same in both.
+#[cfg(feature = "jsx")]
+impl<I> Lexer<I> where I: Iterator<Item=char> {
+    // A token inside a tag: a name, a string, or punctuation.
+    pub fn peek_jsx_tag_token(&mut self) -> Result<&Token> {
+        if self.lookahead.is_empty() {
+            let token = self.read_next_jsx_tag_token()?;
+            self.lookahead.push_front(token);
+        }
+        Ok(self.lookahead.front().unwrap())
+    }
+
+    pub fn read_jsx_tag_token(&mut self) -> Result<Token> {
+        match self.lookahead.pop_front() {
+            Some(token) => Ok(token),
+            None => self.read_next_jsx_tag_token()
+        }
+    }
+
+    // A token between the tags of an element: text, "<", or "{".
+    pub fn read_jsx_child_token(&mut self) -> Result<Token> {
+        debug_assert!(self.lookahead.is_empty(), "Lookahead buffer is not empty");
+        let span = self.start();
+        match self.peek() {
+            Some('<') => Ok(self.read_punc(TokenData::LAngle)),
+            Some('{') => Ok(self.read_punc(TokenData::LBrace)),
+            Some(_) => {
+                let mut raw = String::new();
+                self.read_into_until(&mut raw, &|ch| ch == '<' || ch == '{' || ch == '>' || ch == '}');
+                match self.peek() {
+                    Some(ch @ '>') | Some(ch @ '}') => { return Err(Error::IllegalJsxTextChar(ch)); }
+                    _ => ()
+                }
+                let value = jsx::decode_entities(&raw);
+                Ok(span.end(self, TokenData::JsxText(StringLiteral {
+                    source: Some(raw),
+                    value: value
+                })))
+            }
+            None => Ok(span.end(self, TokenData::EOF))
+        }
+    }
+
+    fn read_next_jsx_tag_token(&mut self) -> Result<Token> {
+        let mut found_newline = false;
+        loop {
+            match self.peek2() {
+                (Some(ch), _) if ch.is_es_whitespace() => { self.skip_whitespace(); }
+                (Some(ch), _) if ch.is_es_newline() => {
+                    self.skip_newlines();
+                    found_newline = true;
+                }
+                (Some('/'), Some('/')) => { self.skip_line_comment(2); }
+                (Some('/'), Some('*')) => {
+                    found_newline = self.skip_block_comment()? || found_newline;
+                }
+                _ => { break; }
+            }
+        }
+
+        let mut result = match self.peek() {
+            Some('<')                                => Ok(self.read_punc(TokenData::LAngle)),
+            Some('>')                                => Ok(self.read_punc(TokenData::RAngle)),
+            Some('/')                                => Ok(self.read_punc(TokenData::Slash)),
+            Some('{')                                => Ok(self.read_punc(TokenData::LBrace)),
+            Some('=')                                => Ok(self.read_punc(TokenData::Assign)),
+            Some(':')                                => Ok(self.read_punc(TokenData::Colon)),
+            Some('.')                                => Ok(self.read_punc(TokenData::Dot)),
+            Some('"') | Some('\'')                   => self.read_jsx_string(),
+            Some(ch) if ch.is_es_identifier_start()  => Ok(self.read_jsx_name()),
+            Some(ch)                                 => Err(Error::IllegalChar(ch)),
+            None                                     => {
+                let here = self.posn();
+                Ok(Token::new(here, here, TokenData::EOF))
+            }
+        }?;
+        result.newline = found_newline;
+        Ok(result)
+    }
+
+    // JSXIdentifier ::= IdentifierStart (IdentifierPart | "-")*
+    fn read_jsx_name(&mut self) -> Token {
+        let span = self.start();
+        let mut s = String::new();
+        s.push(self.read());
+        self.read_into_until(&mut s, &|ch| ch != '-' && !ch.is_es_identifier_continue());
+        span.end(self, TokenData::JsxName(s))
+    }
+
+    // Unlike JavaScript strings, JSX strings can span lines and have no
+    // escape sequences.
+    fn read_jsx_string(&mut self) -> Result<Token> {
+        let span = self.start();
+        let quote = self.read();
+        let mut raw = String::new();
+        self.read_into_until(&mut raw, &|ch| ch == quote);
+        if !self.matches(quote) {
+            return Err(Error::UnterminatedString(None));
+        }
+        let value = jsx::decode_entities(&raw);
+        let mut source = String::with_capacity(raw.len() + 2);
+        source.push(quote);
+        source.push_str(&raw);
+        source.push(quote);
+        Ok(span.end(self, TokenData::JsxString(StringLiteral {
+            source: Some(source),
+            value: value
+        })))
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -730,6 +1142,9 @@ mod tests {
     use lexer::Lexer;
     use result::Result;
     use token::{Token, TokenData};
+    #[cfg(feature = "jsx")]
+    use token::StringLiteral;
+    use word::Name;
     use std;
 
     fn lex2(source: &String, operator: bool) -> Result<(Token, Token)> {
@@ -771,4 +1186,138 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn hashbang() {
+        let mut lexer = Lexer::new("#!/usr/bin/env node\nx".chars());
+        assert_eq!(lexer.read_token(false).unwrap().value, TokenData::Identifier(Name::String("x".to_string())));
+        assert_eq!(lexer.take_hashbang().map(|(_, value)| value), Some("/usr/bin/env node".to_string()));
+        assert_eq!(lexer.take_hashbang(), None);
+
+        let mut lexer = Lexer::new("x #!".chars());
+        assert!(lexer.read_token(false).is_ok());
+        assert!(lexer.read_token(false).is_err());
+        assert_eq!(lexer.take_hashbang(), None);
+    }
+
+    #[test]
+    pub fn html_comments() {
+        let mut lexer = Lexer::new("x <!-- y".chars());
+        assert!(lexer.read_token(false).is_ok());
+        assert_eq!(lexer.read_token(true).unwrap().value, TokenData::EOF);
+        assert!(lexer.html_comment().is_some());
+
+        let mut lexer = Lexer::new("x <!-- y".chars());
+        lexer.set_html_comments(false);
+        assert!(lexer.read_token(false).is_ok());
+        assert_eq!(lexer.read_token(true).unwrap().value, TokenData::LAngle);
+        assert_eq!(lexer.html_comment(), None);
+    }
+
+    #[test]
+    pub fn decorators() {
+        let mut lexer = Lexer::new("@a".chars());
+        assert!(lexer.read_token(false).is_err());
+
+        let mut lexer = Lexer::new("@a".chars());
+        lexer.set_decorators(true);
+        assert_eq!(lexer.read_token(false).unwrap().value, TokenData::At);
+        assert_eq!(lexer.read_token(false).unwrap().value, TokenData::Identifier(Name::String("a".to_string())));
+    }
+
+    #[test]
+    pub fn regexp_annex_b() {
+        let extended = ["/{/", "/]/", "/a{/", "/\\c/", "/\\1/", "/\\a/", "/(?=a){2}/", "/[\\d-a]/", "/[\\1]/", "/\\07/", "/\\x/", "/\\u/"];
+        let standard = ["/a{2}/", "/(a)\\1/", "/\\cJ/", "/\\0/", "/\\$/", "/\\-/", "/(?=a)/", "/[a-z\\d]/", "/\\u0041\\x41/"];
+        for source in extended.iter() {
+            assert!(Lexer::new(source.chars()).read_token(false).is_ok(), "{}", source);
+            let mut lexer = Lexer::new(source.chars());
+            lexer.set_regexp_annex_b(false);
+            assert!(lexer.read_token(false).is_err(), "{}", source);
+        }
+        for source in standard.iter() {
+            let mut lexer = Lexer::new(source.chars());
+            lexer.set_regexp_annex_b(false);
+            assert!(lexer.read_token(false).is_ok(), "{}", source);
+        }
+    }
+
+    #[test]
+    pub fn regexp_property_escapes() {
+        let valid = ["/\\p{L}/u", "/\\P{Lu}/u", "/\\p{gc=Nd}/u", "/\\p{ASCII}/u", "/\\p{Script=Greek}/u",
+                     "/\\p{sc=Grek}/u", "/\\p{Script_Extensions=Latin}/u", "/\\P{scx=Zyyy}/u", "/[\\p{sc=Qaai}]/u"];
+        let invalid = ["/\\p{Greek}/u", "/\\p{Script=Greece}/u", "/\\p{sc=greek}/u", "/\\p{scx=}/u",
+                       "/\\p{Script=Lu}/u", "/\\p{gc=Greek}/u", "/\\p{Alpha=Greek}/u"];
+        for source in valid.iter() {
+            assert!(Lexer::new(source.chars()).read_token(false).is_ok(), "{}", source);
+        }
+        for source in invalid.iter() {
+            assert!(Lexer::new(source.chars()).read_token(false).is_err(), "{}", source);
+        }
+    }
+
+    #[test]
+    pub fn rewind() {
+        let mut lexer = Lexer::new("f<a>(b)".chars());
+        assert!(lexer.read_token(false).is_ok());
+        assert_eq!(lexer.peek_token(true).unwrap().value, TokenData::LAngle);
+        let posn = lexer.posn();
+        lexer.checkpoint();
+        assert_eq!(lexer.read_token(true).unwrap().value, TokenData::LAngle);
+        assert!(lexer.read_token(false).is_ok());
+        assert_eq!(lexer.peek_token(true).unwrap().value, TokenData::RAngle);
+        lexer.rewind();
+        assert_eq!(lexer.posn(), posn);
+        assert_eq!(lexer.read_token(true).unwrap().value, TokenData::LAngle);
+        assert_eq!(lexer.read_token(false).unwrap().value, TokenData::Identifier(Name::String("a".to_string())));
+        lexer.checkpoint();
+        assert_eq!(lexer.read_token(true).unwrap().value, TokenData::RAngle);
+        lexer.commit();
+        assert_eq!(lexer.read_token(false).unwrap().value, TokenData::LParen);
+    }
+
+    #[test]
+    pub fn lone_surrogates() {
+        let mut lexer = Lexer::new("'\\uD800' `a\\uD800b`".chars());
+        match lexer.read_token(false).unwrap().value {
+            TokenData::String(ref lit) => { assert_eq!(lit.value, "\u{fffd}"); }
+            token => panic!("expected a string, got {:?}", token)
+        }
+        match lexer.read_token(false).unwrap().value {
+            TokenData::NoSubstTemplate(ref part) => {
+                assert_eq!(part.cooked, Some("a\u{fffd}b".to_string()));
+                assert_eq!(part.raw, "a\\uD800b");
+            }
+            token => panic!("expected a template, got {:?}", token)
+        }
+    }
+
+    #[cfg(feature = "jsx")]
+    #[test]
+    pub fn jsx() {
+        fn text(source: &str, value: &str) -> TokenData {
+            TokenData::JsxText(StringLiteral { source: Some(source.to_string()), value: value.to_string() })
+        }
+
+        let mut lexer = Lexer::new("<a-b c='&lt;&#x41;'>x &amp;&bogus;{y}</a-b>".chars());
+        assert_eq!(lexer.read_token(false).unwrap().value, TokenData::LAngle);
+        assert_eq!(lexer.read_jsx_tag_token().unwrap().value, TokenData::JsxName("a-b".to_string()));
+        assert_eq!(lexer.peek_jsx_tag_token().unwrap().value, TokenData::JsxName("c".to_string()));
+        assert_eq!(lexer.read_jsx_tag_token().unwrap().value, TokenData::JsxName("c".to_string()));
+        assert_eq!(lexer.read_jsx_tag_token().unwrap().value, TokenData::Assign);
+        match lexer.read_jsx_tag_token().unwrap().value {
+            TokenData::JsxString(literal) => { assert_eq!(literal.value, "<A"); }
+            value => { panic!("expected JSX string, got {:?}", value); }
+        }
+        assert_eq!(lexer.read_jsx_tag_token().unwrap().value, TokenData::RAngle);
+        assert_eq!(lexer.read_jsx_child_token().unwrap().value, text("x &amp;&bogus;", "x &&bogus;"));
+        assert_eq!(lexer.read_jsx_child_token().unwrap().value, TokenData::LBrace);
+        assert_eq!(lexer.read_token(false).unwrap().value, TokenData::Identifier(Name::String("y".to_string())));
+        assert_eq!(lexer.read_token(false).unwrap().value, TokenData::RBrace);
+        assert_eq!(lexer.read_jsx_child_token().unwrap().value, TokenData::LAngle);
+        assert_eq!(lexer.read_jsx_tag_token().unwrap().value, TokenData::Slash);
+
+        let mut lexer = Lexer::new("a > b".chars());
+        assert!(lexer.read_jsx_child_token().is_err());
+    }
+
 }
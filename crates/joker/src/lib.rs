@@ -13,6 +13,9 @@ extern crate serde_json;
 pub mod word;
 pub mod token;
 pub mod lexer;
+pub mod regexp;
+#[cfg(feature = "jsx")]
+mod jsx;
 mod char;
 mod reader;
 mod test;
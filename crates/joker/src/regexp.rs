@@ -0,0 +1,965 @@
+// 21.2.1 Patterns
+//
+// The lexer only finds the end of a regular expression literal, so this
+// module parses the body and flags separately, both to validate them and to
+// produce an AST for clients that want to look inside patterns. Patterns are
+// parsed differently depending on the u flag: without it, the more lenient
+// grammar of Annex B.1.4 applies.
+
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::error::Error as StdError;
+
+use ucd::Codepoint;
+use track::{Posn, Span};
+use char::ESCharExt;
+use error::Error;
+use result::Result;
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy, Default)]
+pub struct Flags {
+    pub has_indices: bool, // d
+    pub global: bool,      // g
+    pub ignore_case: bool, // i
+    pub multiline: bool,   // m
+    pub dot_all: bool,     // s
+    pub unicode: bool,     // u
+    pub sticky: bool       // y
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Pattern {
+    pub disjunction: Disjunction,
+    pub groups: u32 // number of capturing groups
+}
+
+pub type Disjunction = Vec<Alternative>;
+
+pub type Alternative = Vec<Term>;
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Term {
+    Assertion(Assertion),
+    Atom(Atom, Option<Quantifier>),
+    QuantifiedAssertion(Assertion, Quantifier) // Annex B: a quantified lookahead, only without the u flag
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Assertion {
+    Start,
+    End,
+    WordBoundary,
+    NotWordBoundary,
+    Lookahead(bool, Disjunction), // negated?
+    Lookbehind(bool, Disjunction) // negated?
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum Atom {
+    Char(u32), // a code point, or a code unit for a lone surrogate
+    Dot,
+    Class(Class),
+    Escape(ClassEscape),
+    Backreference(u32),
+    NamedBackreference(String),
+    Group(Option<String>, Disjunction), // capturing, with an optional name
+    NonCapturingGroup(Disjunction)
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Quantifier {
+    pub min: u32,
+    pub max: Option<u32>, // None is unbounded
+    pub greedy: bool
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Class {
+    pub negated: bool,
+    pub ranges: Vec<ClassRange>
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ClassRange {
+    Char(u32),
+    Range(u32, u32),
+    Escape(ClassEscape)
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ClassEscape {
+    Digit(bool), // negated?
+    Space(bool), // negated?
+    Word(bool),  // negated?
+    Property(bool, String, Option<String>) // negated?, name or lone value, value
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RegExpError {
+    InvalidFlag(char),
+    RepeatedFlag(char),
+    EscapedFlag,
+    NothingToRepeat,
+    UnmatchedParen,
+    UnterminatedGroup,
+    UnterminatedClass,
+    InvalidGroup,
+    InvalidEscape,
+    InvalidUnicodeEscape,
+    InvalidBackreference,
+    InvalidGroupName,
+    DuplicateGroupName,
+    UndefinedGroupName,
+    IncompleteQuantifier,
+    QuantifierOutOfOrder,
+    RangeOutOfOrder,
+    InvalidClassRange,
+    InvalidProperty,
+    LoneBrace,
+    LoneBracket
+}
+
+impl Display for RegExpError {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        match *self {
+            RegExpError::InvalidFlag(ch)
+          | RegExpError::RepeatedFlag(ch) => fmt.write_fmt(format_args!("{}: {:?}", self.description(), ch)),
+            _ => fmt.write_str(self.description())
+        }
+    }
+}
+
+impl StdError for RegExpError {
+    fn description(&self) -> &str {
+        match *self {
+            RegExpError::InvalidFlag(_) => "invalid regexp flag",
+            RegExpError::RepeatedFlag(_) => "repeated regexp flag",
+            RegExpError::EscapedFlag => "escape sequence in regexp flags",
+            RegExpError::NothingToRepeat => "nothing to repeat",
+            RegExpError::UnmatchedParen => "unmatched ')'",
+            RegExpError::UnterminatedGroup => "unterminated group",
+            RegExpError::UnterminatedClass => "unterminated character class",
+            RegExpError::InvalidGroup => "invalid group",
+            RegExpError::InvalidEscape => "invalid escape",
+            RegExpError::InvalidUnicodeEscape => "invalid unicode escape",
+            RegExpError::InvalidBackreference => "backreference to a nonexistent group",
+            RegExpError::InvalidGroupName => "invalid capture group name",
+            RegExpError::DuplicateGroupName => "duplicate capture group name",
+            RegExpError::UndefinedGroupName => "reference to an undefined capture group name",
+            RegExpError::IncompleteQuantifier => "incomplete quantifier",
+            RegExpError::QuantifierOutOfOrder => "numbers out of order in quantifier",
+            RegExpError::RangeOutOfOrder => "range out of order in character class",
+            RegExpError::InvalidClassRange => "character class escape in a range",
+            RegExpError::InvalidProperty => "invalid unicode property",
+            RegExpError::LoneBrace => "lone quantifier brace",
+            RegExpError::LoneBracket => "lone ']'"
+        }
+    }
+}
+
+// The position of the nth character after start, which is fine since
+// regular expression literals can't span lines.
+fn advance(start: Posn, n: usize) -> Posn {
+    Posn {
+        offset: start.offset + n as u32,
+        line: start.line,
+        column: start.column + n as u32
+    }
+}
+
+// Parses the flags of a regular expression literal, which start at the given
+// position.
+pub fn parse_flags(flags: &[char], start: Posn) -> Result<Flags> {
+    let mut result = Flags::default();
+    for (i, &ch) in flags.iter().enumerate() {
+        let flag = match ch {
+            'd' => &mut result.has_indices,
+            'g' => &mut result.global,
+            'i' => &mut result.ignore_case,
+            'm' => &mut result.multiline,
+            's' => &mut result.dot_all,
+            'u' => &mut result.unicode,
+            'y' => &mut result.sticky,
+            _ => {
+                let location = Span { start: advance(start, i), end: advance(start, i + 1) };
+                return Err(Error::InvalidRegExp(location, RegExpError::InvalidFlag(ch)));
+            }
+        };
+        if *flag {
+            let location = Span { start: advance(start, i), end: advance(start, i + 1) };
+            return Err(Error::InvalidRegExp(location, RegExpError::RepeatedFlag(ch)));
+        }
+        *flag = true;
+    }
+    Ok(result)
+}
+
+// Parses the body of a regular expression literal, which starts at the given
+// position. Without the u flag, the Annex B grammar is used unless annex_b is
+// false.
+pub fn parse_pattern(source: &str, unicode: bool, annex_b: bool, start: Posn) -> Result<Pattern> {
+    let chars: Vec<char> = source.chars().collect();
+    let (groups, named_groups) = scan_groups(&chars);
+    let mut parser = PatternParser {
+        chars: chars,
+        pos: 0,
+        start: start,
+        unicode: unicode,
+        annex_b: annex_b && !unicode,
+        groups: groups,
+        named_groups: named_groups,
+        names: Vec::new(),
+        name_refs: Vec::new()
+    };
+    parser.pattern()
+}
+
+// Counts the capturing groups ahead of time, since backreferences can refer
+// to later groups, and finds out whether there are any named groups, which
+// changes the meaning of \k without the u flag.
+fn scan_groups(chars: &[char]) -> (u32, bool) {
+    let mut groups = 0;
+    let mut named_groups = false;
+    let mut in_class = false;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => { i += 1; }
+            '[' => { in_class = true; }
+            ']' => { in_class = false; }
+            '(' if !in_class => {
+                match (chars.get(i + 1), chars.get(i + 2), chars.get(i + 3)) {
+                    (Some(&'?'), Some(&'<'), Some(&ch)) if ch != '=' && ch != '!' => {
+                        groups += 1;
+                        named_groups = true;
+                    }
+                    (Some(&'?'), _, _) => { }
+                    _ => { groups += 1; }
+                }
+            }
+            _ => { }
+        }
+        i += 1;
+    }
+    (groups, named_groups)
+}
+
+struct PatternParser {
+    chars: Vec<char>,
+    pos: usize,
+    start: Posn,
+    unicode: bool,
+    annex_b: bool, // the Annex B extensions, which never apply with u
+    groups: u32,
+    named_groups: bool,
+    names: Vec<String>,
+    name_refs: Vec<(String, Span)>
+}
+
+impl PatternParser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).cloned()
+    }
+
+    fn peek_at(&self, n: usize) -> Option<char> {
+        self.chars.get(self.pos + n).cloned()
+    }
+
+    fn matches(&mut self, ch: char) -> bool {
+        (self.peek() == Some(ch)) && { self.pos += 1; true }
+    }
+
+    fn matches_str(&mut self, s: &str) -> bool {
+        let matched = s.chars().enumerate().all(|(i, ch)| self.peek_at(i) == Some(ch));
+        if matched {
+            self.pos += s.chars().count();
+        }
+        matched
+    }
+
+    fn span_from(&self, start: usize) -> Span {
+        let end = if self.pos > start { self.pos } else { start + 1 };
+        Span { start: advance(self.start, start), end: advance(self.start, end) }
+    }
+
+    fn error<T>(&self, start: usize, error: RegExpError) -> Result<T> {
+        Err(Error::InvalidRegExp(self.span_from(start), error))
+    }
+
+    // Pattern ::= Disjunction
+    fn pattern(&mut self) -> Result<Pattern> {
+        let disjunction = self.disjunction()?;
+        if self.pos < self.chars.len() {
+            let start = self.pos;
+            self.pos += 1;
+            return self.error(start, RegExpError::UnmatchedParen);
+        }
+        for &(ref name, location) in self.name_refs.iter() {
+            if !self.names.contains(name) {
+                return Err(Error::InvalidRegExp(location, RegExpError::UndefinedGroupName));
+            }
+        }
+        Ok(Pattern {
+            disjunction: disjunction,
+            groups: self.groups
+        })
+    }
+
+    // Disjunction ::= Alternative ("|" Alternative)*
+    fn disjunction(&mut self) -> Result<Disjunction> {
+        let mut alternatives = vec![self.alternative()?];
+        while self.matches('|') {
+            alternatives.push(self.alternative()?);
+        }
+        Ok(alternatives)
+    }
+
+    // Alternative ::= Term*
+    fn alternative(&mut self) -> Result<Alternative> {
+        let mut terms = Vec::new();
+        loop {
+            match self.peek() {
+                None | Some('|') | Some(')') => { return Ok(terms); }
+                _ => { terms.push(self.term()?); }
+            }
+        }
+    }
+
+    // Term ::=
+    //   Assertion
+    //   Atom Quantifier?
+    //   QuantifiableAssertion Quantifier    (Annex B, without u)
+    fn term(&mut self) -> Result<Term> {
+        let start = self.pos;
+        if let Some(assertion) = self.assertion_opt()? {
+            let lookahead = match assertion {
+                Assertion::Lookahead(_, _) => true,
+                _ => false
+            };
+            let quantifier_start = self.pos;
+            return match self.quantifier_opt()? {
+                None => Ok(Term::Assertion(assertion)),
+                Some(quantifier) if lookahead && self.annex_b => Ok(Term::QuantifiedAssertion(assertion, quantifier)),
+                Some(_) => self.error(quantifier_start, RegExpError::NothingToRepeat)
+            };
+        }
+        let atom = self.atom(start)?;
+        Ok(Term::Atom(atom, self.quantifier_opt()?))
+    }
+
+    // Assertion ::=
+    //   "^"
+    //   "$"
+    //   "\" "b"
+    //   "\" "B"
+    //   "(" "?" ("=" | "!") Disjunction ")"
+    //   "(" "?" "<" ("=" | "!") Disjunction ")"
+    fn assertion_opt(&mut self) -> Result<Option<Assertion>> {
+        let start = self.pos;
+        let assertion = if self.matches('^') {
+            Assertion::Start
+        } else if self.matches('$') {
+            Assertion::End
+        } else if self.matches_str("\\b") {
+            Assertion::WordBoundary
+        } else if self.matches_str("\\B") {
+            Assertion::NotWordBoundary
+        } else if self.matches_str("(?=") {
+            Assertion::Lookahead(false, self.more_group(start)?)
+        } else if self.matches_str("(?!") {
+            Assertion::Lookahead(true, self.more_group(start)?)
+        } else if self.matches_str("(?<=") {
+            Assertion::Lookbehind(false, self.more_group(start)?)
+        } else if self.matches_str("(?<!") {
+            Assertion::Lookbehind(true, self.more_group(start)?)
+        } else {
+            return Ok(None);
+        };
+        Ok(Some(assertion))
+    }
+
+    // "(" ... . Disjunction ")"
+    fn more_group(&mut self, start: usize) -> Result<Disjunction> {
+        let disjunction = self.disjunction()?;
+        if !self.matches(')') {
+            return self.error(start, RegExpError::UnterminatedGroup);
+        }
+        Ok(disjunction)
+    }
+
+    // Atom ::=
+    //   PatternCharacter
+    //   "."
+    //   "\" AtomEscape
+    //   CharacterClass
+    //   "(" GroupSpecifier? Disjunction ")"
+    //   "(" "?" ":" Disjunction ")"
+    fn atom(&mut self, start: usize) -> Result<Atom> {
+        let ch = self.peek().unwrap();
+        self.pos += 1;
+        match ch {
+            '.' => Ok(Atom::Dot),
+            '\\' => self.atom_escape(start),
+            '[' => self.class(start).map(Atom::Class),
+            '(' => {
+                if self.matches_str("?:") {
+                    return self.more_group(start).map(Atom::NonCapturingGroup);
+                }
+                if self.matches('?') {
+                    if !self.matches('<') {
+                        return self.error(start, RegExpError::InvalidGroup);
+                    }
+                    let name = self.group_name()?;
+                    if self.names.contains(&name) {
+                        return self.error(start, RegExpError::DuplicateGroupName);
+                    }
+                    self.names.push(name.clone());
+                    return self.more_group(start).map(|disjunction| Atom::Group(Some(name), disjunction));
+                }
+                self.more_group(start).map(|disjunction| Atom::Group(None, disjunction))
+            }
+            '*' | '+' | '?' => self.error(start, RegExpError::NothingToRepeat),
+            '{' => {
+                self.pos = start;
+                if self.braced_quantifier_follows() {
+                    return self.error(start, RegExpError::NothingToRepeat);
+                }
+                self.pos = start + 1;
+                if !self.annex_b {
+                    return self.error(start, RegExpError::LoneBrace);
+                }
+                Ok(Atom::Char('{' as u32))
+            }
+            '}' if !self.annex_b => self.error(start, RegExpError::LoneBrace),
+            ']' if !self.annex_b => self.error(start, RegExpError::LoneBracket),
+            _ => Ok(Atom::Char(ch as u32))
+        }
+    }
+
+    // GroupName ::= "<" RegExpIdentifierName ">"
+    fn group_name(&mut self) -> Result<String> {
+        let start = self.pos;
+        let mut name = String::new();
+        loop {
+            let ch = match self.peek() {
+                Some('>') => { break; }
+                Some('\\') => {
+                    self.pos += 1;
+                    if !self.matches('u') {
+                        return self.error(start, RegExpError::InvalidGroupName);
+                    }
+                    match self.unicode_escape(true)?.and_then(::std::char::from_u32) {
+                        Some(ch) => ch,
+                        None => { return self.error(start, RegExpError::InvalidGroupName); }
+                    }
+                }
+                Some(ch) => { self.pos += 1; ch }
+                None => { return self.error(start, RegExpError::InvalidGroupName); }
+            };
+            let valid = if name.is_empty() { ch.is_es_identifier_start() } else { ch.is_es_identifier_continue() };
+            if !valid {
+                return self.error(start, RegExpError::InvalidGroupName);
+            }
+            name.push(ch);
+        }
+        if name.is_empty() {
+            return self.error(start, RegExpError::InvalidGroupName);
+        }
+        self.pos += 1;
+        Ok(name)
+    }
+
+    // Quantifier ::= QuantifierPrefix "?"?
+    // QuantifierPrefix ::=
+    //   "*"
+    //   "+"
+    //   "?"
+    //   "{" DecimalDigits ("," DecimalDigits?)? "}"
+    fn quantifier_opt(&mut self) -> Result<Option<Quantifier>> {
+        let (min, max) = match self.peek() {
+            Some('*') => { self.pos += 1; (0, None) }
+            Some('+') => { self.pos += 1; (1, None) }
+            Some('?') => { self.pos += 1; (0, Some(1)) }
+            Some('{') => {
+                match self.braced_quantifier_opt()? {
+                    Some(bounds) => bounds,
+                    None => { return Ok(None); }
+                }
+            }
+            _ => { return Ok(None); }
+        };
+        let greedy = !self.matches('?');
+        Ok(Some(Quantifier { min: min, max: max, greedy: greedy }))
+    }
+
+    fn braced_quantifier_follows(&self) -> bool {
+        let digits = |mut i: usize| {
+            let start = i;
+            while self.peek_at(i).map_or(false, |ch| ch.is_es_dec_digit()) {
+                i += 1;
+            }
+            (i, i > start)
+        };
+        if self.peek() != Some('{') {
+            return false;
+        }
+        let (mut i, found) = digits(1);
+        if !found {
+            return false;
+        }
+        if self.peek_at(i) == Some(',') {
+            i = digits(i + 1).0;
+        }
+        self.peek_at(i) == Some('}')
+    }
+
+    // Annex B: a "{" that doesn't start a well-formed quantifier is just a
+    // pattern character.
+    fn braced_quantifier_opt(&mut self) -> Result<Option<(u32, Option<u32>)>> {
+        let start = self.pos;
+        if !self.braced_quantifier_follows() {
+            if !self.annex_b {
+                self.pos += 1;
+                return self.error(start, RegExpError::IncompleteQuantifier);
+            }
+            return Ok(None);
+        }
+        self.pos += 1;
+        let min = self.decimal_digits().unwrap();
+        let max = if self.matches(',') { self.decimal_digits() } else { Some(min) };
+        self.pos += 1;
+        if max.map_or(false, |max| max < min) {
+            return self.error(start, RegExpError::QuantifierOutOfOrder);
+        }
+        Ok(Some((min, max)))
+    }
+
+    // Saturates rather than overflowing, since a quantifier that large is
+    // effectively unbounded anyway.
+    fn decimal_digits(&mut self) -> Option<u32> {
+        let mut value: Option<u32> = None;
+        while let Some(digit) = self.peek().and_then(|ch| ch.to_digit(10)) {
+            self.pos += 1;
+            value = Some(value.unwrap_or(0).saturating_mul(10).saturating_add(digit));
+        }
+        value
+    }
+
+    // AtomEscape ::=
+    //   DecimalEscape
+    //   CharacterClassEscape
+    //   CharacterEscape
+    //   "k" GroupName
+    fn atom_escape(&mut self, start: usize) -> Result<Atom> {
+        match self.peek() {
+            Some('1'...'9') => {
+                let digits_start = self.pos;
+                let n = self.decimal_digits().unwrap();
+                if n <= self.groups {
+                    return Ok(Atom::Backreference(n));
+                }
+                if !self.annex_b {
+                    return self.error(start, RegExpError::InvalidBackreference);
+                }
+                // Annex B: a legacy octal escape, or an identity escape of 8 or 9.
+                self.pos = digits_start;
+                self.legacy_octal_escape().map(Atom::Char)
+            }
+            Some('k') if self.unicode || self.named_groups => {
+                self.pos += 1;
+                if !self.matches('<') {
+                    return self.error(start, RegExpError::InvalidGroupName);
+                }
+                let name = self.group_name()?;
+                let location = self.span_from(start);
+                self.name_refs.push((name.clone(), location));
+                Ok(Atom::NamedBackreference(name))
+            }
+            _ => {
+                match self.class_escape_opt(start)? {
+                    Some(escape) => Ok(Atom::Escape(escape)),
+                    None => self.character_escape(start, false).map(Atom::Char)
+                }
+            }
+        }
+    }
+
+    // CharacterClassEscape ::=
+    //   "d" | "D" | "s" | "S" | "w" | "W"
+    //   "p" "{" UnicodePropertyValueExpression "}"    (only with u)
+    //   "P" "{" UnicodePropertyValueExpression "}"    (only with u)
+    fn class_escape_opt(&mut self, start: usize) -> Result<Option<ClassEscape>> {
+        let escape = match self.peek() {
+            Some('d') => ClassEscape::Digit(false),
+            Some('D') => ClassEscape::Digit(true),
+            Some('s') => ClassEscape::Space(false),
+            Some('S') => ClassEscape::Space(true),
+            Some('w') => ClassEscape::Word(false),
+            Some('W') => ClassEscape::Word(true),
+            Some(ch @ 'p') | Some(ch @ 'P') if self.unicode => {
+                self.pos += 1;
+                return self.property_escape(start, ch == 'P').map(Some);
+            }
+            _ => { return Ok(None); }
+        };
+        self.pos += 1;
+        Ok(Some(escape))
+    }
+
+    // UnicodePropertyValueExpression ::=
+    //   UnicodePropertyName "=" UnicodePropertyValue
+    //   LoneUnicodePropertyNameOrValue
+    //
+    // Script names are only checked for well-formedness.
+    fn property_escape(&mut self, start: usize, negated: bool) -> Result<ClassEscape> {
+        if !self.matches('{') {
+            return self.error(start, RegExpError::InvalidProperty);
+        }
+        let name = self.property_word(&|ch| ch.is_ascii_alphabetic() || ch == '_');
+        let value = if self.matches('=') {
+            Some(self.property_word(&|ch| ch.is_ascii_alphanumeric() || ch == '_'))
+        } else {
+            None
+        };
+        if !self.matches('}') {
+            return self.error(start, RegExpError::InvalidProperty);
+        }
+        let valid = match value {
+            Some(ref value) => {
+                match &name[..] {
+                    "General_Category" | "gc" => GENERAL_CATEGORY_VALUES.contains(&&value[..]),
+                    "Script" | "sc" | "Script_Extensions" | "scx" => SCRIPT_VALUES.contains(&&value[..]),
+                    _ => false
+                }
+            }
+            None => GENERAL_CATEGORY_VALUES.contains(&&name[..]) || BINARY_PROPERTIES.contains(&&name[..])
+        };
+        if !valid {
+            return self.error(start, RegExpError::InvalidProperty);
+        }
+        Ok(ClassEscape::Property(negated, name, value))
+    }
+
+    fn property_word<F>(&mut self, pred: &F) -> String
+      where F: Fn(char) -> bool
+    {
+        let mut s = String::new();
+        while let Some(ch) = self.peek() {
+            if !pred(ch) {
+                break;
+            }
+            s.push(ch);
+            self.pos += 1;
+        }
+        s
+    }
+
+    // CharacterEscape ::=
+    //   ControlEscape
+    //   "c" ControlLetter
+    //   "0" [lookahead ∉ DecimalDigit]
+    //   HexEscapeSequence
+    //   RegExpUnicodeEscapeSequence
+    //   IdentityEscape
+    fn character_escape(&mut self, start: usize, in_class: bool) -> Result<u32> {
+        let ch = match self.peek() {
+            Some(ch) => ch,
+            None => { return self.error(start, RegExpError::InvalidEscape); }
+        };
+        self.pos += 1;
+        Ok(match ch {
+            'f' => 0x0C,
+            'n' => 0x0A,
+            'r' => 0x0D,
+            't' => 0x09,
+            'v' => 0x0B,
+            'c' => {
+                match self.peek() {
+                    Some(letter) if letter.is_ascii_alphabetic() => {
+                        self.pos += 1;
+                        letter as u32 % 32
+                    }
+                    // Annex B: digits and "_" are allowed in classes too.
+                    Some(other) if in_class && self.annex_b && (other.is_es_dec_digit() || other == '_') => {
+                        self.pos += 1;
+                        other as u32 % 32
+                    }
+                    _ if !self.annex_b => { return self.error(start, RegExpError::InvalidEscape); }
+                    // Annex B: a "\" that's just a pattern character.
+                    _ => {
+                        self.pos -= 1;
+                        '\\' as u32
+                    }
+                }
+            }
+            '0' if !self.peek().map_or(false, |ch| ch.is_es_dec_digit()) => 0,
+            '0'...'7' if self.annex_b => {
+                self.pos -= 1;
+                self.legacy_octal_escape()?
+            }
+            'x' => {
+                match self.hex_digits(2) {
+                    Some(value) => value,
+                    None if !self.annex_b => { return self.error(start, RegExpError::InvalidEscape); }
+                    None => 'x' as u32
+                }
+            }
+            'u' => {
+                match self.unicode_escape(self.unicode)? {
+                    Some(value) => value,
+                    None if !self.annex_b => { return self.error(start, RegExpError::InvalidUnicodeEscape); }
+                    None => 'u' as u32
+                }
+            }
+            _ if self.unicode => {
+                match ch {
+                    '^' | '$' | '\\' | '.' | '*' | '+' | '?' | '(' | ')' | '[' | ']' | '{' | '}' | '|' | '/' => ch as u32,
+                    '-' if in_class => ch as u32,
+                    _ => { return self.error(start, RegExpError::InvalidEscape); }
+                }
+            }
+            // IdentityEscape ::= SourceCharacter but not UnicodeIDContinue
+            _ if !self.annex_b && ch.is_id_continue() => {
+                return self.error(start, RegExpError::InvalidEscape);
+            }
+            _ => ch as u32
+        })
+    }
+
+    // Annex B: LegacyOctalEscapeSequence, or an identity escape of 8 or 9.
+    fn legacy_octal_escape(&mut self) -> Result<u32> {
+        let first = self.peek().unwrap();
+        self.pos += 1;
+        let mut value = match first.to_digit(8) {
+            Some(digit) => digit,
+            None => { return Ok(first as u32); }
+        };
+        let max_digits = if first <= '3' { 3 } else { 2 };
+        for _ in 1..max_digits {
+            match self.peek().and_then(|ch| ch.to_digit(8)) {
+                Some(digit) => {
+                    self.pos += 1;
+                    value = value * 8 + digit;
+                }
+                None => { break; }
+            }
+        }
+        Ok(value)
+    }
+
+    fn hex_digits(&mut self, n: usize) -> Option<u32> {
+        let mut value = 0;
+        for i in 0..n {
+            match self.peek_at(i).and_then(|ch| ch.to_digit(16)) {
+                Some(digit) => { value = value * 16 + digit; }
+                None => { return None; }
+            }
+        }
+        self.pos += n;
+        Some(value)
+    }
+
+    // RegExpUnicodeEscapeSequence ::=
+    //   "u" HexLeadSurrogate "\u" HexTrailSurrogate    (only with u)
+    //   "u" Hex4Digits
+    //   "u" "{" CodePoint "}"                            (only with u)
+    //
+    // The "u" has already been read. Fails with None, restoring the
+    // position, if the escape is incomplete.
+    fn unicode_escape(&mut self, unicode: bool) -> Result<Option<u32>> {
+        let start = self.pos;
+        if unicode && self.matches('{') {
+            let mut value: u32 = 0;
+            let mut digits = 0;
+            while let Some(digit) = self.peek().and_then(|ch| ch.to_digit(16)) {
+                self.pos += 1;
+                digits += 1;
+                value = value.saturating_mul(16).saturating_add(digit);
+            }
+            if digits == 0 || value > 0x10FFFF || !self.matches('}') {
+                self.pos = start;
+                return Ok(None);
+            }
+            return Ok(Some(value));
+        }
+        let lead = match self.hex_digits(4) {
+            Some(lead) => lead,
+            None => { return Ok(None); }
+        };
+        if unicode && lead >= 0xD800 && lead <= 0xDBFF {
+            let trail_start = self.pos;
+            if self.matches_str("\\u") {
+                match self.hex_digits(4) {
+                    Some(trail) if trail >= 0xDC00 && trail <= 0xDFFF => {
+                        return Ok(Some(0x10000 + ((lead - 0xD800) << 10) + (trail - 0xDC00)));
+                    }
+                    _ => { self.pos = trail_start; }
+                }
+            }
+        }
+        Ok(Some(lead))
+    }
+
+    // CharacterClass ::= "[" "^"? ClassRanges "]"
+    fn class(&mut self, start: usize) -> Result<Class> {
+        let negated = self.matches('^');
+        let mut ranges = Vec::new();
+        loop {
+            match self.peek() {
+                Some(']') => { self.pos += 1; break; }
+                None => { return self.error(start, RegExpError::UnterminatedClass); }
+                _ => { }
+            }
+            let atom_start = self.pos;
+            let left = self.class_atom()?;
+            if self.peek() != Some('-') || self.peek_at(1) == Some(']') || self.peek_at(1).is_none() {
+                ranges.push(left);
+                continue;
+            }
+            self.pos += 1;
+            let right = self.class_atom()?;
+            match (left, right) {
+                (ClassRange::Char(from), ClassRange::Char(to)) => {
+                    if from > to {
+                        return self.error(atom_start, RegExpError::RangeOutOfOrder);
+                    }
+                    ranges.push(ClassRange::Range(from, to));
+                }
+                _ if !self.annex_b => { return self.error(atom_start, RegExpError::InvalidClassRange); }
+                // Annex B: a class escape at either end makes the "-" literal.
+                (left, right) => {
+                    ranges.push(left);
+                    ranges.push(ClassRange::Char('-' as u32));
+                    ranges.push(right);
+                }
+            }
+        }
+        Ok(Class { negated: negated, ranges: ranges })
+    }
+
+    // ClassAtom ::=
+    //   "-"
+    //   ClassAtomNoDash
+    // ClassEscape ::=
+    //   "b"
+    //   "-"                       (only with u)
+    //   CharacterClassEscape
+    //   CharacterEscape
+    fn class_atom(&mut self) -> Result<ClassRange> {
+        let start = self.pos;
+        let ch = self.peek().unwrap();
+        self.pos += 1;
+        if ch != '\\' {
+            return Ok(ClassRange::Char(ch as u32));
+        }
+        match self.peek() {
+            Some('b') => {
+                self.pos += 1;
+                Ok(ClassRange::Char(0x08))
+            }
+            Some('B') | Some('k') if self.unicode => self.error(start, RegExpError::InvalidEscape),
+            Some('1'...'9') if !self.annex_b => self.error(start, RegExpError::InvalidEscape),
+            Some('8') | Some('9') => {
+                self.pos += 1;
+                Ok(ClassRange::Char(self.chars[self.pos - 1] as u32))
+            }
+            _ => {
+                match self.class_escape_opt(start)? {
+                    Some(escape) => Ok(ClassRange::Escape(escape)),
+                    None => self.character_escape(start, true).map(ClassRange::Char)
+                }
+            }
+        }
+    }
+}
+
+static GENERAL_CATEGORY_VALUES: &'static [&'static str] = &[
+    "C", "Other", "Cc", "Control", "cntrl", "Cf", "Format", "Cn", "Unassigned",
+    "Co", "Private_Use", "Cs", "Surrogate", "L", "Letter", "LC", "Cased_Letter",
+    "Ll", "Lowercase_Letter", "Lm", "Modifier_Letter", "Lo", "Other_Letter",
+    "Lt", "Titlecase_Letter", "Lu", "Uppercase_Letter", "M", "Mark",
+    "Combining_Mark", "Mc", "Spacing_Mark", "Me", "Enclosing_Mark", "Mn",
+    "Nonspacing_Mark", "N", "Number", "Nd", "Decimal_Number", "digit", "Nl",
+    "Letter_Number", "No", "Other_Number", "P", "Punctuation", "punct", "Pc",
+    "Connector_Punctuation", "Pd", "Dash_Punctuation", "Pe", "Close_Punctuation",
+    "Pf", "Final_Punctuation", "Pi", "Initial_Punctuation", "Po",
+    "Other_Punctuation", "Ps", "Open_Punctuation", "S", "Symbol", "Sc",
+    "Currency_Symbol", "Sk", "Modifier_Symbol", "Sm", "Math_Symbol", "So",
+    "Other_Symbol", "Z", "Separator", "Zl", "Line_Separator", "Zp",
+    "Paragraph_Separator", "Zs", "Space_Separator"
+];
+
+// The short and long names of the scripts, as of Unicode 16.0.
+static SCRIPT_VALUES: &'static [&'static str] = &[
+    "Adlm", "Adlam", "Aghb", "Caucasian_Albanian", "Ahom", "Arab", "Arabic",
+    "Armi", "Imperial_Aramaic", "Armn", "Armenian", "Avst", "Avestan", "Bali",
+    "Balinese", "Bamu", "Bamum", "Bass", "Bassa_Vah", "Batk", "Batak", "Beng",
+    "Bengali", "Bhks", "Bhaiksuki", "Bopo", "Bopomofo", "Brah", "Brahmi",
+    "Brai", "Braille", "Bugi", "Buginese", "Buhd", "Buhid", "Cakm", "Chakma",
+    "Cans", "Canadian_Aboriginal", "Cari", "Carian", "Cham", "Cher", "Cherokee",
+    "Chrs", "Chorasmian", "Copt", "Coptic", "Qaac", "Cpmn", "Cypro_Minoan",
+    "Cprt", "Cypriot", "Cyrl", "Cyrillic", "Deva", "Devanagari", "Diak",
+    "Dives_Akuru", "Dogr", "Dogra", "Dsrt", "Deseret", "Dupl", "Duployan",
+    "Egyp", "Egyptian_Hieroglyphs", "Elba", "Elbasan", "Elym", "Elymaic",
+    "Ethi", "Ethiopic", "Gara", "Garay", "Geor", "Georgian", "Glag",
+    "Glagolitic", "Gong", "Gunjala_Gondi", "Gonm", "Masaram_Gondi", "Goth",
+    "Gothic", "Gran", "Grantha", "Grek", "Greek", "Gujr", "Gujarati", "Gukh",
+    "Gurung_Khema", "Guru", "Gurmukhi", "Hang", "Hangul", "Hani", "Han", "Hano",
+    "Hanunoo", "Hatr", "Hatran", "Hebr", "Hebrew", "Hira", "Hiragana", "Hluw",
+    "Anatolian_Hieroglyphs", "Hmng", "Pahawh_Hmong", "Hmnp",
+    "Nyiakeng_Puachue_Hmong", "Hrkt", "Katakana_Or_Hiragana", "Hung",
+    "Old_Hungarian", "Ital", "Old_Italic", "Java", "Javanese", "Kali",
+    "Kayah_Li", "Kana", "Katakana", "Kawi", "Khar", "Kharoshthi", "Khmr",
+    "Khmer", "Khoj", "Khojki", "Kits", "Khitan_Small_Script", "Knda", "Kannada",
+    "Krai", "Kirat_Rai", "Kthi", "Kaithi", "Lana", "Tai_Tham", "Laoo", "Lao",
+    "Latn", "Latin", "Lepc", "Lepcha", "Limb", "Limbu", "Lina", "Linear_A",
+    "Linb", "Linear_B", "Lisu", "Lyci", "Lycian", "Lydi", "Lydian", "Mahj",
+    "Mahajani", "Maka", "Makasar", "Mand", "Mandaic", "Mani", "Manichaean",
+    "Marc", "Marchen", "Medf", "Medefaidrin", "Mend", "Mende_Kikakui", "Merc",
+    "Meroitic_Cursive", "Mero", "Meroitic_Hieroglyphs", "Mlym", "Malayalam",
+    "Modi", "Mong", "Mongolian", "Mroo", "Mro", "Mtei", "Meetei_Mayek", "Mult",
+    "Multani", "Mymr", "Myanmar", "Nagm", "Nag_Mundari", "Nand", "Nandinagari",
+    "Narb", "Old_North_Arabian", "Nbat", "Nabataean", "Newa", "Nkoo", "Nko",
+    "Nshu", "Nushu", "Ogam", "Ogham", "Olck", "Ol_Chiki", "Onao", "Ol_Onal",
+    "Orkh", "Old_Turkic", "Orya", "Oriya", "Osge", "Osage", "Osma", "Osmanya",
+    "Ougr", "Old_Uyghur", "Palm", "Palmyrene", "Pauc", "Pau_Cin_Hau", "Perm",
+    "Old_Permic", "Phag", "Phags_Pa", "Phli", "Inscriptional_Pahlavi", "Phlp",
+    "Psalter_Pahlavi", "Phnx", "Phoenician", "Plrd", "Miao", "Prti",
+    "Inscriptional_Parthian", "Rjng", "Rejang", "Rohg", "Hanifi_Rohingya",
+    "Runr", "Runic", "Samr", "Samaritan", "Sarb", "Old_South_Arabian", "Saur",
+    "Saurashtra", "Sgnw", "SignWriting", "Shaw", "Shavian", "Shrd", "Sharada",
+    "Sidd", "Siddham", "Sind", "Khudawadi", "Sinh", "Sinhala", "Sogd",
+    "Sogdian", "Sogo", "Old_Sogdian", "Sora", "Sora_Sompeng", "Soyo", "Soyombo",
+    "Sund", "Sundanese", "Sunu", "Sunuwar", "Sylo", "Syloti_Nagri", "Syrc",
+    "Syriac", "Tagb", "Tagbanwa", "Takr", "Takri", "Tale", "Tai_Le", "Talu",
+    "New_Tai_Lue", "Taml", "Tamil", "Tang", "Tangut", "Tavt", "Tai_Viet",
+    "Telu", "Telugu", "Tfng", "Tifinagh", "Tglg", "Tagalog", "Thaa", "Thaana",
+    "Thai", "Tibt", "Tibetan", "Tirh", "Tirhuta", "Tnsa", "Tangsa", "Todr",
+    "Todhri", "Toto", "Tutg", "Tulu_Tigalari", "Ugar", "Ugaritic", "Vaii",
+    "Vai", "Vith", "Vithkuqi", "Wara", "Warang_Citi", "Wcho", "Wancho", "Xpeo",
+    "Old_Persian", "Xsux", "Cuneiform", "Yezi", "Yezidi", "Yiii", "Yi", "Zanb",
+    "Zanabazar_Square", "Zinh", "Inherited", "Qaai", "Zyyy", "Common", "Zzzz",
+    "Unknown"
+];
+
+static BINARY_PROPERTIES: &'static [&'static str] = &[
+    "ASCII", "ASCII_Hex_Digit", "AHex", "Alphabetic", "Alpha", "Any", "Assigned",
+    "Bidi_Control", "Bidi_C", "Bidi_Mirrored", "Bidi_M", "Case_Ignorable", "CI",
+    "Cased", "Changes_When_Casefolded", "CWCF", "Changes_When_Casemapped", "CWCM",
+    "Changes_When_Lowercased", "CWL", "Changes_When_NFKC_Casefolded", "CWKCF",
+    "Changes_When_Titlecased", "CWT", "Changes_When_Uppercased", "CWU", "Dash",
+    "Default_Ignorable_Code_Point", "DI", "Deprecated", "Dep", "Diacritic", "Dia",
+    "Emoji", "Emoji_Component", "EComp", "Emoji_Modifier", "EMod",
+    "Emoji_Modifier_Base", "EBase", "Emoji_Presentation", "EPres",
+    "Extended_Pictographic", "ExtPict", "Extender", "Ext", "Grapheme_Base",
+    "Gr_Base", "Grapheme_Extend", "Gr_Ext", "Hex_Digit", "Hex",
+    "IDS_Binary_Operator", "IDSB", "IDS_Trinary_Operator", "IDST", "ID_Continue",
+    "IDC", "ID_Start", "IDS", "Ideographic", "Ideo", "Join_Control", "Join_C",
+    "Logical_Order_Exception", "LOE", "Lowercase", "Lower", "Math",
+    "Noncharacter_Code_Point", "NChar", "Pattern_Syntax", "Pat_Syn",
+    "Pattern_White_Space", "Pat_WS", "Quotation_Mark", "QMark", "Radical",
+    "Regional_Indicator", "RI", "Sentence_Terminal", "STerm", "Soft_Dotted", "SD",
+    "Terminal_Punctuation", "Term", "Unified_Ideograph", "UIdeo", "Uppercase",
+    "Upper", "Variation_Selector", "VS", "White_Space", "space", "XID_Continue",
+    "XIDC", "XID_Start", "XIDS"
+];
@@ -118,6 +118,7 @@ impl Deserialize for TokenData {
                     "Plus"          => TokenData::Plus,
                     "Minus"         => TokenData::Minus,
                     "Star"          => TokenData::Star,
+                    "StarStar"      => TokenData::StarStar,
                     "Mod"           => TokenData::Mod,
                     "Slash"         => TokenData::Slash,
                     "Inc"           => TokenData::Inc,
@@ -132,12 +133,15 @@ impl Deserialize for TokenData {
                     "Tilde"         => TokenData::Tilde,
                     "LogicalAnd"    => TokenData::LogicalAnd,
                     "LogicalOr"     => TokenData::LogicalOr,
+                    "Coalesce"      => TokenData::Coalesce,
                     "Question"      => TokenData::Question,
+                    "QuestionDot"   => TokenData::QuestionDot,
                     "Colon"         => TokenData::Colon,
                     "Assign"        => TokenData::Assign,
                     "PlusAssign"    => TokenData::PlusAssign,
                     "MinusAssign"   => TokenData::MinusAssign,
                     "StarAssign"    => TokenData::StarAssign,
+                    "StarStarAssign" => TokenData::StarStarAssign,
                     "SlashAssign"   => TokenData::SlashAssign,
                     "ModAssign"     => TokenData::ModAssign,
                     "LShiftAssign"  => TokenData::LShiftAssign,
@@ -146,6 +150,9 @@ impl Deserialize for TokenData {
                     "BitAndAssign"  => TokenData::BitAndAssign,
                     "BitOrAssign"   => TokenData::BitOrAssign,
                     "BitXorAssign"  => TokenData::BitXorAssign,
+                    "LogicalAndAssign" => TokenData::LogicalAndAssign,
+                    "LogicalOrAssign" => TokenData::LogicalOrAssign,
+                    "CoalesceAssign" => TokenData::CoalesceAssign,
                     "Arrow"         => TokenData::Arrow,
                     "EOF"           => TokenData::EOF,
                     "DecimalInt"    => {
@@ -164,6 +171,14 @@ impl Deserialize for TokenData {
                         let (flag, value) = Deserialize::deserialize(inner)?;
                         NumberSource::RadixInt(Radix::Hex(flag), value).into_token_data()
                     }
+                    "BigInt"        => {
+                        let (digits,) = Deserialize::deserialize(inner)?;
+                        TokenData::BigInt(BigIntLiteral { radix: None, digits: digits })
+                    }
+                    "HexBigInt"     => {
+                        let (flag, digits) = Deserialize::deserialize(inner)?;
+                        TokenData::BigInt(BigIntLiteral { radix: Some(Radix::Hex(flag)), digits: digits })
+                    }
                     "Float"         => {
                         let (int, frac, exp) = Deserialize::deserialize(inner)?;
                         NumberSource::Float(int, frac, exp).into_token_data()
@@ -182,10 +197,24 @@ impl Deserialize for TokenData {
                             flags: flags.chars().collect()
                         })
                     }
+                    "NoSubstTemplate" | "TemplateHead" | "TemplateMiddle" | "TemplateTail" => {
+                        let (cooked, raw) = Deserialize::deserialize(inner)?;
+                        let part = TemplatePart { cooked: cooked, raw: raw };
+                        match &ty[..] {
+                            "NoSubstTemplate" => TokenData::NoSubstTemplate(part),
+                            "TemplateHead"    => TokenData::TemplateHead(part),
+                            "TemplateMiddle"  => TokenData::TemplateMiddle(part),
+                            _                 => TokenData::TemplateTail(part)
+                        }
+                    }
                     "Identifier"    => {
                         let (name,) = Deserialize::deserialize(inner)?;
                         TokenData::Identifier(name)
                     },
+                    "PrivateName"   => {
+                        let (name,) = Deserialize::deserialize(inner)?;
+                        TokenData::PrivateName(name)
+                    },
                     other => { return Err(V::Error::unknown_variant(other, &KNOWN_TOKEN_VARIANTS)); }
                 })
             }
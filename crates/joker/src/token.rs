@@ -46,6 +46,7 @@ pub enum TokenData {
     Plus,
     Minus,
     Star,
+    StarStar,
     Mod,
     Slash,
     Inc,
@@ -60,12 +61,15 @@ pub enum TokenData {
     Tilde,
     LogicalAnd,
     LogicalOr,
+    Coalesce,
     Question,
+    QuestionDot,
     Colon,
     Assign,
     PlusAssign,
     MinusAssign,
     StarAssign,
+    StarStarAssign,
     SlashAssign,
     ModAssign,
     LShiftAssign,
@@ -74,13 +78,34 @@ pub enum TokenData {
     BitAndAssign,
     BitOrAssign,
     BitXorAssign,
+    LogicalAndAssign,
+    LogicalOrAssign,
+    CoalesceAssign,
     Arrow,
+    At, // only lexed when decorators are enabled
 
     Number(NumberLiteral),
+    BigInt(BigIntLiteral),
     String(StringLiteral),
     RegExp(RegExpLiteral),
 
+    // 11.8.6 Template Literal Lexical Components
+    NoSubstTemplate(TemplatePart),
+    TemplateHead(TemplatePart),
+    TemplateMiddle(TemplatePart),
+    TemplateTail(TemplatePart),
+
     Identifier(Name),
+    PrivateName(String), // without the leading "#"
+
+    // JSX tokens, only produced by the JSX lexing modes. The source of text
+    // and strings is raw; the value has its character references decoded.
+    #[cfg(feature = "jsx")]
+    JsxText(StringLiteral),
+    #[cfg(feature = "jsx")]
+    JsxString(StringLiteral),
+    #[cfg(feature = "jsx")]
+    JsxName(String), // an identifier that may contain "-"
 
     EOF
 }
@@ -138,6 +163,34 @@ pub struct StringLiteral {
     pub value: String
 }
 
+impl StringLiteral {
+    // Whether the source contains a legacy octal escape sequence like "\07"
+    // or a NonOctalDecimalEscapeSequence ("\8" or "\9"), which are only
+    // allowed in sloppy code (Annex B).
+    pub fn has_legacy_escape(&self) -> bool {
+        let source = match self.source {
+            Some(ref source) => source,
+            None => { return false; }
+        };
+        let mut chars = source.chars().peekable();
+        while let Some(ch) = chars.next() {
+            if ch != '\\' {
+                continue;
+            }
+            match chars.next() {
+                Some('1'...'9') => { return true; }
+                Some('0') => {
+                    if let Some(&('0'...'9')) = chars.peek() {
+                        return true;
+                    }
+                }
+                _ => ()
+            }
+        }
+        false
+    }
+}
+
 impl Untrack for StringLiteral {
     fn untrack(&mut self) {}
 }
@@ -156,6 +209,16 @@ impl PartialEq for StringLiteral {
     }
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct TemplatePart {
+    pub cooked: Option<String>, // None if the part contains an invalid escape
+    pub raw: String
+}
+
+impl Untrack for TemplatePart {
+    fn untrack(&mut self) {}
+}
+
 #[derive(Clone)]
 pub struct NumberLiteral {
     pub source: Option<NumberSource>,
@@ -227,6 +290,20 @@ impl NumberSource {
         }
     }
 
+    // Whether this is a legacy octal literal like 017 or a
+    // NonOctalDecimalIntegerLiteral like 089, which are only allowed in
+    // sloppy code (Annex B).
+    pub fn is_legacy(&self) -> bool {
+        match *self {
+            NumberSource::RadixInt(Radix::Oct(None), _) => true,
+            NumberSource::DecimalInt(ref mantissa, _)
+          | NumberSource::Float(Some(ref mantissa), _, _) => {
+                mantissa.len() > 1 && mantissa.starts_with('0')
+            }
+            _ => false
+        }
+    }
+
     pub fn into_token_data(self) -> TokenData {
         let value = self.value();
         TokenData::Number(NumberLiteral {
@@ -236,6 +313,16 @@ impl NumberSource {
     }
 }
 
+#[derive(Debug, PartialEq, Clone)]
+pub struct BigIntLiteral {
+    pub radix: Option<Radix>, // None for a decimal literal
+    pub digits: String        // without the prefix, separators, or "n" suffix
+}
+
+impl Untrack for BigIntLiteral {
+    fn untrack(&mut self) {}
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Exp {
     pub e: CharCase,
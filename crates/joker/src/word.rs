@@ -130,6 +130,7 @@ impl Reserved {
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum Atom {
     Arguments,
+    As,
     Async,
     Await,
     Eval,
@@ -138,6 +139,7 @@ pub enum Atom {
     Implements,
     Interface,
     Let,
+    Meta,
     Of,
     Package,
     Private,
@@ -184,6 +186,7 @@ impl From<String> for Name {
     fn from(s: String) -> Name {
         match &s[..] {
             "arguments"  => Name::Atom(Atom::Arguments),
+            "as"         => Name::Atom(Atom::As),
             "await"      => Name::Atom(Atom::Await),
             "eval"       => Name::Atom(Atom::Eval),
             "async"      => Name::Atom(Atom::Async),
@@ -192,6 +195,7 @@ impl From<String> for Name {
             "implements" => Name::Atom(Atom::Implements),
             "interface"  => Name::Atom(Atom::Interface),
             "let"        => Name::Atom(Atom::Let),
+            "meta"       => Name::Atom(Atom::Meta),
             "of"         => Name::Atom(Atom::Of),
             "package"    => Name::Atom(Atom::Package),
             "private"    => Name::Atom(Atom::Private),
@@ -210,6 +214,7 @@ impl Atom {
     pub fn name(self) -> &'static str {
         match self {
             Atom::Arguments  => "arguments",
+            Atom::As         => "as",
             Atom::Await      => "await",
             Atom::Eval       => "eval",
             Atom::Async      => "async",
@@ -218,6 +223,7 @@ impl Atom {
             Atom::Implements => "implements",
             Atom::Interface  => "interface",
             Atom::Let        => "let",
+            Atom::Meta       => "meta",
             Atom::Of         => "of",
             Atom::Package    => "package",
             Atom::Private    => "private",
@@ -289,7 +295,8 @@ impl Map {
 
                 // Purely contextual identifier names
                 ("async",      Async),      ("from",       From),       ("of",       Of),
-                ("get",        Get),        ("set",        Set),        ("target",   Target)
+                ("get",        Get),        ("set",        Set),        ("target",   Target),
+                ("as",         As),         ("meta",       Meta)
             ])
         }
     }
@@ -3,10 +3,13 @@ use std::default::Default;
 use std::rc::Rc;
 use std::mem::replace;
 use joker::word::Name;
-use joker::track::span;
+use joker::track::{span, Span};
 use easter::stmt::Stmt;
 use easter::id::Id;
+use easter::obj::PrivateKey;
+use easter::cover;
 use result::Result;
+use error::Error;
 use parser::{Parser, Strict};
 
 pub trait WithContext {
@@ -14,6 +17,22 @@ pub trait WithContext {
       where F: FnOnce(&mut Self) -> Result<Stmt>;
     fn allow_in<F, T>(&mut self, allow_in: bool, parse: F) -> Result<T>
       where F: FnOnce(&mut Self) -> Result<T>;
+    fn in_generator<F, T>(&mut self, generator: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>;
+    fn in_async<F, T>(&mut self, asynchronous: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>;
+    fn in_params<F, T>(&mut self, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>;
+    fn in_function<F, T>(&mut self, generator: bool, asynchronous: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>;
+    fn in_method<F, T>(&mut self, generator: bool, asynchronous: bool, super_call: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>;
+    fn in_class_init<F, T>(&mut self, static_block: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>;
+    fn in_static_block<F, T>(&mut self, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>;
+    fn in_arrow<F, T>(&mut self, asynchronous: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>;
 }
 
 impl<I: Iterator<Item=char>> WithContext for Parser<I> {
@@ -47,6 +66,134 @@ impl<I: Iterator<Item=char>> WithContext for Parser<I> {
         replace(&mut self.context.allow_in, allow_in);
         result
     }
+
+    fn in_generator<F, T>(&mut self, generator: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>
+    {
+        let generator = replace(&mut self.context.generator, generator);
+        let result = parse(self);
+        replace(&mut self.context.generator, generator);
+        result
+    }
+
+    fn in_async<F, T>(&mut self, asynchronous: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>
+    {
+        let asynchronous = replace(&mut self.context.asynchronous, asynchronous);
+        let top_level_await = replace(&mut self.context.top_level_await, false);
+        let result = parse(self);
+        replace(&mut self.context.asynchronous, asynchronous);
+        self.context.top_level_await = top_level_await;
+        result
+    }
+
+    fn in_params<F, T>(&mut self, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>
+    {
+        let params = replace(&mut self.context.params, true);
+        let result = parse(self);
+        replace(&mut self.context.params, params);
+        result
+    }
+
+    fn in_function<F, T>(&mut self, generator: bool, asynchronous: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>
+    {
+        let super_property = replace(&mut self.context.super_property, false);
+        let super_call = replace(&mut self.context.super_call, false);
+        let class_init = replace(&mut self.context.class_init, false);
+        let static_block = replace(&mut self.context.static_block, false);
+        let cover_await_id = self.context.cover_await_id.take();
+        let result = self.in_generator(generator, |this| this.in_async(asynchronous, parse));
+        self.context.super_property = super_property;
+        self.context.super_call = super_call;
+        self.context.class_init = class_init;
+        self.context.static_block = static_block;
+        self.context.cover_await_id = cover_await_id;
+        result
+    }
+
+    fn in_method<F, T>(&mut self, generator: bool, asynchronous: bool, super_call: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>
+    {
+        let outer_super_property = replace(&mut self.context.super_property, true);
+        let outer_super_call = replace(&mut self.context.super_call, super_call);
+        let outer_class_init = replace(&mut self.context.class_init, false);
+        let outer_static_block = replace(&mut self.context.static_block, false);
+        let outer_cover_await_id = self.context.cover_await_id.take();
+        let result = self.in_generator(generator, |this| this.in_async(asynchronous, parse));
+        self.context.super_property = outer_super_property;
+        self.context.super_call = outer_super_call;
+        self.context.class_init = outer_class_init;
+        self.context.static_block = outer_static_block;
+        self.context.cover_await_id = outer_cover_await_id;
+        result
+    }
+
+    // Field initializers and static blocks are parsed like method bodies,
+    // but they can't refer to arguments.
+    fn in_class_init<F, T>(&mut self, static_block: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>
+    {
+        self.in_method(false, false, false, |this| {
+            this.context.class_init = true;
+            this.context.static_block = static_block;
+            parse(this)
+        })
+    }
+
+    // A static block starts fresh labels, loops and switches like a function
+    // body, but it isn't a function: return is still illegal.
+    fn in_static_block<F, T>(&mut self, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>
+    {
+        let inner = Context { function: false, ..self.context.new_function() };
+        let outer = replace(&mut self.context, inner);
+        let result = self.in_class_init(true, parse);
+        self.context = outer;
+        result
+    }
+
+    fn in_arrow<F, T>(&mut self, asynchronous: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>
+    {
+        let outer_params = replace(&mut self.context.params, false);
+        let outer_cover_await = self.context.cover_await.take();
+        let outer_cover_yield = self.context.cover_yield.take();
+        let outer_cover_await_id = self.context.cover_await_id.take();
+        let result = self.in_generator(false, |this| this.in_async(asynchronous, parse));
+        self.context.params = outer_params;
+        self.context.cover_await = outer_cover_await;
+        self.context.cover_yield = outer_cover_yield;
+        self.context.cover_await_id = outer_cover_await_id;
+        result
+    }
+}
+
+// The private names declared by a class body, and the references made
+// inside it, which are resolved once the whole body has been parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrivateScope {
+    pub declared: HashMap<String, PrivateDecl>,
+    pub references: Vec<PrivateKey>
+}
+
+impl PrivateScope {
+    pub fn new() -> PrivateScope {
+        PrivateScope {
+            declared: HashMap::new(),
+            references: Vec::new()
+        }
+    }
+}
+
+// A getter and setter of the same staticness can share a private name.
+// The bool flags indicate static members.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum PrivateDecl {
+    Get(bool),
+    Set(bool),
+    Other
 }
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
@@ -66,13 +213,29 @@ impl Default for Goal {
     fn default() -> Goal { Goal::Unknown }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Context {
     pub strict: Strict,
     pub function: bool,
+    pub generator: bool, // yield is a keyword (generator params and body)
+    pub asynchronous: bool, // await is a keyword (async function params and body)
+    pub top_level_await: bool, // await is a keyword if this is a module (outside of any function)
+    pub params: bool, // parsing formal parameters
+    pub super_property: bool, // super.x and super[x] are allowed (methods)
+    pub super_call: bool, // super() is allowed (derived constructors)
+    pub class_init: bool, // arguments is disallowed (field initializers and static blocks)
+    pub static_block: bool, // await is reserved but isn't an operator (static blocks)
     pub iteration: bool,
     pub switch: bool,
     pub allow_in: bool,
+    pub cover_init: Option<Error>, // first unresolved CoverInitializedName or duplicate __proto__
+    pub cover_await: Option<Span>, // first AwaitExpression, in case it's in arrow parameters
+    pub cover_yield: Option<Span>, // first YieldExpression, in case it's in arrow parameters
+    pub cover_await_id: Option<Span>, // first await identifier, in case it's in async arrow parameters
+    pub cover_await_goal: Option<Span>, // first top-level await read as an identifier before the goal was known
+    pub cover_paren: Option<Span>, // first parenthesized element, in case it's in a binding pattern
+    pub cover_patt: Option<cover::Error>, // first error that only stands if a literal is reinterpreted as a pattern
+    pub paren: Option<Span>, // location of the last parenthesized expression
     pub labels: HashMap<Rc<Name>, LabelType>
 }
 
@@ -81,20 +244,55 @@ impl Context {
         Context {
             strict: Strict::Unknown,
             function: false,
+            generator: false,
+            asynchronous: false,
+            top_level_await: true,
+            params: false,
+            super_property: false,
+            super_call: false,
+            class_init: false,
+            static_block: false,
             iteration: false,
             switch: false,
             allow_in: true,
+            cover_init: None,
+            cover_await: None,
+            cover_yield: None,
+            cover_await_id: None,
+            cover_await_goal: None,
+            cover_paren: None,
+            cover_patt: None,
+            paren: None,
             labels: HashMap::new()
         }
     }
 
+    // The generator, async and super flags are set by the caller (see
+    // in_function, in_method and in_arrow); arrow functions inherit the
+    // super flags.
     pub fn new_function(&self) -> Context {
         Context {
             strict: self.strict,
             function: true,
+            generator: self.generator,
+            asynchronous: self.asynchronous,
+            top_level_await: false,
+            params: false,
+            super_property: self.super_property,
+            super_call: self.super_call,
+            class_init: false,
+            static_block: false,
             iteration: false,
             switch: false,
             allow_in: true,
+            cover_init: None,
+            cover_await: None,
+            cover_yield: None,
+            cover_await_id: None,
+            cover_await_goal: self.cover_await_goal,
+            cover_paren: None,
+            cover_patt: None,
+            paren: None,
             labels: HashMap::new()
         }
     }
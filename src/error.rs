@@ -7,7 +7,8 @@ use joker::track::*;
 use joker::word::Atom;
 use easter::id::Id;
 use easter::decl::{Import, Export};
-use easter::patt::CompoundPatt;
+use easter::obj::{DotKey, PrivateKey};
+use easter::patt::Patt;
 use easter::cover;
 use result::Result;
 
@@ -25,16 +26,59 @@ pub enum Error {
     IllegalStrictBinding(Span, Atom),
     UnexpectedDirective(Option<Span>, StringLiteral),
     UnexpectedModule(Option<Span>),
+    UnexpectedScript(Option<Span>),
     ImportInScript(Import),
     ExportInScript(Export),
+    DuplicateExport(DotKey),
+    DuplicateDefaultExport(Option<Span>),
     ForOfLetExpr(Span),
     DuplicateDefault(Token),
     StrictWith(Token),
     ThrowArgument(Token),
     OrphanTry(Token),
     InvalidLHS(Option<Span>, cover::Error),
+    InvalidParam(Option<Span>, cover::Error),
+    DuplicateParam(Id),
+    DuplicateBinding(Id),
+    ArrowNewline(Span),
+    InvalidTemplateEscape(Span),
+    YieldInParameter(Span),
+    AwaitInParameter(Span),
+    UnaryBeforeExponent(Option<Span>),
+    MixedCoalesce(Option<Span>),
+    OptionalChainTemplate(Option<Span>),
+    IllegalSuperProperty(Span),
+    IllegalSuperCall(Span),
+    ImportMetaInScript(Option<Span>),
+    ForAwaitInScript(Span),
+    ForAwaitWithoutOf(Span),
     UnsupportedFeature(&'static str),
-    CompoundParamWithUseStrict(CompoundPatt<Id>)
+    NonSimpleParamWithUseStrict(Patt<Id>),
+    CoverInitializedName(Option<Span>),
+    DuplicateConstructor(Option<Span>),
+    GeneratorConstructor(Option<Span>),
+    AsyncConstructor(Option<Span>),
+    AccessorConstructor(Option<Span>),
+    StaticPrototype(Option<Span>),
+    ConstructorField(Option<Span>),
+    PrivateConstructor(Option<Span>),
+    DecoratedConstructor(Option<Span>),
+    DuplicatePrivateName(PrivateKey),
+    UndeclaredPrivateName(PrivateKey),
+    UnexpectedPrivateName(PrivateKey),
+    DeletePrivateField(Option<Span>),
+    ArgumentsInClassInit(Span),
+    LegacyOctal(Option<Span>),
+    LegacyOctalEscape(Option<Span>),
+    HtmlCommentInModule(Span),
+    IfFunction(Span),
+    LabelledFunction(Span),
+    ForInVarInit(Option<Span>),
+    DuplicateProto(Option<Span>),
+    TypedForHead(Option<Span>),
+    BoundedTypeArgument(Option<Span>),
+    #[cfg(feature = "jsx")]
+    MismatchedJsxTag(Option<Span>, Option<Span>) // the opening and closing tags
 }
 
 impl StdError for Error {
@@ -52,16 +96,59 @@ impl StdError for Error {
             Error::IllegalStrictBinding(_, _) => "illegal strict binding",
             Error::UnexpectedDirective(_, _) => "unexpected directive",
             Error::UnexpectedModule(_) => "unexpected module",
+            Error::UnexpectedScript(_) => "unexpected script",
             Error::ImportInScript(_) => "import in script",
             Error::ExportInScript(_) => "export in script",
+            Error::DuplicateExport(_) => "duplicate export",
+            Error::DuplicateDefaultExport(_) => "duplicate default export",
             Error::ForOfLetExpr(_) => "for-of-let expr",
             Error::DuplicateDefault(_) => "duplicate default",
             Error::StrictWith(_) => "strict with",
             Error::ThrowArgument(_) => "throw argument",
             Error::OrphanTry(_) => "orphan try",
             Error::InvalidLHS(_, ref err) => err.description(),
+            Error::InvalidParam(_, ref err) => err.description(),
+            Error::DuplicateParam(_) => "duplicate parameter",
+            Error::DuplicateBinding(_) => "duplicate binding",
+            Error::ArrowNewline(_) => "line terminator before arrow",
+            Error::InvalidTemplateEscape(_) => "invalid escape sequence in template",
+            Error::YieldInParameter(_) => "yield expression in formal parameters",
+            Error::AwaitInParameter(_) => "await expression in formal parameters",
+            Error::UnaryBeforeExponent(_) => "unparenthesized unary expression before **",
+            Error::MixedCoalesce(_) => "?? mixed with || or && without parentheses",
+            Error::OptionalChainTemplate(_) => "tagged template in optional chain",
+            Error::IllegalSuperProperty(_) => "super property outside of a method",
+            Error::IllegalSuperCall(_) => "super call outside of a derived class constructor",
+            Error::ImportMetaInScript(_) => "import.meta outside of a module",
+            Error::ForAwaitInScript(_) => "for await outside of an async function or module",
+            Error::ForAwaitWithoutOf(_) => "for await without of",
             Error::UnsupportedFeature(_) => "unsupported feature",
-            Error::CompoundParamWithUseStrict(_) => "compound param with use strict",
+            Error::NonSimpleParamWithUseStrict(_) => "non-simple param with use strict",
+            Error::CoverInitializedName(_) => "invalid shorthand property initializer",
+            Error::DuplicateConstructor(_) => "duplicate constructor",
+            Error::GeneratorConstructor(_) => "generator constructor",
+            Error::AsyncConstructor(_) => "async constructor",
+            Error::AccessorConstructor(_) => "accessor constructor",
+            Error::StaticPrototype(_) => "static prototype method",
+            Error::ConstructorField(_) => "field named constructor",
+            Error::PrivateConstructor(_) => "private name #constructor",
+            Error::DecoratedConstructor(_) => "decorator on a constructor",
+            Error::DuplicatePrivateName(_) => "duplicate private name",
+            Error::UndeclaredPrivateName(_) => "undeclared private name",
+            Error::UnexpectedPrivateName(_) => "unexpected private name",
+            Error::DeletePrivateField(_) => "delete of private field",
+            Error::ArgumentsInClassInit(_) => "arguments in a field initializer or static block",
+            Error::LegacyOctal(_) => "legacy octal literal",
+            Error::LegacyOctalEscape(_) => "legacy octal escape sequence",
+            Error::HtmlCommentInModule(_) => "HTML-like comment in module",
+            Error::IfFunction(_) => "function declaration as if clause",
+            Error::LabelledFunction(_) => "labelled function declaration",
+            Error::ForInVarInit(_) => "initializer in for-in head",
+            Error::DuplicateProto(_) => "duplicate __proto__ property",
+            Error::TypedForHead(_) => "type annotation in for-in or for-of head",
+            Error::BoundedTypeArgument(_) => "bound or default on a type argument",
+            #[cfg(feature = "jsx")]
+            Error::MismatchedJsxTag(_, _) => "mismatched JSX closing tag",
         }
     }
 
@@ -69,6 +156,7 @@ impl StdError for Error {
         match *self {
             Error::LexError(ref joker_err) => Some(joker_err),
             Error::InvalidLHS(_, ref cover_err) => Some(cover_err),
+            Error::InvalidParam(_, ref cover_err) => Some(cover_err),
             _ => None,
         }
     }
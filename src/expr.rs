@@ -1,13 +1,15 @@
 use joker::track::{Span, span};
 use joker::token::Token;
 use easter::punc::Unop;
-use easter::expr::{Expr, ExprListItem};
-use easter::obj::DotKey;
+use easter::expr::{Expr, ExprListItem, Template};
+use easter::obj::{DotKey, PrivateKey};
+use easter::types::Type;
 
 pub enum Prefix {
     Unop(Unop),
     Inc(Span),
-    Dec(Span)
+    Dec(Span),
+    Await(Span)
 }
 
 pub enum Postfix {
@@ -17,7 +19,9 @@ pub enum Postfix {
 
 pub enum Deref {
     Brack(Expr, Token),
-    Dot(DotKey)
+    Dot(DotKey),
+    PrivateDot(PrivateKey),
+    Template(Template)
 }
 
 impl Deref {
@@ -29,13 +33,38 @@ impl Deref {
             Deref::Dot(key) => {
                 Expr::Dot(span(&expr, &key), Box::new(expr), key)
             }
+            Deref::PrivateDot(key) => {
+                Expr::PrivateDot(span(&expr, &key), Box::new(expr), key)
+            }
+            Deref::Template(template) => {
+                Expr::TaggedTemplate(span(&expr, &template), Box::new(expr), template)
+            }
+        }
+    }
+
+    pub fn append_optional_to(self, expr: Expr) -> Expr {
+        match self {
+            Deref::Brack(deref, end) => {
+                Expr::OptionalBrack(span(&expr, &Some(end.location)), Box::new(expr), Box::new(deref))
+            }
+            Deref::Dot(key) => {
+                Expr::OptionalDot(span(&expr, &key), Box::new(expr), key)
+            }
+            Deref::PrivateDot(key) => {
+                Expr::OptionalPrivateDot(span(&expr, &key), Box::new(expr), key)
+            }
+            Deref::Template(_) => unreachable!("templates can't be optional")
         }
     }
 }
 
 pub enum Suffix {
     Deref(Deref),
-    Arguments(Arguments)
+    Arguments(Arguments),
+    OptionalDeref(Deref),
+    OptionalArguments(Arguments),
+    TypeArguments(TypeArguments),
+    OptionalTypeArguments(TypeArguments, Arguments)
 }
 
 pub struct Arguments {
@@ -48,16 +77,35 @@ impl Arguments {
         Expr::Call(span(&expr, &Some(self.end.location)), Box::new(expr), self.args)
     }
 
+    pub fn append_optional_to(self, expr: Expr) -> Expr {
+        Expr::OptionalCall(span(&expr, &Some(self.end.location)), Box::new(expr), self.args)
+    }
+
     pub fn append_to_new(self, new: Token, expr: Expr) -> Expr {
         Expr::New(span(&Some(new.location), &Some(self.end.location)), Box::new(expr), Some(self.args))
     }
 }
 
+pub struct TypeArguments {
+    pub args: Vec<Type>,
+    pub end: Token
+}
+
+impl TypeArguments {
+    pub fn append_to(self, expr: Expr) -> Expr {
+        Expr::TypeArgs(span(&expr, &Some(self.end.location)), Box::new(expr), self.args)
+    }
+}
+
 impl Suffix {
     pub fn append_to(self, expr: Expr) -> Expr {
         match self {
             Suffix::Deref(deref) => deref.append_to(expr),
-            Suffix::Arguments(args) => args.append_to(expr)
+            Suffix::Arguments(args) => args.append_to(expr),
+            Suffix::OptionalDeref(deref) => deref.append_optional_to(expr),
+            Suffix::OptionalArguments(args) => args.append_optional_to(expr),
+            Suffix::TypeArguments(args) => args.append_to(expr),
+            Suffix::OptionalTypeArguments(type_args, args) => args.append_optional_to(type_args.append_to(expr))
         }
     }
 }
@@ -25,27 +25,28 @@ mod parser;
 mod state;
 mod expr;
 mod stack;
+mod options;
 
 // type Parser<I: Iterator<Item=char>> = parser::Parser<I>;
 
 use easter::stmt::{Script, Module};
-use parser::Parser;
 use result::Result;
 
 pub use parser::Program;
+pub use options::Options;
 
 pub fn script(s: &str) -> Result<Script> {
-    Parser::from_chars(s.chars()).script(false)
+    Options::default().script(s)
 }
 
 pub fn strict(s: &str) -> Result<Script> {
-    Parser::from_chars(s.chars()).script(true)
+    Options::default().strict(s)
 }
 
 pub fn module(s: &str) -> Result<Module> {
-    Parser::from_chars(s.chars()).module()
+    Options::default().module(s)
 }
 
 pub fn program(s: &str) -> Result<Program> {
-    Parser::from_chars(s.chars()).program()
+    Options::default().program(s)
 }
@@ -0,0 +1,75 @@
+use std::default::Default;
+
+use easter::stmt::{Script, Module};
+use joker::word::Atom;
+use parser::{Parser, Program};
+use result::Result;
+use error::Error;
+
+// Settings that select the language variant a parser accepts.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Options {
+    // Accept the web browser extensions of Annex B in sloppy code: legacy
+    // octal literals and escapes, HTML-like comments, function declarations
+    // as if clauses and labelled statements, and initializers in for-in
+    // heads. The duplicate __proto__ property rule also comes with it.
+    // Module code never accepts the extensions, and HTML-like comments are
+    // only recognized in scripts. The extended regular expression grammar
+    // is the exception: it applies to patterns without the u flag in any
+    // code.
+    pub annex_b: bool,
+
+    // Accept TypeScript-style type annotations on bindings, parameters and
+    // return types, optional and "this" parameters, interface and type alias
+    // declarations, type parameters on functions, methods, classes and arrow
+    // functions, type arguments on calls and superclasses, and "as" casts.
+    // With the jsx option, an arrow function can only have type parameters
+    // after "async", since "<" starts an element. The annotations can be
+    // removed with easter::strip::StripTypes.
+    pub types: bool,
+
+    // Accept decorators before classes and class elements. This makes "@"
+    // a punctuator, so it's off by default.
+    pub decorators: bool,
+
+    // Accept JSX elements and fragments as primary expressions. This needs
+    // the jsx feature; without it, "<" never starts an element.
+    pub jsx: bool
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            annex_b: true,
+            types: false,
+            decorators: false,
+            jsx: false
+        }
+    }
+}
+
+impl Options {
+    pub fn script(&self, s: &str) -> Result<Script> {
+        Parser::from_chars(*self, s.chars()).script(false)
+    }
+
+    pub fn strict(&self, s: &str) -> Result<Script> {
+        Parser::from_chars(*self, s.chars()).script(true)
+    }
+
+    pub fn module(&self, s: &str) -> Result<Module> {
+        Parser::from_chars(*self, s.chars()).module()
+    }
+
+    // Tokens can't be lexed again, so when a program that looked like a
+    // script at an await turns out to be a module, it's parsed again.
+    pub fn program(&self, s: &str) -> Result<Program> {
+        let mut parser = Parser::from_chars(*self, s.chars());
+        match parser.program() {
+            Err(Error::ContextualKeyword(location, Atom::Await)) if parser.covered_await() == Some(location) => {
+                self.module(s).map(Program::Module)
+            }
+            result => result
+        }
+    }
+}
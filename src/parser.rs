@@ -1,20 +1,28 @@
 use joker::track::*;
-use joker::token::{Token, TokenData};
+use joker::token::{Token, TokenData, StringLiteral, NumberLiteral};
 use joker::word::{Atom, Name, Reserved};
 use joker::lexer::Lexer;
-use easter::stmt::{Stmt, Block, StmtListItem, ForHead, ForInHead, ForOfHead, Case, Catch, Script, Dir, ModItem, Module};
-use easter::expr::{Expr, ExprListItem};
-use easter::decl::{Decl, Dtor, ConstDtor, DtorExt};
-use easter::patt::{Patt, RestPatt, CompoundPatt};
-use easter::fun::{Fun, Params};
-use easter::obj::{PropKey, PropVal, Prop, DotKey};
+use easter::stmt::{Stmt, Block, StmtListItem, ForHead, ForInHead, ForOfHead, Case, Catch, Script, Dir, ModItem, Module, Hashbang};
+use easter::expr::{Expr, ExprListItem, Template, TemplateString};
+use easter::decl::{Decl, Dtor, ConstDtor, DtorExt, Import, ImportSpec, Export, ExportSpec};
+use easter::patt::{Patt, RestPatt, CompoundPatt, PropPatt, AssignTarget};
+use easter::fun::{Fun, Params, Arrow, ArrowBody};
+use easter::class::{Class, ClassMember, Decorator};
+use easter::obj::{PropKey, PropVal, Prop, DotKey, PrivateKey};
 use easter::id::{Id, IdExt};
-use easter::punc::{Unop, UnopTag, ToOp, Op};
-use easter::cover::{IntoAssignTarget, IntoAssignPatt};
+use easter::punc::{Unop, UnopTag, Binop, BinopTag, LogopTag, ToOp, Op, Precedence};
+use easter::cover;
+use easter::cover::{IntoAssignTarget, IntoAssignPatt, IntoBindingPatt};
+use easter::types::{Type, TypeName, TypeParam, FunType, FunTypeParam, TypeMember, Interface, TypeAlias};
+#[cfg(feature = "jsx")]
+use easter::jsx::{JsxId, JsxElementName, JsxAttr, JsxAttrName, JsxAttrValue, JsxExprContainer, JsxChild};
+#[cfg(feature = "jsx")]
+use easter::jsx::{JsxOpeningElement, JsxClosingElement, JsxElement, JsxFragment};
 
 use std::rc::Rc;
 use std::mem::replace;
-use context::{Context, LabelType, WithContext, Goal};
+use std::collections::HashSet;
+use context::{Context, LabelType, WithContext, Goal, PrivateScope, PrivateDecl};
 use tokens::{First, Follows, HasLabelType};
 use atom::AtomExt;
 use track::Newline;
@@ -22,8 +30,9 @@ use result::Result;
 use error::{Error, Check};
 use track::{SpanTracker, Tracking};
 use state::State;
-use expr::{Deref, Suffix, Arguments, Prefix, Postfix};
+use expr::{Deref, Suffix, Arguments, TypeArguments, Prefix, Postfix};
 use stack::{Stack, Infix};
+use options::Options;
 
 use tristate::TriState;
 pub use tristate::TriState as Strict;
@@ -33,7 +42,9 @@ pub struct Parser<I> {
     pub validate: bool,       // should we do strict mode validation as eagerly as possible?
     pub deferred: Vec<Check>, // strict mode checks that haven't been performed yet
     pub lexer: Lexer<I>,
-    pub context: Context
+    pub context: Context,
+    pub private_scopes: Vec<PrivateScope>, // one for each enclosing class body
+    pub options: Options
 }
 
 enum ProgramItems {
@@ -44,13 +55,15 @@ enum ProgramItems {
 #[derive(Debug, PartialEq, Clone)]
 pub enum Program {
     Ambiguous(Vec<Check>, Script),
+    Script(Vec<Check>, Script), // uses await as an identifier, so it can't be a module
     Module(Module)
 }
 
 impl TrackingRef for Program {
     fn tracking_ref(&self) -> &Option<Span> {
         match *self {
-            Program::Ambiguous(_, ref script) => script.tracking_ref(),
+            Program::Ambiguous(_, ref script)
+          | Program::Script(_, ref script) => script.tracking_ref(),
             Program::Module(ref module) => module.tracking_ref()
         }
     }
@@ -59,7 +72,8 @@ impl TrackingRef for Program {
 impl TrackingMut for Program {
     fn tracking_mut(&mut self) -> &mut Option<Span> {
         match *self {
-            Program::Ambiguous(_, ref mut script) => script.tracking_mut(),
+            Program::Ambiguous(_, ref mut script)
+          | Program::Script(_, ref mut script) => script.tracking_mut(),
             Program::Module(ref mut module) => module.tracking_mut()
         }
     }
@@ -68,14 +82,15 @@ impl TrackingMut for Program {
 impl Untrack for Program {
     fn untrack(&mut self) {
         match *self {
-            Program::Ambiguous(_, ref mut script) => script.untrack(),
+            Program::Ambiguous(_, ref mut script)
+          | Program::Script(_, ref mut script) => script.untrack(),
             Program::Module(ref mut module) => module.untrack()
         }
     }
 }
 
 fn unexpected_module(module: Module) -> Error {
-    let Module { location, dirs, items } = module;
+    let Module { location, dirs, items, .. } = module;
 
     // If there's a "use module" pragma, blame that.
     if let Some(Dir { location, string, .. }) = dirs.into_iter().find(|dir| dir.pragma() == "use module") {
@@ -91,24 +106,24 @@ fn unexpected_module(module: Module) -> Error {
         }
     }
 
-    // We determined it's a module for some other reason. For
-    // now this should never happen, but serves as catch-all
-    // for any future reasons we might determine a program unit
-    // is a module.
+    // We determined it's a module for some other reason, such as
+    // an import.meta expression.
     return Error::UnexpectedModule(location);
 }
 
 impl Program {
     pub fn script(self) -> Result<Script> {
         match self {
-            Program::Ambiguous(_, script) => Ok(script),
+            Program::Ambiguous(_, script)
+          | Program::Script(_, script) => Ok(script),
             Program::Module(module) => { return Err(unexpected_module(module)); }
         }
     }
 
     pub fn strict(self) -> Result<Script> {
         match self {
-            Program::Ambiguous(checks, script) => {
+            Program::Ambiguous(checks, script)
+          | Program::Script(checks, script) => {
                 for check in checks {
                     check.perform(false)?;
                 }
@@ -126,32 +141,47 @@ impl Program {
                     check.perform(true)?;
                 }
 
-                let Script { location, dirs, items } = script;
+                let Script { location, hashbang, dirs, items } = script;
 
                 Ok(Module {
                     location: location,
+                    hashbang: hashbang,
                     dirs: dirs,
                     items: items.into_iter().map(|item| item.into_mod_item()).collect()
                 })
             }
+            Program::Script(checks, script) => {
+                // One of the checks is for the use of await that made this
+                // a script.
+                for check in checks {
+                    check.perform(true)?;
+                }
+
+                Err(Error::UnexpectedScript(script.location))
+            }
             Program::Module(module) => Ok(module)
         }
     }
 }
 
 impl<I: Iterator<Item=char>> Parser<I> {
-    pub fn from_chars(i: I) -> Parser<I> {
+    pub fn from_chars(options: Options, i: I) -> Parser<I> {
         let lexer = Lexer::new(i);
-        Parser::new(true, lexer)
+        Parser::new(true, options, lexer)
     }
 
-    pub fn new(validate: bool, lexer: Lexer<I>) -> Parser<I> {
+    pub fn new(validate: bool, options: Options, mut lexer: Lexer<I>) -> Parser<I> {
+        lexer.set_html_comments(options.annex_b);
+        lexer.set_regexp_annex_b(options.annex_b);
+        lexer.set_decorators(options.decorators);
         Parser {
             goal: Goal::Unknown,
             validate: validate,
             deferred: Vec::new(),
             lexer: lexer,
-            context: Context::new()
+            context: Context::new(),
+            private_scopes: Vec::new(),
+            options: options
         }
     }
 
@@ -181,19 +211,28 @@ impl<I: Iterator<Item=char>> Parser<I> {
         Ok(None)
     }
 
-    fn set_module(&mut self) {
+    fn set_module(&mut self) -> Result<()> {
         self.goal = Goal::Module;
         self.context.strict = Strict::Yes;
+        // HTML-like comments are only recognized in scripts.
+        self.lexer.set_html_comments(false);
+        if let Some(location) = self.lexer.html_comment() {
+            return Err(Error::HtmlCommentInModule(location));
+        }
+        Ok(())
     }
 
     pub fn module(&mut self) -> Result<Module> {
         debug_assert_eq!(self.goal, Goal::Unknown);
-        self.set_module();
+        self.set_module()?;
         self.span(&mut |this| {
+            let dirs = this.body_directives()?;
+            let items = this.module_items()?;
             Ok(Module {
                 location: None,
-                dirs: this.body_directives()?,
-                items: this.module_items()?
+                hashbang: this.hashbang(),
+                dirs: dirs,
+                items: items
             })
         })
     }
@@ -205,15 +244,27 @@ impl<I: Iterator<Item=char>> Parser<I> {
 
             match this.program_items()? {
                 ProgramItems::Script(items) => {
-                    let checks = this.take_deferred();
-                    Ok(Program::Ambiguous(checks, Script {
+                    let mut checks = this.take_deferred();
+                    if let Some(location) = this.lexer.html_comment() {
+                        checks.push(Check::Module(Error::HtmlCommentInModule(location)));
+                    }
+                    let script = Script {
                         location: None,
+                        hashbang: this.hashbang(),
                         dirs: dirs,
                         items: items
-                    }))
+                    };
+                    // A covered await with nothing to say otherwise is
+                    // read as an identifier.
+                    Ok(if this.goal == Goal::Script || this.context.cover_await_goal.is_some() {
+                        Program::Script(checks, script)
+                    } else {
+                        Program::Ambiguous(checks, script)
+                    })
                 }
                 ProgramItems::Module(items) => Ok(Program::Module(Module {
                     location: None,
+                    hashbang: this.hashbang(),
                     dirs: dirs,
                     items: items
                 }))
@@ -221,23 +272,41 @@ impl<I: Iterator<Item=char>> Parser<I> {
         })
     }
 
+    // The first top-level await that program() read as an identifier
+    // without knowing the goal. If the program fails with a
+    // ContextualKeyword error there, it turned out to be a module, and the
+    // await has to be read again as an operator.
+    pub fn covered_await(&self) -> Option<Span> {
+        self.context.cover_await_goal
+    }
+
     pub fn script(&mut self, strict: bool) -> Result<Script> {
         debug_assert_eq!(self.goal, Goal::Unknown);
         self.goal = Goal::Script;
         self.context.strict = TriState::from(strict);
-        self.script_body()
+        let mut script = self.script_body()?;
+        script.hashbang = self.hashbang();
+        Ok(script)
     }
 
     fn script_body(&mut self) -> Result<Script> {
         self.span(&mut |this| {
             Ok(Script {
                 location: None,
+                hashbang: None,
                 dirs: this.body_directives()?,
                 items: this.statement_list()?
             })
         })
     }
 
+    // Only meaningful once the first token has been read.
+    fn hashbang(&mut self) -> Option<Hashbang> {
+        self.lexer.take_hashbang().map(|(location, value)| {
+            Hashbang { location: Some(location), value: value }
+        })
+    }
+
     fn body_directives(&mut self) -> Result<Vec<Dir>> {
         let mut dirs = Vec::new();
 
@@ -247,17 +316,29 @@ impl<I: Iterator<Item=char>> Parser<I> {
                     self.context.strict = Strict::Yes;
                 }
                 "use module" if !self.context.function => {
-                    self.set_module();
+                    self.set_module()?;
                 }
                 _ => {}
             }
             dirs.push(dir);
         }
 
+        // A legacy octal escape is an error even before a "use strict".
+        for dir in dirs.iter() {
+            if dir.string.has_legacy_escape() {
+                self.annex_b_check(Error::LegacyOctalEscape(dir.location))?;
+            }
+        }
+
         Ok(dirs)
     }
 
     fn force_deferred_module_validation(&mut self) -> Result<()> {
+        // An await covered as an identifier was misread; see covered_await.
+        if let Some(location) = self.context.cover_await_goal {
+            return Err(Error::ContextualKeyword(location, Atom::Await));
+        }
+
         if !self.validate {
             return Ok(());
         }
@@ -274,1422 +355,4363 @@ impl<I: Iterator<Item=char>> Parser<I> {
     fn program_items(&mut self) -> Result<ProgramItems> {
         let mut stmts: Vec<StmtListItem> = Vec::new();
 
-        loop {
-            match self.peek()?.value {
-                TokenData::EOF => break,
-                TokenData::Reserved(Reserved::Import)
-              | TokenData::Reserved(Reserved::Export) => {
-                    self.force_deferred_module_validation()?;
-                    let items = self.more_module_items(stmts.into_iter().map(|stmt| stmt.into_mod_item()).collect())?;
-                    return Ok(ProgramItems::Module(items));
-                }
-                _ => { }
+        while self.peek()?.value != TokenData::EOF {
+            let unknown = self.goal == Goal::Unknown;
+            // Decorators can come before an export declaration.
+            let mut decorators = self.decorators()?;
+            let declaration = match self.peek()?.value {
+                TokenData::Reserved(Reserved::Import) => decorators.is_empty() && !self.import_expression_follows()?,
+                TokenData::Reserved(Reserved::Export) => true,
+                _ => false
+            };
+
+            if !declaration {
+                stmts.push(self.decorated_stmt_list_item(replace(&mut decorators, Vec::new()))?);
             }
 
-            stmts.push(self.stmt_list_item(true)?);
+            // An import or export declaration means this is a module, and so
+            // does anything in the statement that required one (import.meta).
+            if declaration || (unknown && self.goal == Goal::Module) {
+                self.force_deferred_module_validation()?;
+                self.set_module()?;
+                let items = self.more_module_items(stmts.into_iter().map(|stmt| stmt.into_mod_item()).collect(), decorators)?;
+                return Ok(ProgramItems::Module(items));
+            }
         }
 
         Ok(ProgramItems::Script(stmts))
     }
 
-    fn module_items(&mut self) -> Result<Vec<ModItem>> {
-        self.more_module_items(Vec::new())
-    }
-
-    fn more_module_items(&mut self, mut items: Vec<ModItem>) -> Result<Vec<ModItem>> {
-        loop {
-            match self.peek()?.value {
-                TokenData::EOF => break,
-                // ES6: import declaration
-                TokenData::Reserved(Reserved::Import) => unimplemented!(),
-                // ES6: export declaration
-                TokenData::Reserved(Reserved::Export) => unimplemented!(),
-                _ => { }
+    // Commits to parsing a module, or fails with the given error if this is
+    // a script.
+    fn require_module(&mut self, error: Error) -> Result<()> {
+        match self.goal {
+            Goal::Script => Err(error),
+            Goal::Module => Ok(()),
+            Goal::Unknown => {
+                self.force_deferred_module_validation()?;
+                self.set_module()?;
+                Ok(())
             }
-
-            items.push(ModItem::StmtListItem(self.stmt_list_item(true)?));
         }
-
-        Ok(items)
     }
 
-    fn statement_list(&mut self) -> Result<Vec<StmtListItem>> {
-        let mut items = Vec::new();
-        while !self.peek()?.follow_statement_list() {
-            //println!("statement at: {:?}", self.peek()?.location().unwrap().start);
-            items.push(self.stmt_list_item(true)?);
+    // An "import" followed by "(" or "." starts an ImportCall or an
+    // import.meta expression rather than an ImportDeclaration.
+    fn import_expression_follows(&mut self) -> Result<bool> {
+        if self.peek()?.value != TokenData::Reserved(Reserved::Import) {
+            return Ok(false);
         }
-        Ok(items)
+        let import = self.reread(TokenData::Reserved(Reserved::Import));
+        let follows = match self.peek_op()?.value {
+            TokenData::LParen | TokenData::Dot => true,
+            _ => false
+        };
+        self.lexer.unread_token(import);
+        Ok(follows)
     }
 
-    fn function_declaration(&mut self) -> Result<Decl> {
-        self.span(&mut |this| {
-            Ok(Decl::Fun(this.function(|this| this.id(true))?))
-        })
+    fn module_items(&mut self) -> Result<Vec<ModItem>> {
+        self.more_module_items(Vec::new(), Vec::new())
     }
 
-    fn formal_parameters(&mut self) -> Result<Params> {
-        self.span(&mut |this| {
-            this.expect(TokenData::LParen)?;
-            let mut list = Vec::new();
-            let mut rest = None;
-            loop {
-                match this.peek()?.value {
-                    TokenData::RParen => {
-                        break;
-                    }
-                    TokenData::Ellipsis => {
-                        rest = Some(this.span(&mut |this| {
-                            this.reread(TokenData::Ellipsis);
-                            Ok(RestPatt {
-                                location: None,
-                                patt: this.pattern()?
-                            })
-                        })?);
-                        break;
+    // Any decorators that have already been read belong to the first item.
+    fn more_module_items(&mut self, mut items: Vec<ModItem>, mut decorators: Vec<Decorator>) -> Result<Vec<ModItem>> {
+        let mut exported = HashSet::new();
+        loop {
+            if decorators.is_empty() {
+                decorators = self.decorators()?;
+            }
+            match self.peek()?.value {
+                TokenData::EOF if decorators.is_empty() => break,
+                TokenData::Reserved(Reserved::Import) if decorators.is_empty() => {
+                    if !self.import_expression_follows()? {
+                        items.push(ModItem::Import(self.import_declaration()?));
+                        continue;
                     }
-                    _ => {
-                        list.push(this.pattern()?);
-                        if !this.matches(TokenData::Comma)? {
-                            break;
+                }
+                TokenData::Reserved(Reserved::Export) => {
+                    let export = self.export_declaration(replace(&mut decorators, Vec::new()))?;
+                    // 15.2.1.1 Static Semantics: Early Errors
+                    for name in export.exported_names() {
+                        if !exported.insert(name.value.clone()) {
+                            return Err(if name.value == "default" {
+                                Error::DuplicateDefaultExport(name.location)
+                            } else {
+                                Error::DuplicateExport(name)
+                            });
                         }
                     }
+                    items.push(ModItem::Export(export));
+                    continue;
                 }
+                _ => { }
             }
-            this.expect(TokenData::RParen)?;
-            Ok(Params {
-                location: None,
-                list: list,
-                rest: rest
-            })
-        })
-    }
 
-    fn pattern(&mut self) -> Result<Patt<Id>> {
-        match self.peek()?.value {
-            TokenData::Identifier(_) => {
-                let id = self.id(true)?;
-                Ok(Patt::Simple(id))
-            }
-            _ => {
-                let patt = self.binding_pattern()?;
-                Ok(Patt::Compound(patt))
-            }
+            let item = self.decorated_stmt_list_item(replace(&mut decorators, Vec::new()))?;
+            items.push(ModItem::StmtListItem(item));
         }
-    }
 
-    fn binding_pattern(&mut self) -> Result<CompoundPatt<Id>> {
-        if !self.peek()?.first_binding() {
-            return self.unexpected();
-        }
-        Err(Error::UnsupportedFeature("destructuring"))
+        Ok(items)
     }
 
-    fn strict_check<F>(&mut self, f: F) -> Result<()>
-        where F: FnOnce(&mut Self) -> Option<Check>
-    {
-        let strict = self.context.strict;
-        if strict != Strict::No {
-            if let Some(check) = f(self) {
-                match check {
-                    Check::Strict(error) => {
-                        if strict == Strict::Yes && self.validate {
-                            return Err(error);
-                        } else {
-                            self.deferred.push(Check::Strict(error));
+    // ImportDeclaration ::=
+    //   "import" ImportClause "from" ModuleSpecifier ";"
+    //   "import" ModuleSpecifier ";"
+    fn import_declaration(&mut self) -> Result<Import> {
+        let import_token = self.reread(TokenData::Reserved(Reserved::Import));
+        let span = SpanTracker::new(import_token.location.start);
+        let import = match self.peek()?.value {
+            TokenData::String(_) => {
+                let specifier = self.module_specifier()?;
+                Import::ForEffect(None, specifier)
+            }
+            TokenData::Star => {
+                let ns = self.namespace_import()?;
+                let specifier = self.from_clause()?;
+                Import::Namespace(None, None, ns, specifier)
+            }
+            TokenData::LBrace => {
+                let specs = self.named_imports()?;
+                let specifier = self.from_clause()?;
+                Import::Named(None, None, specs, specifier)
+            }
+            TokenData::Identifier(_) => {
+                let default = self.id(true)?;
+                if self.matches(TokenData::Comma)? {
+                    match self.peek()?.value {
+                        TokenData::Star => {
+                            let ns = self.namespace_import()?;
+                            let specifier = self.from_clause()?;
+                            Import::Namespace(None, Some(default), ns, specifier)
                         }
-                    }
-                    Check::Module(error) => {
-                        if self.goal == Goal::Module && self.validate {
-                            return Err(error);
-                        } else if self.goal != Goal::Script {
-                            self.deferred.push(Check::Module(error));
+                        TokenData::LBrace => {
+                            let specs = self.named_imports()?;
+                            let specifier = self.from_clause()?;
+                            Import::Named(None, Some(default), specs, specifier)
                         }
+                        _ => { return self.unexpected(); }
                     }
+                } else {
+                    let specifier = self.from_clause()?;
+                    Import::Default(None, default, specifier)
                 }
             }
-        }
-        Ok(())
+            _ => { return self.unexpected(); }
+        };
+        span.end_with_auto_semi(self, Newline::Required, |_| import)
     }
 
-    fn function<Id, F>(&mut self, get_id: F) -> Result<Fun<Id>>
-        where F: Fn(&mut Self) -> Result<Id>
-    {
-        self.span(&mut |this| {
-            this.reread(TokenData::Reserved(Reserved::Function));
-            let generator = this.matches(TokenData::Star)?;
-            let id = get_id(this)?;
-            let params = this.formal_parameters()?;
-            let body = this.function_body(&params.list)?;
-            Ok(Fun { location: None, id: id, params: params, body: body, generator: generator })
-        })
+    // NameSpaceImport ::= "*" "as" ImportedBinding
+    fn namespace_import(&mut self) -> Result<Id> {
+        self.reread(TokenData::Star);
+        self.expect(TokenData::Identifier(Name::Atom(Atom::As)))?;
+        self.id(true)
     }
 
-    fn function_body(&mut self, params: &[Patt<Id>]) -> Result<Script> {
-        let inner = self.context.new_function();
-        let outer = replace(&mut self.context, inner);
-        self.expect(TokenData::LBrace)?;
-        // ES6: if the body has "use strict" check for simple parameters
-        let body = self.script_body()?;
-        self.strict_check(|_| {
-            if body.dirs.iter().any(|dir| dir.pragma() == "use strict") {
-                for param in params {
-                    if let Patt::Compound(ref compound) = *param {
-                        return Some(Check::Strict(Error::CompoundParamWithUseStrict(compound.clone())));
-                    }
-                }
+    // NamedImports ::= "{" ImportSpecifier*[","] ","? "}"
+    fn named_imports(&mut self) -> Result<Vec<ImportSpec>> {
+        self.reread(TokenData::LBrace);
+        let mut specs = Vec::new();
+        loop {
+            if self.peek()?.value == TokenData::RBrace {
+                break;
             }
-            None
-        })?;
+            specs.push(self.import_specifier()?);
+            if !self.matches(TokenData::Comma)? {
+                break;
+            }
+        }
         self.expect(TokenData::RBrace)?;
-        self.context = outer;
-        Ok(body)
+        Ok(specs)
     }
 
-    fn stmt_list_item(&mut self, allow_decl: bool) -> Result<StmtListItem> {
-        (match self.peek()?.value {
-            TokenData::Reserved(Reserved::Function) => {
-                if !allow_decl {
-                    return self.unexpected();
+    // ImportSpecifier ::=
+    //   ImportedBinding
+    //   IdentifierName "as" ImportedBinding
+    fn import_specifier(&mut self) -> Result<ImportSpec> {
+        let token = self.read()?;
+        let location = token.location;
+        let imported = DotKey {
+            location: Some(location),
+            value: match token.value {
+                TokenData::Identifier(ref name) => name.as_ref().to_string(),
+                TokenData::Reserved(word) => word.into_string(),
+                _ => { return Err(Error::UnexpectedToken(token)); }
+            }
+        };
+        let local = if self.matches(TokenData::Identifier(Name::Atom(Atom::As)))? {
+            self.id(true)?
+        } else {
+            match token.value {
+                TokenData::Identifier(name) => self.new_id(true, name, location)?,
+                _ => { return Err(Error::UnexpectedToken(token)); }
+            }
+        };
+        Ok(ImportSpec { location: span(&imported, &local), imported: imported, local: local })
+    }
+
+    // ExportDeclaration ::=
+    //   "export" "*" FromClause ";"
+    //   "export" ExportClause FromClause ";"
+    //   "export" ExportClause ";"
+    //   "export" VariableStatement
+    //   "export" Declaration
+    //   "export" "default" HoistableDeclaration
+    //   "export" "default" AssignmentExpression ";"
+    //
+    // The class can have decorators either before or after "export".
+    fn export_declaration(&mut self, decorators: Vec<Decorator>) -> Result<Export> {
+        let export_token = self.reread(TokenData::Reserved(Reserved::Export));
+        let export_location = match decorators.first() {
+            Some(decorator) => span(decorator, &Some(export_token.location)),
+            None => Some(export_token.location)
+        };
+        let tracker = SpanTracker::new(export_token.location.start);
+        let types = self.options.types;
+        if !decorators.is_empty() {
+            match self.peek()?.value {
+                TokenData::Reserved(Reserved::Class) | TokenData::Reserved(Reserved::Default) => { }
+                _ => { return self.unexpected(); }
+            }
+        }
+        match self.peek()?.value {
+            TokenData::Star => {
+                self.reread(TokenData::Star);
+                let specifier = self.from_clause()?;
+                tracker.end_with_auto_semi(self, Newline::Required, |semi| Export::All(None, specifier, semi))
+            }
+            TokenData::LBrace => {
+                let (specs, reserved) = self.export_clause()?;
+                if self.matches(TokenData::Identifier(Name::Atom(Atom::From)))? {
+                    let specifier = self.module_specifier()?;
+                    return tracker.end_with_auto_semi(self, Newline::Required, |semi| {
+                        Export::NamedFrom(None, specs, specifier, semi)
+                    });
                 }
-                return self.function_declaration().map(StmtListItem::Decl);
+                // Without a FromClause, the local names are identifier references.
+                if let Some(token) = reserved {
+                    return Err(Error::UnexpectedToken(token));
+                }
+                for spec in specs.iter() {
+                    self.new_id(false, Name::from(spec.local.value.clone()), spec.local.location.unwrap())?;
+                }
+                tracker.end_with_auto_semi(self, Newline::Required, |semi| Export::Named(None, specs, semi))
             }
-            TokenData::LBrace                       => self.block().map(Stmt::Block),
-            TokenData::Reserved(Reserved::Var)      => self.var_statement(),
-            TokenData::Reserved(Reserved::Const)    => {
-                if !allow_decl {
+            TokenData::Reserved(Reserved::Var) => {
+                self.reread(TokenData::Reserved(Reserved::Var));
+                let dtors = self.comma_separated(Self::declarator)?;
+                tracker.end_with_auto_semi(self, Newline::Required, |semi| Export::Var(None, dtors, semi))
+            }
+            TokenData::Reserved(Reserved::Const) => {
+                let decl = self.const_declaration()?;
+                Ok(Export::Decl(span(&export_location, &decl), decl))
+            }
+            TokenData::Identifier(Name::Atom(Atom::Let)) => {
+                let let_token = self.reread(TokenData::Identifier(Name::Atom(Atom::Let)));
+                let decl = self.let_declaration(let_token.location.start)?;
+                Ok(Export::Decl(span(&export_location, &decl), decl))
+            }
+            TokenData::Reserved(Reserved::Function) => {
+                let decl = self.function_declaration()?;
+                Ok(Export::Decl(span(&export_location, &decl), decl))
+            }
+            TokenData::Identifier(Name::Atom(Atom::Async)) => {
+                let async_token = self.reread(TokenData::Identifier(Name::Atom(Atom::Async)));
+                if !self.async_function_follows()? {
                     return self.unexpected();
                 }
-                return self.const_declaration().map(StmtListItem::Decl);
+                let decl = Decl::Fun(self.async_function(async_token, |this, _| this.id(true))?);
+                Ok(Export::Decl(span(&export_location, &decl), decl))
             }
-            TokenData::Semi                         => self.empty_statement(),
-            TokenData::Reserved(Reserved::If)       => self.if_statement(),
-            TokenData::Reserved(Reserved::Continue) => self.continue_statement(),
-            TokenData::Reserved(Reserved::Break)    => self.break_statement(),
-            TokenData::Reserved(Reserved::Return)   => self.return_statement(),
-            TokenData::Reserved(Reserved::With)     => self.with_statement(),
-            TokenData::Reserved(Reserved::Switch)   => self.switch_statement(),
-            TokenData::Reserved(Reserved::Throw)    => self.throw_statement(),
-            TokenData::Reserved(Reserved::Try)      => self.try_statement(),
-            TokenData::Reserved(Reserved::While)    => self.while_statement(),
-            TokenData::Reserved(Reserved::Do)       => self.do_statement(),
-            TokenData::Reserved(Reserved::For)      => self.for_statement(),
-            TokenData::Reserved(Reserved::Debugger) => self.debugger_statement(),
-            TokenData::Identifier(_)                => {
+            TokenData::Reserved(Reserved::Class) => {
+                let decl = self.class_declaration(decorators)?;
+                Ok(Export::Decl(span(&export_location, &decl), decl))
+            }
+            TokenData::At => {
+                let decorators = self.decorators()?;
+                let decl = self.class_declaration(decorators)?;
+                Ok(Export::Decl(span(&export_location, &decl), decl))
+            }
+            TokenData::Identifier(_) if types => {
                 let token = self.lexer.reread_token();
-                match self.peek_op()?.value {
-                    TokenData::Colon => {
-                        let id = self.new_id_from_token(false, token)?;
-                        self.labelled_statement(id)
-                    },
-                    TokenData::Identifier(_) | TokenData::LBrace | TokenData::LBrack if token.value == TokenData::Identifier(Name::Atom(Atom::Let)) => {
-                        if !allow_decl {
-                            return self.unexpected();
-                        }
-                        return self.let_declaration(token.location.start).map(StmtListItem::Decl);
-                    },
+                if !self.type_declaration_follows(&token)? {
+                    return Err(Error::UnexpectedToken(token));
+                }
+                let decl = self.type_declaration(token)?;
+                Ok(Export::Decl(span(&export_location, &decl), decl))
+            }
+            TokenData::Reserved(Reserved::Default) => {
+                self.reread(TokenData::Reserved(Reserved::Default));
+                if !decorators.is_empty() {
+                    let class = self.class(decorators, |this| this.id_opt(true))?;
+                    return Ok(Export::DefaultClass(span(&export_location, &class), class));
+                }
+                if let Some(async_token) = self.matches_token(TokenData::Identifier(Name::Atom(Atom::Async)))? {
+                    if self.async_function_follows()? {
+                        let fun = self.async_function(async_token, |this, _| this.id_opt(true))?;
+                        return Ok(Export::DefaultFun(span(&export_location, &fun), fun));
+                    }
+                    self.lexer.unread_token(async_token);
+                }
+                match self.peek()?.value {
+                    TokenData::Reserved(Reserved::Function) => {
+                        let fun = self.function(|this, _| this.id_opt(true))?;
+                        Ok(Export::DefaultFun(span(&export_location, &fun), fun))
+                    }
+                    TokenData::Reserved(Reserved::Class) | TokenData::At => {
+                        let decorators = self.decorators()?;
+                        let class = self.class(decorators, |this| this.id_opt(true))?;
+                        Ok(Export::DefaultClass(span(&export_location, &class), class))
+                    }
                     _ => {
-                        self.lexer.unread_token(token);
-                        self.expression_statement()
+                        let expr = self.allow_in(true, |this| this.assignment_expression())?;
+                        tracker.end_with_auto_semi(self, Newline::Required, |semi| Export::DefaultExpr(None, expr, semi))
                     }
                 }
             }
-            _  => self.expression_statement()
-        }).map(StmtListItem::Stmt)
-    }
-
-    fn statement(&mut self) -> Result<Stmt> {
-        self.stmt_list_item(false).map(|item| match item {
-            StmtListItem::Stmt(stmt) => stmt,
-            _ => unreachable!()
-        })
+            _ => self.unexpected()
+        }
     }
 
-    fn labelled_statement(&mut self, id: Id) -> Result<Stmt> {
-        self.reread(TokenData::Colon);
-
-        let mut labels = vec![id]; // vector of consecutive labels
-
-        while let TokenData::Identifier(_) = self.peek()?.value {
-            let token = self.lexer.reread_token();
-            if !self.matches_op(TokenData::Colon)? {
-                self.lexer.unread_token(token);
+    // ExportClause ::= "{" ExportSpecifier*[","] ","? "}"
+    //
+    // Also returns the first reserved word used as a local name, which is
+    // only legal if the clause is followed by a FromClause.
+    fn export_clause(&mut self) -> Result<(Vec<ExportSpec>, Option<Token>)> {
+        self.reread(TokenData::LBrace);
+        let mut specs = Vec::new();
+        let mut reserved = None;
+        loop {
+            if self.peek()?.value == TokenData::RBrace {
+                break;
+            }
+            if reserved.is_none() {
+                if let TokenData::Reserved(_) = self.peek()?.value {
+                    reserved = Some(self.lexer.repeek_token().clone());
+                }
+            }
+            specs.push(self.export_specifier()?);
+            if !self.matches(TokenData::Comma)? {
                 break;
             }
-            labels.push(self.new_id_from_token(false, token)?);
         }
+        self.expect(TokenData::RBrace)?;
+        Ok((specs, reserved))
+    }
 
-        let label_type = self.peek()?.label_type();
-        self.with_labels(labels, label_type, |this| this.statement())
+    // ExportSpecifier ::=
+    //   IdentifierName
+    //   IdentifierName "as" IdentifierName
+    fn export_specifier(&mut self) -> Result<ExportSpec> {
+        let local = self.id_name()?;
+        let exported = if self.matches(TokenData::Identifier(Name::Atom(Atom::As)))? {
+            self.id_name()?
+        } else {
+            local.clone()
+        };
+        Ok(ExportSpec { location: span(&local, &exported), local: local, exported: exported })
     }
 
-    fn expression_statement(&mut self) -> Result<Stmt> {
-        let span = self.start();
-        let expr = self.allow_in(true, |this| this.expression())?;
-        Ok(span.end_with_auto_semi(self, Newline::Required, |semi| Stmt::Expr(None, expr, semi))?)
+    // FromClause ::= "from" ModuleSpecifier
+    fn from_clause(&mut self) -> Result<StringLiteral> {
+        self.expect(TokenData::Identifier(Name::Atom(Atom::From)))?;
+        self.module_specifier()
     }
 
-    fn block(&mut self) -> Result<Block> {
+    fn module_specifier(&mut self) -> Result<StringLiteral> {
+        let token = self.read()?;
+        self.legacy_literal_check(&token)?;
+        match token.value {
+            TokenData::String(literal) => Ok(literal),
+            _ => Err(Error::UnexpectedToken(token))
+        }
+    }
+
+    fn statement_list(&mut self) -> Result<Vec<StmtListItem>> {
+        let mut items = Vec::new();
+        while !self.peek()?.follow_statement_list() {
+            //println!("statement at: {:?}", self.peek()?.location().unwrap().start);
+            items.push(self.stmt_list_item(true)?);
+        }
+        Ok(items)
+    }
+
+    fn function_declaration(&mut self) -> Result<Decl> {
         self.span(&mut |this| {
-            this.expect(TokenData::LBrace)?;
-            let items = this.statement_list()?;
-            this.expect(TokenData::RBrace)?;
-            Ok(Block {
-                location: None,
-                items: items
-            })
+            Ok(Decl::Fun(this.function(|this, _| this.id(true))?))
         })
     }
 
-    fn var_statement(&mut self) -> Result<Stmt> {
-        let span = self.start();
-        self.reread(TokenData::Reserved(Reserved::Var));
-        let dtors = self.comma_separated(Self::declarator)?;
-        span.end_with_auto_semi(self, Newline::Required, |semi| Stmt::Var(None, dtors, semi))
+    // A StatementListItem after a non-empty DecoratorList has to be a class
+    // declaration. An export declaration in a script is read for the error.
+    fn decorated_stmt_list_item(&mut self, decorators: Vec<Decorator>) -> Result<StmtListItem> {
+        if decorators.is_empty() {
+            return self.stmt_list_item(true);
+        }
+        if self.goal == Goal::Script && self.peek()?.value == TokenData::Reserved(Reserved::Export) {
+            return Err(Error::ExportInScript(self.export_declaration(decorators)?));
+        }
+        self.class_declaration(decorators).map(StmtListItem::Decl)
     }
 
-    fn let_declaration(&mut self, start: Posn) -> Result<Decl> {
-        let span = SpanTracker::new(start);
-        let dtors = self.comma_separated(Self::declarator)?;
-        span.end_with_auto_semi(self, Newline::Required, |semi| Decl::Let(None, dtors, semi))
+    // DecoratorList ::= Decorator*
+    //
+    // The lexer only produces "@" when decorators are enabled.
+    fn decorators(&mut self) -> Result<Vec<Decorator>> {
+        let mut decorators = Vec::new();
+        while self.peek()?.value == TokenData::At {
+            decorators.push(self.decorator()?);
+        }
+        Ok(decorators)
     }
 
-    fn const_declaration(&mut self) -> Result<Decl> {
-        let span = self.start();
-        self.reread(TokenData::Reserved(Reserved::Const));
-        let dtors = self.comma_separated(Self::const_declarator)?;
-        span.end_with_auto_semi(self, Newline::Required, |semi| Decl::Const(None, dtors, semi))
+    // Decorator ::=
+    //   "@" DecoratorMemberExpression Arguments?
+    //   "@" "(" Expression ")"
+    //
+    // DecoratorMemberExpression ::=
+    //   IdentifierReference
+    //   DecoratorMemberExpression "." IdentifierName
+    //   DecoratorMemberExpression "." PrivateName
+    fn decorator(&mut self) -> Result<Decorator> {
+        let at_location = Some(self.reread(TokenData::At).location);
+        if self.matches(TokenData::LParen)? {
+            let expr = self.allow_in(true, |this| this.expression())?;
+            let end_location = Some(self.expect(TokenData::RParen)?.location);
+            return Ok(Decorator { location: span(&at_location, &end_location), expr: expr });
+        }
+        let mut expr = Expr::Id(self.id(false)?);
+        while self.peek_op()?.value == TokenData::Dot {
+            expr = self.deref_dot()?.append_to(expr);
+        }
+        if self.peek_op()?.value == TokenData::LParen {
+            expr = self.arguments()?.append_to(expr);
+        }
+        Ok(Decorator { location: span(&at_location, &expr), expr: expr })
     }
 
-    fn new_id_from_token(&mut self, binding: bool, token: Token) -> Result<Id> {
-        match token.value {
-            TokenData::Identifier(name) => self.new_id(binding, name, token.location),
-            _ => unreachable!()
-        }
+    fn class_declaration(&mut self, decorators: Vec<Decorator>) -> Result<Decl> {
+        Ok(Decl::Class(self.class(decorators, |this| this.id(true))?))
     }
 
-    fn new_id(&mut self, binding: bool, name: Name, location: Span) -> Result<Id> {
-        self.strict_check(|_| {
-            if binding && name.is_illegal_strict_binding() {
-                return Some(Check::Strict(Error::IllegalStrictBinding(location, name.atom().unwrap())));
-            }
-            let is_reserved = name.is_strict_reserved();
-            if is_reserved != TriState::No {
-                let error = Error::ContextualKeyword(location, name.atom().unwrap());
-                return Some(if is_reserved == TriState::Yes {
-                    Check::Strict(error)
-                } else {
-                    // TriState::Unknown means word is reserved only
-                    // for module goal
-                    Check::Module(error)
-                });
+    // ClassDeclaration ::= DecoratorList? "class" BindingIdentifier ClassTail
+    // ClassExpression ::= DecoratorList? "class" BindingIdentifier? ClassTail
+    //
+    // The decorators have already been read, and are evaluated outside of
+    // the class, so they aren't strict mode code.
+    fn class<Id, F>(&mut self, decorators: Vec<Decorator>, get_id: F) -> Result<Class<Id>>
+        where F: Fn(&mut Self) -> Result<Id>
+    {
+        let class_location = Some(self.expect(TokenData::Reserved(Reserved::Class))?.location);
+        let class_location = match decorators.first() {
+            Some(decorator) => span(decorator, &class_location),
+            None => class_location
+        };
+        // All parts of a class are strict mode code.
+        let strict = replace(&mut self.context.strict, Strict::Yes);
+        let result = self.class_tail(class_location, decorators, get_id);
+        self.context.strict = strict;
+        result
+    }
+
+    // ClassTail ::= TypeParameters? ("extends" LHSExpression TypeArguments?)? "{" (ClassElement | ";")* "}"
+    //
+    // The type parameters and arguments are only allowed in types mode.
+    fn class_tail<Id, F>(&mut self, class_location: Option<Span>, decorators: Vec<Decorator>, get_id: F) -> Result<Class<Id>>
+        where F: Fn(&mut Self) -> Result<Id>
+    {
+        let id = get_id(self)?;
+        let type_params = self.type_parameters_opt()?;
+        let extends = if self.matches(TokenData::Reserved(Reserved::Extends))? {
+            let mut extends = self.lhs_expression()?;
+            if self.type_parameters_follow()? {
+                let (args, end) = self.type_arguments()?;
+                extends = TypeArguments { args: args, end: end }.append_to(extends);
             }
+            Some(Box::new(extends))
+        } else {
             None
-        })?;
-        Ok(Id::new(name, Some(location)))
+        };
+        let derived = extends.is_some();
+        self.expect(TokenData::LBrace)?;
+        self.private_scopes.push(PrivateScope::new());
+        let mut body = Vec::new();
+        let mut has_constructor = false;
+        loop {
+            match self.peek()?.value {
+                TokenData::RBrace => {
+                    break;
+                }
+                TokenData::Semi => {
+                    self.reread(TokenData::Semi);
+                }
+                _ => {
+                    let member = self.class_element(derived)?;
+                    if let ClassMember::Constructor(ref fun) = member {
+                        if has_constructor {
+                            return Err(Error::DuplicateConstructor(fun.location));
+                        }
+                        has_constructor = true;
+                    }
+                    body.push(member);
+                }
+            }
+        }
+        let end_location = Some(self.expect(TokenData::RBrace)?.location);
+        self.resolve_private_scope()?;
+        Ok(Class {
+            location: span(&class_location, &end_location),
+            decorators: decorators,
+            id: id,
+            type_params: type_params,
+            extends: extends,
+            body: body
+        })
     }
 
-    fn id(&mut self, binding: bool) -> Result<Id> {
-        let Token { location, newline, value: data } = self.read()?;
-        match data {
-            TokenData::Identifier(name) => self.new_id(binding, name, location),
-            _ => Err(Error::UnexpectedToken(Token {
-                location: location,
-                newline: newline,
-                value: data
-            }))
+    // ClassElement ::=
+    //   DecoratorList? "static"? MethodDefinition
+    //   DecoratorList? "static"? FieldDefinition
+    //   "static" "{" StatementList "}"
+    fn class_element(&mut self, derived: bool) -> Result<ClassMember> {
+        let decorators = self.decorators()?;
+        let member = self.undecorated_class_element(derived, !decorators.is_empty())?;
+        if decorators.is_empty() {
+            return Ok(member);
         }
+        if let ClassMember::Constructor(_) = member {
+            return Err(Error::DecoratedConstructor(decorators[0].location));
+        }
+        Ok(ClassMember::Decorated(span(&decorators[0], &member), decorators, Box::new(member)))
     }
 
-    fn id_opt(&mut self, binding: bool) -> Result<Option<Id>> {
-        let next = self.read()?;
-        match next.value {
-            TokenData::Identifier(name) => self.new_id(binding, name, next.location).map(Some),
+    fn undecorated_class_element(&mut self, derived: bool, decorated: bool) -> Result<ClassMember> {
+        let first = self.read()?;
+        let start_location = Some(first.location);
+        let is_static = match first.value {
+            TokenData::Identifier(Name::Atom(Atom::Static)) => {
+                match self.peek()?.value {
+                    TokenData::LBrace if decorated => {
+                        return self.unexpected();
+                    }
+                    TokenData::LBrace => {
+                        return self.static_block(start_location);
+                    }
+                    TokenData::LParen | TokenData::Assign | TokenData::Semi | TokenData::RBrace => {
+                        self.lexer.unread_token(first);
+                        false
+                    }
+                    _ => true
+                }
+            }
             _ => {
-                self.lexer.unread_token(next);
-                Ok(None)
+                self.lexer.unread_token(first);
+                false
+            }
+        };
+        let member = self.method_definition(start_location, is_static, derived)?;
+        match member {
+            ClassMember::Get(_, is_static, PropKey::Private(ref key), _) => {
+                self.declare_private(key, PrivateDecl::Get(is_static))?;
+            }
+            ClassMember::Set(_, is_static, PropKey::Private(ref key), _, _) => {
+                self.declare_private(key, PrivateDecl::Set(is_static))?;
+            }
+            ClassMember::Method(_, _, Fun { id: PropKey::Private(ref key), .. })
+          | ClassMember::Field(_, _, PropKey::Private(ref key), _) => {
+                self.declare_private(key, PrivateDecl::Other)?;
+            }
+            _ => { }
+        }
+        // 14.5.1 Static Semantics: Early Errors
+        match member {
+            ClassMember::Method(location, false, ref fun) if fun.generator && fun.id.is_name("constructor") => {
+                Err(Error::GeneratorConstructor(location))
+            }
+            ClassMember::Method(location, false, ref fun) if fun.asynchronous && fun.id.is_name("constructor") => {
+                Err(Error::AsyncConstructor(location))
             }
+            ClassMember::Get(location, false, ref key, _)
+          | ClassMember::Set(location, false, ref key, _, _) if key.is_name("constructor") => {
+                Err(Error::AccessorConstructor(location))
+            }
+            ClassMember::Field(location, _, ref key, _) if key.is_name("constructor") => {
+                Err(Error::ConstructorField(location))
+            }
+            ClassMember::Method(location, true, Fun { id: ref key, .. })
+          | ClassMember::Get(location, true, ref key, _)
+          | ClassMember::Set(location, true, ref key, _, _)
+          | ClassMember::Field(location, true, ref key, _) if key.is_name("prototype") => {
+                Err(Error::StaticPrototype(location))
+            }
+            ClassMember::Method(_, false, fun) if fun.id.is_name("constructor") => {
+                Ok(ClassMember::Constructor(fun))
+            }
+            _ => Ok(member)
         }
     }
 
-    fn declarator(&mut self) -> Result<Dtor> {
-        self.span(&mut |this| {
-            match this.peek()?.value {
-                TokenData::Identifier(_) => {
-                    let id = this.id(true)?;
-                    let init = if this.matches(TokenData::Assign)? {
-                        Some(this.assignment_expression()?)
-                    } else {
-                        None
-                    };
-                    Ok(Dtor::Simple(None, id, init))
-                }
-                _ => {
-                    let lhs = this.binding_pattern()?;
-                    this.expect(TokenData::Assign)?;
-                    let rhs = this.assignment_expression()?;
-                    Ok(Dtor::Compound(None, lhs, rhs))
-                }
-            }
-        })
+    // "static" . "{" StatementList "}"
+    //
+    // The block is parsed like a method body, except that it can't return.
+    fn static_block(&mut self, start_location: Option<Span>) -> Result<ClassMember> {
+        let (items, end_location) = self.in_static_block(|this| {
+            this.expect(TokenData::LBrace)?;
+            let items = this.statement_list()?;
+            Ok((items, Some(this.expect(TokenData::RBrace)?.location)))
+        })?;
+        Ok(ClassMember::StaticBlock(Block {
+            location: span(&start_location, &end_location),
+            items: items
+        }))
     }
 
-    fn const_declarator(&mut self) -> Result<ConstDtor> {
-        let lhs = self.pattern()?;
-        self.expect(TokenData::Assign)?;
-        let rhs = self.assignment_expression()?;
-        Ok(ConstDtor::from_init(lhs, rhs))
+    // FieldDefinition ::= ClassElementName . ("=" AssignmentExpression)? ";"
+    //
+    // The initializer is evaluated like a method body, with the instance (or
+    // the class, for static fields) as this.
+    fn field_definition(&mut self, start_location: Option<Span>, is_static: bool, key: PropKey) -> Result<ClassMember> {
+        let init = if self.matches_op(TokenData::Assign)? {
+            Some(self.in_class_init(false, |this| {
+                this.allow_in(true, |this| this.assignment_expression())
+            })?)
+        } else {
+            None
+        };
+        let span = SpanTracker::new(start_location.unwrap().start);
+        span.end_with_auto_semi(self, Newline::Required, |_| ClassMember::Field(None, is_static, key, init))
     }
 
-    fn empty_statement(&mut self) -> Result<Stmt> {
-        self.span(&mut |this| {
-            this.expect(TokenData::Semi)?;
-            Ok(Stmt::Empty(None))
-        })
+    // ClassElementName ::=
+    //   PropertyName
+    //   PrivateName
+    fn class_element_name_opt(&mut self) -> Result<Option<PropKey>> {
+        if let TokenData::PrivateName(_) = self.peek()?.value {
+            return self.private_name().map(|key| Some(PropKey::Private(key)));
+        }
+        self.property_key_opt()
     }
 
-    fn if_statement(&mut self) -> Result<Stmt> {
-        self.span(&mut |this| {
-            this.expect(TokenData::Reserved(Reserved::If))?;
-            let test = this.paren_expression()?;
-            let cons = Box::new(this.statement()?);
-            let alt = if this.peek()?.value == TokenData::Reserved(Reserved::Else) {
-                this.reread(TokenData::Reserved(Reserved::Else));
-                Some(Box::new(this.statement()?))
-            } else {
-                None
-            };
-            Ok(Stmt::If(None, test, cons, alt))
-        })
+    fn class_element_name(&mut self) -> Result<PropKey> {
+        match self.class_element_name_opt()? {
+            Some(key) => Ok(key),
+            None => self.unexpected()
+        }
     }
 
-    fn iteration_body(&mut self) -> Result<Stmt> {
-        let iteration = replace(&mut self.context.iteration, true);
-        let result = self.statement();
-        replace(&mut self.context.iteration, iteration);
-        result
+    fn private_name(&mut self) -> Result<PrivateKey> {
+        let token = self.read()?;
+        match token.value {
+            TokenData::PrivateName(name) => Ok(PrivateKey { location: Some(token.location), value: name }),
+            _ => Err(Error::UnexpectedToken(token))
+        }
     }
 
-    fn do_statement(&mut self) -> Result<Stmt> {
-        let span = self.start();
-        self.reread(TokenData::Reserved(Reserved::Do));
-        let body = Box::new(self.iteration_body()?);
-        self.expect(TokenData::Reserved(Reserved::While))?;
-        let test = self.paren_expression()?;
-        Ok(span.end_with_auto_semi(self, Newline::Optional, |semi| {
-            Stmt::DoWhile(None, body, test, semi)
-        })?)
+    fn declare_private(&mut self, key: &PrivateKey, decl: PrivateDecl) -> Result<()> {
+        if key.value == "constructor" {
+            return Err(Error::PrivateConstructor(key.location));
+        }
+        let scope = self.private_scopes.last_mut().unwrap();
+        let decl = match (scope.declared.get(&key.value), decl) {
+            (None, decl) => decl,
+            (Some(&PrivateDecl::Get(get_static)), PrivateDecl::Set(set_static))
+          | (Some(&PrivateDecl::Set(set_static)), PrivateDecl::Get(get_static)) if get_static == set_static => {
+                PrivateDecl::Other
+            }
+            _ => { return Err(Error::DuplicatePrivateName(key.clone())); }
+        };
+        scope.declared.insert(key.value.clone(), decl);
+        Ok(())
     }
 
-    fn while_statement(&mut self) -> Result<Stmt> {
-        self.span(&mut |this| {
-            this.reread(TokenData::Reserved(Reserved::While));
-            let test = this.paren_expression()?;
-            let body = Box::new(this.iteration_body()?);
-            Ok(Stmt::While(None, test, body))
-        })
+    // A private name has to be declared by an enclosing class, but it can
+    // be declared after the reference, so references are only checked when
+    // the class body is done (see resolve_private_scope).
+    fn reference_private(&mut self, key: PrivateKey) -> Result<()> {
+        match self.private_scopes.last_mut() {
+            Some(scope) => {
+                scope.references.push(key);
+                Ok(())
+            }
+            None => Err(Error::UndeclaredPrivateName(key))
+        }
     }
 
-    fn for_statement(&mut self) -> Result<Stmt> {
-        self.span(&mut |this| {
-            this.reread(TokenData::Reserved(Reserved::For));
-            this.expect(TokenData::LParen)?;
-            match this.peek()?.value {
-                TokenData::Reserved(Reserved::Var)           => this.for_var(),
-                TokenData::Identifier(Name::Atom(Atom::Let)) => this.for_let(),
-                TokenData::Reserved(Reserved::Const)         => this.for_const(),
-                TokenData::Semi                              => {
-                    this.reread(TokenData::Semi);
-                    this.more_for(None)
-                }
-                _                                            => this.for_expr()
+    fn private_reference(&mut self) -> Result<PrivateKey> {
+        let key = self.private_name()?;
+        self.reference_private(key.clone())?;
+        Ok(key)
+    }
+
+    // Pops the innermost class body's private scope, passing any names it
+    // doesn't declare out to the enclosing class body.
+    fn resolve_private_scope(&mut self) -> Result<()> {
+        let scope = self.private_scopes.pop().unwrap();
+        for key in scope.references {
+            if !scope.declared.contains_key(&key.value) {
+                self.reference_private(key)?;
             }
-        })
+        }
+        Ok(())
     }
 
-    // 'for' '(' 'var' .
-    fn for_var(&mut self) -> Result<Stmt> {
-        let var_token = self.reread(TokenData::Reserved(Reserved::Var));
-        let var_location = Some(var_token.location);
-        let lhs = self.pattern()?;
-        match self.peek()?.value {
-            // 'for' '(' 'var' id   '=' .
-            // 'for' '(' 'var' patt '=' . ==> C-style
-            TokenData::Assign => {
-                self.reread(TokenData::Assign);
-                match lhs {
-                    Patt::Simple(id) => {
-                        let rhs = self.allow_in(false, |this| this.assignment_expression())?;
-                        match self.peek()?.value {
-                            // 'for' '(' 'var' id '=' expr ','  . ==> C-style
-                            // 'for' '(' 'var' id '=' expr ';'  . ==> C-style
-                            TokenData::Comma
-                          | TokenData::Semi => {
-                                self.more_for_head(&var_location, Dtor::from_simple_init(id, rhs), ForHead::Var)
-                            }
-                            // 'for' '(' 'var' id '=' expr 'in' . ==> legacy enumeration
-                            TokenData::Reserved(Reserved::In) => {
-                                self.reread(TokenData::Reserved(Reserved::In));
-                                let head = Box::new(ForInHead::VarInit(span(&var_location, &rhs), id, rhs));
-                                self.more_for_in(head)
-                            }
-                            _ => self.unexpected()
-                        }
-                    }
-                    // 'for' '(' 'var' patt '=' . ==> C-style
-                    Patt::Compound(patt) => {
-                        let rhs = self.allow_in(false, |this| this.assignment_expression())?;
-                        self.more_for_head(&var_location, Dtor::from_compound_init(patt, rhs), ForHead::Var)
+    // MethodDefinition ::=
+    //   ClassElementName "(" FormalParameters ")" "{" FunctionBody "}"
+    //   "*" ClassElementName "(" FormalParameters ")" "{" FunctionBody "}"
+    //   "async" [no line terminator] "*"? ClassElementName "(" FormalParameters ")" "{" AsyncFunctionBody "}"
+    //   "get" ClassElementName "(" ")" "{" FunctionBody "}"
+    //   "set" ClassElementName "(" BindingElement ")" "{" FunctionBody "}"
+    //
+    // A ClassElementName that isn't followed by "(" starts a FieldDefinition.
+    fn method_definition(&mut self, start_location: Option<Span>, is_static: bool, derived: bool) -> Result<ClassMember> {
+        let first = self.read()?;
+        let (key, generator, asynchronous) = match first.value {
+            TokenData::Identifier(Name::Atom(Atom::Get)) => {
+                if let Some(key) = self.class_element_name_opt()? {
+                    self.expect(TokenData::LParen)?;
+                    self.expect(TokenData::RParen)?;
+                    let body = self.in_method(false, false, false, |this| this.function_body(&vec![], &None))?;
+                    return Ok(ClassMember::Get(span(&start_location, &body), is_static, key, body));
+                }
+                (PropKey::Id(Some(first.location), "get".to_string()), false, false)
+            }
+            TokenData::Identifier(Name::Atom(Atom::Set)) => {
+                if let Some(key) = self.class_element_name_opt()? {
+                    self.expect(TokenData::LParen)?;
+                    let (param, body) = self.in_method(false, false, false, |this| {
+                        let param = this.formal_parameter()?;
+                        this.expect(TokenData::RParen)?;
+                        let body = this.function_body(&[param.clone()], &None)?;
+                        Ok((param, body))
+                    })?;
+                    return Ok(ClassMember::Set(span(&start_location, &body), is_static, key, param, body));
+                }
+                (PropKey::Id(Some(first.location), "set".to_string()), false, false)
+            }
+            TokenData::Identifier(Name::Atom(Atom::Async)) => {
+                let newline = self.peek()?.newline;
+                if !newline && self.matches(TokenData::Star)? {
+                    (self.class_element_name()?, true, true)
+                } else {
+                    let key = if newline { None } else { self.class_element_name_opt()? };
+                    match key {
+                        Some(key) => (key, false, true),
+                        None => (PropKey::Id(Some(first.location), "async".to_string()), false, false)
                     }
                 }
             }
-            TokenData::Comma
-          | TokenData::Semi => {
-                // 'for' '(' 'var' id   ',' . ==> C-style
-                // 'for' '(' 'var' id   ';' . ==> C-style
-                // 'for' '(' 'var' patt ',' . ==> syntax error
-                // 'for' '(' 'var' patt ';' . ==> syntax error
-                let dtor = match Dtor::from_init_opt(lhs, None) {
-                    Ok(dtor) => dtor,
-                    Err(_) => { return self.unexpected(); }
-                };
-                self.more_for_head(&var_location, dtor, ForHead::Var)
-            }
-            // 'for' '(' 'var' id   'in' . ==> enumeration
-            // 'for' '(' 'var' patt 'in' . ==> enumeration
-            TokenData::Reserved(Reserved::In) => {
-                self.reread(TokenData::Reserved(Reserved::In));
-                let head = Box::new(ForInHead::Var(span(&var_location, &lhs), lhs));
-                self.more_for_in(head)
+            TokenData::Star => {
+                (self.class_element_name()?, true, false)
             }
-            // 'for' '(' 'var' id   'of' . ==> enumeration
-            // 'for' '(' 'var' patt 'of' . ==> enumeration
-            TokenData::Identifier(Name::Atom(Atom::Of)) => {
-                self.reread(TokenData::Identifier(Name::Atom(Atom::Of)));
-                let head = Box::new(ForOfHead::Var(span(&var_location, &lhs), lhs));
-                self.more_for_of(head)
+            _ => {
+                self.lexer.unread_token(first);
+                (self.class_element_name()?, false, false)
             }
-            _ => self.unexpected()
+        };
+        if !generator && !asynchronous && self.peek_op()?.value != TokenData::LParen && !self.type_parameters_follow()? {
+            return self.field_definition(start_location, is_static, key);
         }
+        // 14.5.1 Static Semantics: Early Errors
+        let super_call = derived && !is_static && key.is_name("constructor");
+        let type_params = self.type_parameters_opt()?;
+        let (this_type, params, ret, body) = self.in_method(generator, asynchronous, super_call, |this| {
+            let (this_type, params) = this.formal_parameters()?;
+            let ret = this.type_annotation_opt()?;
+            let body = this.function_body(&params.list, &params.rest)?;
+            Ok((this_type, params, ret, body))
+        })?;
+        let fun = Fun {
+            location: span(key.tracking_ref(), body.tracking_ref()),
+            id: key,
+            params: params,
+            body: body,
+            generator: generator,
+            asynchronous: asynchronous,
+            type_params: type_params,
+            this_type: this_type,
+            ret: ret
+        };
+        Ok(ClassMember::Method(span(&start_location, &fun), is_static, fun))
     }
 
-    // 'for' '(' 'let' .
-    fn for_let(&mut self) -> Result<Stmt> {
-        let let_token = self.reread(TokenData::Identifier(Name::Atom(Atom::Let)));
-        if !self.peek()?.first_binding() {
-            self.lexer.unread_token(let_token);
-            return self.for_expr();
+    // Returns the annotation of the "this" parameter too, if there is one.
+    fn formal_parameters(&mut self) -> Result<(Option<Type>, Params)> {
+        let mut this_type = None;
+        let params = self.in_params(|this| this.span(&mut |this| {
+            this.expect(TokenData::LParen)?;
+            this_type = this.this_parameter_opt()?;
+            let mut list = Vec::new();
+            let mut rest = None;
+            loop {
+                match this.peek()?.value {
+                    TokenData::RParen => {
+                        break;
+                    }
+                    TokenData::Ellipsis => {
+                        rest = Some(this.rest_parameter()?);
+                        break;
+                    }
+                    _ => {
+                        list.push(this.formal_parameter()?);
+                        if !this.matches(TokenData::Comma)? {
+                            break;
+                        }
+                    }
+                }
+            }
+            this.expect(TokenData::RParen)?;
+            Ok(Params {
+                location: None,
+                list: list,
+                rest: rest
+            })
+        }))?;
+        // 14.1.2 Static Semantics: Early Errors: only a simple parameter
+        // list can bind the same name twice.
+        if !params.is_simple() {
+            unique_names(params.bound_names(), Error::DuplicateParam)?;
         }
-        let let_location = Some(let_token.location);
-        // 'for' '(' 'let' . !{id, patt} ==> error
-        let lhs = self.pattern()?;
+        Ok((this_type, params))
+    }
+
+    fn pattern(&mut self) -> Result<Patt<Id>> {
         match self.peek()?.value {
-            // 'for' '(' 'let' id   '=' . ==> C-style
-            // 'for' '(' 'let' patt '=' . ==> C-style
-            TokenData::Assign => {
-                self.reread(TokenData::Assign);
-                let rhs = self.allow_in(false, |this| this.assignment_expression())?;
-                self.more_for_head(&let_location, Dtor::from_init(lhs, rhs), ForHead::Let)
-            }
-            TokenData::Comma
-          | TokenData::Semi => {
-                // 'for' '(' 'let' id   ',' . ==> C-style
-                // 'for' '(' 'let' id   ';' . ==> C-style
-                // 'for' '(' 'let' patt ',' . ==> error
-                // 'for' '(' 'let' patt ';' . ==> error
-                let dtor = match Dtor::from_init_opt(lhs, None) {
-                    Ok(dtor) => dtor,
-                    Err(_) => { return self.unexpected(); }
-                };
-                self.more_for_head(&let_location, dtor, ForHead::Let)
-            }
-            // 'for' '(' 'let' id   'in' . ==> enumeration
-            // 'for' '(' 'let' patt 'in' . ==> enumeration
-            TokenData::Reserved(Reserved::In) => {
-                self.reread(TokenData::Reserved(Reserved::In));
-                let head = Box::new(ForInHead::Let(span(&let_location, &lhs), lhs));
-                self.more_for_in(head)
+            TokenData::Identifier(_) => {
+                let id = self.id(true)?;
+                Ok(Patt::Simple(id))
             }
-            // 'for' '(' 'let' id   'of' . ==> enumeration
-            // 'for' '(' 'let' patt 'of' . ==> enumeration
-            TokenData::Identifier(Name::Atom(Atom::Of)) => {
-                self.reread(TokenData::Identifier(Name::Atom(Atom::Of)));
-                let head = Box::new(ForOfHead::Let(span(&let_location, &lhs), lhs));
-                self.more_for_of(head)
+            _ => {
+                let patt = self.binding_pattern()?;
+                Ok(Patt::Compound(patt))
             }
-            _ => self.unexpected()
         }
     }
 
-    // 'for' '(' 'const' .
-    fn for_const(&mut self) -> Result<Stmt> {
-        let const_token = self.reread(TokenData::Reserved(Reserved::Const));
-        if !self.peek()?.first_binding() {
-            self.lexer.unread_token(const_token);
-            return self.for_expr();
+    // BindingElement ::= BindingTarget Initializer?
+    fn binding_element(&mut self) -> Result<Patt<Id>> {
+        let patt = self.pattern()?;
+        self.more_binding_element(patt)
+    }
+
+    // FormalParameter ::=
+    //   BindingTarget TypeAnnotation? Initializer?
+    //   BindingIdentifier "?" TypeAnnotation?
+    fn formal_parameter(&mut self) -> Result<Patt<Id>> {
+        let patt = self.pattern()?;
+        if let Some(question) = self.optional_mark_opt(&patt)? {
+            let patt = optional_patt(patt, question);
+            return self.annotated(patt);
         }
-        let const_location = Some(const_token.location);
-        // 'for' '(' 'const' . !{id, patt} ==> error
-        let lhs = self.pattern()?;
+        let patt = self.annotated(patt)?;
+        self.more_binding_element(patt)
+    }
+
+    // BindingTarget . Initializer?
+    fn more_binding_element(&mut self, patt: Patt<Id>) -> Result<Patt<Id>> {
+        if !self.matches(TokenData::Assign)? {
+            return Ok(patt);
+        }
+        let init = self.allow_in(true, |this| this.assignment_expression())?;
+        Ok(Patt::Default(span(&patt, &init), Box::new(patt), Box::new(init)))
+    }
+
+    // "..." BindingTarget
+    fn rest_pattern(&mut self) -> Result<RestPatt<Id>> {
+        let ellipsis_location = Some(self.reread(TokenData::Ellipsis).location);
+        let patt = self.pattern()?;
+        Ok(RestPatt { location: span(&ellipsis_location, &patt), patt: patt })
+    }
+
+    // "..." BindingTarget TypeAnnotation?
+    fn rest_parameter(&mut self) -> Result<RestPatt<Id>> {
+        let RestPatt { location, patt } = self.rest_pattern()?;
+        let patt = self.annotated(patt)?;
+        Ok(RestPatt { location: span(&location, &patt), patt: patt })
+    }
+
+    // BindingPattern ::=
+    //   ObjectBindingPattern
+    //   ArrayBindingPattern
+    fn binding_pattern(&mut self) -> Result<CompoundPatt<Id>> {
         match self.peek()?.value {
-            // 'for' '(' 'const' {id, patt}   '=' . ==> C-style
-            TokenData::Assign => {
-                self.reread(TokenData::Assign);
-                let dtors = self.allow_in(false, |this| {
-                    let rhs = this.assignment_expression()?;
-                    let dtor = ConstDtor::from_init(lhs, rhs);
-                    this.more_comma(dtor, Self::const_declarator)
-                })?;
-                let semi_location = Some(self.expect(TokenData::Semi)?.location);
-                let head = Box::new(ForHead::Const(span(&const_location, &semi_location), dtors));
-                self.more_for(Some(head))
-            }
-            // 'for' '(' 'const' {id, patt}   'in' . ==> enumeration
-            TokenData::Reserved(Reserved::In) => {
-                self.reread(TokenData::Reserved(Reserved::In));
-                let head = Box::new(ForInHead::Const(span(&const_location, &lhs), lhs));
-                self.more_for_in(head)
-            }
-            // 'for' '(' 'const' {id, patt}   'of' . ==> enumeration
-            TokenData::Identifier(Name::Atom(Atom::Of)) => {
-                self.reread(TokenData::Identifier(Name::Atom(Atom::Of)));
-                let head = Box::new(ForOfHead::Const(span(&const_location, &lhs), lhs));
-                self.more_for_of(head)
-            }
+            TokenData::LBrace => self.object_binding_pattern(),
+            TokenData::LBrack => self.array_binding_pattern(),
             _ => self.unexpected()
         }
     }
 
-    fn for_expr(&mut self) -> Result<Stmt> {
-        let lhs = self.allow_in(false, |this| this.expression())?;
-        match self.peek()?.value {
-            TokenData::Semi => {
-                let semi_location = Some(self.reread(TokenData::Semi).location);
-                let head = Some(Box::new(ForHead::Expr(span(&lhs, &semi_location), lhs)));
-                self.more_for(head)
+    // ObjectBindingPattern ::=
+    //   "{" BindingProperty*[","] ","? "}"
+    //   "{" (BindingProperty ",")* BindingRestProperty "}"
+    fn object_binding_pattern(&mut self) -> Result<CompoundPatt<Id>> {
+        let start_location = Some(self.reread(TokenData::LBrace).location);
+        let mut props = Vec::new();
+        let mut rest = None;
+        loop {
+            match self.peek()?.value {
+                TokenData::RBrace => {
+                    break;
+                }
+                TokenData::Ellipsis => {
+                    rest = Some(Box::new(self.binding_rest_property()?));
+                    break;
+                }
+                _ => {
+                    props.push(self.binding_property()?);
+                    if !self.matches(TokenData::Comma)? {
+                        break;
+                    }
+                }
             }
-            TokenData::Reserved(Reserved::In) => {
-                self.reread(TokenData::Reserved(Reserved::In));
-                let lhs_location = *lhs.tracking_ref();
-                let lhs = match lhs.into_assign_patt() {
-                    Ok(lhs) => lhs,
-                    Err(cover_err) => { return Err(Error::InvalidLHS(lhs_location, cover_err)); }
-                };
-                let head = Box::new(ForInHead::Patt(lhs));
-                self.more_for_in(head)
+        }
+        let end_location = Some(self.expect(TokenData::RBrace)?.location);
+        Ok(CompoundPatt::Obj(span(&start_location, &end_location), props, rest))
+    }
+
+    // BindingRestProperty ::= "..." BindingIdentifier
+    fn binding_rest_property(&mut self) -> Result<RestPatt<Id>> {
+        let ellipsis_location = Some(self.reread(TokenData::Ellipsis).location);
+        let id = self.id(true)?;
+        Ok(RestPatt { location: span(&ellipsis_location, &id), patt: Patt::Simple(id) })
+    }
+
+    // BindingProperty ::=
+    //   BindingIdentifier Initializer?
+    //   PropertyName ":" BindingElement
+    fn binding_property(&mut self) -> Result<PropPatt<Id>> {
+        let token = self.read()?;
+        let key = match token.value {
+            TokenData::Identifier(name) => {
+                if self.peek()?.value != TokenData::Colon {
+                    let id = self.new_id(true, name, token.location)?;
+                    if !self.matches(TokenData::Assign)? {
+                        return Ok(PropPatt::Shorthand(id));
+                    }
+                    let init = self.allow_in(true, |this| this.assignment_expression())?;
+                    return Ok(PropPatt::ShorthandDefault(span(&id, &init), id, init));
+                }
+                PropKey::Id(Some(token.location), name.into_string())
             }
-            TokenData::Identifier(Name::Atom(Atom::Of)) => {
-                self.reread(TokenData::Identifier(Name::Atom(Atom::Of)));
-                let lhs_location = *lhs.tracking_ref();
-                let lhs = match lhs.into_assign_patt() {
-                    Ok(lhs) => lhs,
-                    Err(cover_err) => { return Err(Error::InvalidLHS(lhs_location, cover_err)); }
-                };
-                let head = Box::new(ForOfHead::Patt(lhs));
-                self.more_for_of(head)
+            _ => {
+                self.lexer.unread_token(token);
+                self.property_key()?
+            }
+        };
+        self.expect(TokenData::Colon)?;
+        let patt = self.binding_element()?;
+        Ok(PropPatt::Regular(span(&key, &patt), key, patt))
+    }
+
+    // ArrayBindingPattern ::=
+    //   "[" (BindingElement? ",")* (BindingElement | "..." BindingTarget)? "]"
+    fn array_binding_pattern(&mut self) -> Result<CompoundPatt<Id>> {
+        let start_location = Some(self.reread(TokenData::LBrack).location);
+        let mut elts = Vec::new();
+        let mut rest = None;
+        loop {
+            match self.peek()?.value {
+                TokenData::RBrack => {
+                    break;
+                }
+                TokenData::Comma => {
+                    self.reread(TokenData::Comma);
+                    elts.push(None);
+                }
+                TokenData::Ellipsis => {
+                    rest = Some(Box::new(self.rest_pattern()?));
+                    break;
+                }
+                _ => {
+                    elts.push(Some(self.binding_element()?));
+                    if !self.matches(TokenData::Comma)? {
+                        break;
+                    }
+                }
             }
-            _ => self.unexpected()
         }
+        let end_location = Some(self.expect(TokenData::RBrack)?.location);
+        Ok(CompoundPatt::Arr(span(&start_location, &end_location), elts, rest))
     }
 
-    // 'for' '(' dtor .
-    fn more_for_head<F>(&mut self, start: &Option<Span>, dtor: Dtor, op: F) -> Result<Stmt>
-      where F: FnOnce(Option<Span>, Vec<Dtor>) -> ForHead
+    fn strict_check<F>(&mut self, f: F) -> Result<()>
+        where F: FnOnce(&mut Self) -> Option<Check>
     {
-        let dtors = self.allow_in(false, |this| {
-            this.more_comma(dtor, Self::declarator)
-        })?;
-        let semi_location = Some(self.expect(TokenData::Semi)?.location);
-        let head = Box::new(op(span(start, &semi_location), dtors));
-        self.more_for(Some(head))
+        let strict = self.context.strict;
+        if strict != Strict::No {
+            if let Some(check) = f(self) {
+                match check {
+                    Check::Strict(error) => {
+                        if strict == Strict::Yes && self.validate {
+                            return Err(error);
+                        } else {
+                            self.deferred.push(Check::Strict(error));
+                        }
+                    }
+                    Check::Module(error) => {
+                        if self.goal == Goal::Module && self.validate {
+                            return Err(error);
+                        } else if self.goal != Goal::Script {
+                            self.deferred.push(Check::Module(error));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
     }
 
-    // 'for' '(' head ';' .
-    fn more_for(&mut self, head: Option<Box<ForHead>>) -> Result<Stmt> {
-        let test = self.expression_opt_semi()?;
-        let update = if self.matches(TokenData::RParen)? {
-            None
-        } else {
-            let node = Some(self.allow_in(true, |this| this.expression())?);
-            self.expect(TokenData::RParen)?;
-            node
-        };
-        let body = Box::new(self.iteration_body()?);
-        Ok(Stmt::For(None, head, test, update, body))
+    // Checks for an Annex B extension, which is only allowed in sloppy code
+    // when the annex_b option is on.
+    fn annex_b_check(&mut self, error: Error) -> Result<()> {
+        if !self.options.annex_b {
+            return Err(error);
+        }
+        self.strict_check(|_| Some(Check::Strict(error)))
     }
 
-    // 'for' '(' head 'in' .
-    fn more_for_in(&mut self, head: Box<ForInHead>) -> Result<Stmt> {
-        let obj = self.allow_in(true, |this| this.assignment_expression())?;
-        self.expect(TokenData::RParen)?;
-        let body = Box::new(self.iteration_body()?);
-        Ok(Stmt::ForIn(None, head, obj, body))
+    // Legacy octal literals (B.1.1) and escape sequences (B.1.2).
+    fn legacy_literal_check(&mut self, token: &Token) -> Result<()> {
+        match token.value {
+            TokenData::Number(NumberLiteral { source: Some(ref source), .. }) if source.is_legacy() => {
+                self.annex_b_check(Error::LegacyOctal(Some(token.location)))
+            }
+            TokenData::String(ref literal) if literal.has_legacy_escape() => {
+                self.annex_b_check(Error::LegacyOctalEscape(Some(token.location)))
+            }
+            _ => Ok(())
+        }
     }
 
-    // 'for' '(' head 'of' .
-    fn more_for_of(&mut self, head: Box<ForOfHead>) -> Result<Stmt> {
-        let obj = self.allow_in(true, |this| this.assignment_expression())?;
-        self.expect(TokenData::RParen)?;
-        let body = Box::new(self.iteration_body()?);
-        Ok(Stmt::ForOf(None, head, obj, body))
+    // The get_id callback is told whether the function is a generator.
+    fn function<Id, F>(&mut self, get_id: F) -> Result<Fun<Id>>
+        where F: Fn(&mut Self, bool) -> Result<Id>
+    {
+        let start_location = Some(self.reread(TokenData::Reserved(Reserved::Function)).location);
+        let generator = self.matches(TokenData::Star)?;
+        self.more_function(start_location, generator, false, get_id)
     }
 
-    fn expression_opt_semi(&mut self) -> Result<Option<Expr>> {
-        Ok(if self.matches(TokenData::Semi)? {
-            None
-        } else {
-            let expr = self.allow_in(true, |this| this.expression())?;
-            self.expect(TokenData::Semi)?;
-            Some(expr)
-        })
+    // "async" . [no line terminator] "function"
+    fn async_function_follows(&mut self) -> Result<bool> {
+        let next = self.peek_op()?;
+        Ok(next.value == TokenData::Reserved(Reserved::Function) && !next.newline)
     }
 
-    fn comma_separated<T, F>(&mut self, f: F) -> Result<Vec<T>>
-        where F: Fn(&mut Self) -> Result<T>
+    // AsyncFunctionDeclaration ::=
+    //   "async" [no line terminator] "function" "*"? BindingIdentifier "(" FormalParameters ")" "{" AsyncFunctionBody "}"
+    // AsyncFunctionExpression ::=
+    //   "async" [no line terminator] "function" "*"? BindingIdentifier? "(" FormalParameters ")" "{" AsyncFunctionBody "}"
+    fn async_function<Id, F>(&mut self, async_token: Token, get_id: F) -> Result<Fun<Id>>
+        where F: Fn(&mut Self, bool) -> Result<Id>
     {
-        let first = f(self)?;
-        self.more_comma(first, f)
+        self.reread(TokenData::Reserved(Reserved::Function));
+        let generator = self.matches(TokenData::Star)?;
+        self.more_function(Some(async_token.location), generator, true, get_id)
+    }
+
+    fn more_function<Id, F>(&mut self, start_location: Option<Span>, generator: bool, asynchronous: bool, get_id: F) -> Result<Fun<Id>>
+        where F: Fn(&mut Self, bool) -> Result<Id>
+    {
+        let id = get_id(self, generator)?;
+        let type_params = self.type_parameters_opt()?;
+        let (this_type, params, ret, body) = self.in_function(generator, asynchronous, |this| {
+            let (this_type, params) = this.formal_parameters()?;
+            let ret = this.type_annotation_opt()?;
+            let body = this.function_body(&params.list, &params.rest)?;
+            Ok((this_type, params, ret, body))
+        })?;
+        Ok(Fun {
+            location: span(&start_location, &body),
+            id: id,
+            params: params,
+            body: body,
+            generator: generator,
+            asynchronous: asynchronous,
+            type_params: type_params,
+            this_type: this_type,
+            ret: ret
+        })
+    }
+
+    fn function_body(&mut self, params: &[Patt<Id>], rest: &Option<RestPatt<Id>>) -> Result<Script> {
+        let inner = self.context.new_function();
+        let outer = replace(&mut self.context, inner);
+        self.expect(TokenData::LBrace)?;
+        // ES6: if the body has "use strict" check for simple parameters
+        let body = self.script_body()?;
+        self.strict_check(|_| {
+            if body.dirs.iter().any(|dir| dir.pragma() == "use strict") {
+                for param in params {
+                    if !param.is_simple() {
+                        return Some(Check::Strict(Error::NonSimpleParamWithUseStrict(param.clone())));
+                    }
+                }
+                // A rest parameter alone makes the list non-simple.
+                if let Some(ref rest) = *rest {
+                    return Some(Check::Strict(Error::NonSimpleParamWithUseStrict(rest.patt.clone())));
+                }
+            }
+            None
+        })?;
+        self.expect(TokenData::RBrace)?;
+        self.context = outer;
+        Ok(body)
+    }
+
+    fn stmt_list_item(&mut self, allow_decl: bool) -> Result<StmtListItem> {
+        let generator = self.context.generator;
+        let asynchronous = self.await_keyword();
+        let types = self.options.types;
+        (match self.peek()?.value {
+            // Imports and exports are only legal at the top level of a
+            // module, which is handled by more_module_items.
+            TokenData::Reserved(Reserved::Import) => {
+                if self.import_expression_follows()? {
+                    self.expression_statement()
+                } else if self.goal != Goal::Script {
+                    return self.unexpected();
+                } else {
+                    return Err(Error::ImportInScript(self.import_declaration()?));
+                }
+            }
+            TokenData::Reserved(Reserved::Export) => {
+                if self.goal != Goal::Script {
+                    return self.unexpected();
+                }
+                return Err(Error::ExportInScript(self.export_declaration(Vec::new())?));
+            }
+            TokenData::Reserved(Reserved::Function) => {
+                if !allow_decl {
+                    return self.unexpected();
+                }
+                return self.function_declaration().map(StmtListItem::Decl);
+            }
+            TokenData::Reserved(Reserved::Class) => {
+                if !allow_decl {
+                    return self.unexpected();
+                }
+                return self.class_declaration(Vec::new()).map(StmtListItem::Decl);
+            }
+            TokenData::At => {
+                if !allow_decl {
+                    return self.unexpected();
+                }
+                let decorators = self.decorators()?;
+                return self.decorated_stmt_list_item(decorators);
+            }
+            TokenData::LBrace                       => self.block().map(Stmt::Block),
+            TokenData::Reserved(Reserved::Var)      => self.var_statement(),
+            TokenData::Reserved(Reserved::Const)    => {
+                if !allow_decl {
+                    return self.unexpected();
+                }
+                return self.const_declaration().map(StmtListItem::Decl);
+            }
+            TokenData::Semi                         => self.empty_statement(),
+            TokenData::Reserved(Reserved::If)       => self.if_statement(),
+            TokenData::Reserved(Reserved::Continue) => self.continue_statement(),
+            TokenData::Reserved(Reserved::Break)    => self.break_statement(),
+            TokenData::Reserved(Reserved::Return)   => self.return_statement(),
+            TokenData::Reserved(Reserved::With)     => self.with_statement(),
+            TokenData::Reserved(Reserved::Switch)   => self.switch_statement(),
+            TokenData::Reserved(Reserved::Throw)    => self.throw_statement(),
+            TokenData::Reserved(Reserved::Try)      => self.try_statement(),
+            TokenData::Reserved(Reserved::While)    => self.while_statement(),
+            TokenData::Reserved(Reserved::Do)       => self.do_statement(),
+            TokenData::Reserved(Reserved::For)      => self.for_statement(),
+            TokenData::Reserved(Reserved::Debugger) => self.debugger_statement(),
+            TokenData::Identifier(Name::Atom(Atom::Yield)) if generator => self.expression_statement(),
+            TokenData::Identifier(Name::Atom(Atom::Await)) if asynchronous => self.expression_statement(),
+            TokenData::Identifier(_)                => {
+                let token = self.lexer.reread_token();
+                if token.value == TokenData::Identifier(Name::Atom(Atom::Async)) && self.async_function_follows()? {
+                    if !allow_decl {
+                        return self.unexpected();
+                    }
+                    return self.async_function(token, |this, _| this.id(true)).map(|fun| StmtListItem::Decl(Decl::Fun(fun)));
+                }
+                if types && self.type_declaration_follows(&token)? {
+                    if !allow_decl {
+                        return self.unexpected();
+                    }
+                    return self.type_declaration(token).map(StmtListItem::Decl);
+                }
+                match self.peek_op()?.value {
+                    TokenData::Colon => {
+                        let id = self.new_id_from_token(false, token)?;
+                        self.labelled_statement(id, allow_decl)
+                    },
+                    TokenData::Identifier(_) | TokenData::LBrace | TokenData::LBrack if token.value == TokenData::Identifier(Name::Atom(Atom::Let)) => {
+                        if !allow_decl {
+                            return self.unexpected();
+                        }
+                        return self.let_declaration(token.location.start).map(StmtListItem::Decl);
+                    },
+                    _ => {
+                        self.lexer.unread_token(token);
+                        self.expression_statement()
+                    }
+                }
+            }
+            _  => self.expression_statement()
+        }).map(StmtListItem::Stmt)
+    }
+
+    fn statement(&mut self) -> Result<Stmt> {
+        self.stmt_list_item(false).map(|item| match item {
+            StmtListItem::Stmt(stmt) => stmt,
+            _ => unreachable!()
+        })
+    }
+
+    fn labelled_statement(&mut self, id: Id, allow_decl: bool) -> Result<Stmt> {
+        self.reread(TokenData::Colon);
+
+        let mut labels = vec![id]; // vector of consecutive labels
+
+        while let TokenData::Identifier(_) = self.peek()?.value {
+            let token = self.lexer.reread_token();
+            if !self.matches_op(TokenData::Colon)? {
+                self.lexer.unread_token(token);
+                break;
+            }
+            labels.push(self.new_id_from_token(false, token)?);
+        }
+
+        // Annex B.3.1: a labelled function declaration, but not as the body
+        // of another statement.
+        if self.peek()?.value == TokenData::Reserved(Reserved::Function) {
+            if !allow_decl {
+                return self.unexpected();
+            }
+            return self.with_labels(labels, LabelType::Statement, |this| {
+                this.legacy_function_declaration(Error::LabelledFunction)
+            });
+        }
+
+        let label_type = self.peek()?.label_type();
+        self.with_labels(labels, label_type, |this| this.statement())
+    }
+
+    // An Annex B function declaration in statement position. Generators
+    // and async functions never qualify.
+    fn legacy_function_declaration<F>(&mut self, error: F) -> Result<Stmt>
+        where F: Fn(Span) -> Error
+    {
+        let location = self.peek()?.location;
+        self.annex_b_check(error(location))?;
+        let fun = self.function(|this, _| this.id(true))?;
+        if fun.generator {
+            return Err(error(location));
+        }
+        Ok(Stmt::Fun(fun))
+    }
+
+    fn expression_statement(&mut self) -> Result<Stmt> {
+        let span = self.start();
+        let expr = self.allow_in(true, |this| this.expression())?;
+        Ok(span.end_with_auto_semi(self, Newline::Required, |semi| Stmt::Expr(None, expr, semi))?)
+    }
+
+    fn block(&mut self) -> Result<Block> {
+        self.span(&mut |this| {
+            this.expect(TokenData::LBrace)?;
+            let items = this.statement_list()?;
+            this.expect(TokenData::RBrace)?;
+            Ok(Block {
+                location: None,
+                items: items
+            })
+        })
+    }
+
+    fn var_statement(&mut self) -> Result<Stmt> {
+        let span = self.start();
+        self.reread(TokenData::Reserved(Reserved::Var));
+        let dtors = self.comma_separated(Self::declarator)?;
+        span.end_with_auto_semi(self, Newline::Required, |semi| Stmt::Var(None, dtors, semi))
+    }
+
+    fn let_declaration(&mut self, start: Posn) -> Result<Decl> {
+        let span = SpanTracker::new(start);
+        let dtors = self.comma_separated(Self::declarator)?;
+        unique_names(dtors.iter().flat_map(|dtor| dtor.bound_names()), Error::DuplicateBinding)?;
+        span.end_with_auto_semi(self, Newline::Required, |semi| Decl::Let(None, dtors, semi))
+    }
+
+    fn const_declaration(&mut self) -> Result<Decl> {
+        let span = self.start();
+        self.reread(TokenData::Reserved(Reserved::Const));
+        let dtors = self.comma_separated(Self::const_declarator)?;
+        unique_names(dtors.iter().flat_map(|dtor| dtor.patt.bound_names()), Error::DuplicateBinding)?;
+        span.end_with_auto_semi(self, Newline::Required, |semi| Decl::Const(None, dtors, semi))
+    }
+
+    fn new_id_from_token(&mut self, binding: bool, token: Token) -> Result<Id> {
+        match token.value {
+            TokenData::Identifier(name) => self.new_id(binding, name, token.location),
+            _ => unreachable!()
+        }
+    }
+
+    fn new_id(&mut self, binding: bool, name: Name, location: Span) -> Result<Id> {
+        match name {
+            Name::Atom(Atom::Yield) if self.context.generator => {
+                return Err(Error::ContextualKeyword(location, Atom::Yield));
+            }
+            Name::Atom(Atom::Await) if self.context.asynchronous || self.context.static_block => {
+                return Err(Error::ContextualKeyword(location, Atom::Await));
+            }
+            Name::Atom(Atom::Arguments) if !binding && self.context.class_init => {
+                return Err(Error::ArgumentsInClassInit(location));
+            }
+            _ => { }
+        }
+        self.strict_check(|_| {
+            if binding && name.is_illegal_strict_binding() {
+                return Some(Check::Strict(Error::IllegalStrictBinding(location, name.atom().unwrap())));
+            }
+            let is_reserved = name.is_strict_reserved();
+            if is_reserved != TriState::No {
+                let error = Error::ContextualKeyword(location, name.atom().unwrap());
+                return Some(if is_reserved == TriState::Yes {
+                    Check::Strict(error)
+                } else {
+                    // TriState::Unknown means word is reserved only
+                    // for module goal
+                    Check::Module(error)
+                });
+            }
+            None
+        })?;
+        // Since await is reserved in modules, using it as an identifier
+        // makes this a script. The check above is kept for Program::module.
+        // An await that was only read as an identifier because the goal was
+        // unknown leaves the decision to the rest of the program.
+        if let Name::Atom(Atom::Await) = name {
+            if self.goal == Goal::Unknown && self.context.cover_await_goal.is_none() {
+                self.goal = Goal::Script;
+            }
+            self.context.cover_await_id = self.context.cover_await_id.or(Some(location));
+        }
+        Ok(Id::new(name, Some(location)))
+    }
+
+    fn id(&mut self, binding: bool) -> Result<Id> {
+        let Token { location, newline, value: data } = self.read()?;
+        match data {
+            TokenData::Identifier(name) => self.new_id(binding, name, location),
+            _ => Err(Error::UnexpectedToken(Token {
+                location: location,
+                newline: newline,
+                value: data
+            }))
+        }
+    }
+
+    fn id_opt(&mut self, binding: bool) -> Result<Option<Id>> {
+        let next = self.read()?;
+        match next.value {
+            TokenData::Identifier(name) => self.new_id(binding, name, next.location).map(Some),
+            _ => {
+                self.lexer.unread_token(next);
+                Ok(None)
+            }
+        }
+    }
+
+    fn declarator(&mut self) -> Result<Dtor> {
+        self.span(&mut |this| {
+            let lhs = this.annotated_pattern()?;
+            let rhs = if this.matches(TokenData::Assign)? {
+                Some(this.assignment_expression()?)
+            } else {
+                None
+            };
+            // Only a compound pattern requires an initializer.
+            match Dtor::from_init_opt(lhs, rhs) {
+                Ok(dtor) => Ok(dtor),
+                Err(_) => this.unexpected()
+            }
+        })
+    }
+
+    fn const_declarator(&mut self) -> Result<ConstDtor> {
+        let lhs = self.annotated_pattern()?;
+        self.expect(TokenData::Assign)?;
+        let rhs = self.assignment_expression()?;
+        match ConstDtor::from_init(lhs, rhs) {
+            Ok(dtor) => Ok(dtor),
+            Err(_) => self.unexpected()
+        }
+    }
+
+    fn empty_statement(&mut self) -> Result<Stmt> {
+        self.span(&mut |this| {
+            this.expect(TokenData::Semi)?;
+            Ok(Stmt::Empty(None))
+        })
+    }
+
+    fn if_statement(&mut self) -> Result<Stmt> {
+        self.span(&mut |this| {
+            this.expect(TokenData::Reserved(Reserved::If))?;
+            let test = this.paren_expression()?;
+            let cons = Box::new(this.if_clause()?);
+            let alt = if this.peek()?.value == TokenData::Reserved(Reserved::Else) {
+                this.reread(TokenData::Reserved(Reserved::Else));
+                Some(Box::new(this.if_clause()?))
+            } else {
+                None
+            };
+            Ok(Stmt::If(None, test, cons, alt))
+        })
+    }
+
+    // Annex B.3.4: FunctionDeclarations in IfStatement Statement Clauses
+    fn if_clause(&mut self) -> Result<Stmt> {
+        if self.peek()?.value == TokenData::Reserved(Reserved::Function) {
+            return self.legacy_function_declaration(Error::IfFunction);
+        }
+        self.statement()
+    }
+
+    fn iteration_body(&mut self) -> Result<Stmt> {
+        let iteration = replace(&mut self.context.iteration, true);
+        let result = self.statement();
+        replace(&mut self.context.iteration, iteration);
+        result
+    }
+
+    fn do_statement(&mut self) -> Result<Stmt> {
+        let span = self.start();
+        self.reread(TokenData::Reserved(Reserved::Do));
+        let body = Box::new(self.iteration_body()?);
+        self.expect(TokenData::Reserved(Reserved::While))?;
+        let test = self.paren_expression()?;
+        Ok(span.end_with_auto_semi(self, Newline::Optional, |semi| {
+            Stmt::DoWhile(None, body, test, semi)
+        })?)
+    }
+
+    fn while_statement(&mut self) -> Result<Stmt> {
+        self.span(&mut |this| {
+            this.reread(TokenData::Reserved(Reserved::While));
+            let test = this.paren_expression()?;
+            let body = Box::new(this.iteration_body()?);
+            Ok(Stmt::While(None, test, body))
+        })
+    }
+
+    fn for_statement(&mut self) -> Result<Stmt> {
+        self.span(&mut |this| {
+            this.reread(TokenData::Reserved(Reserved::For));
+            let for_await = this.for_await_opt()?;
+            this.expect(TokenData::LParen)?;
+            match this.peek()?.value {
+                TokenData::Reserved(Reserved::Var)           => this.for_var(for_await),
+                TokenData::Identifier(Name::Atom(Atom::Let)) => this.for_let(for_await),
+                TokenData::Reserved(Reserved::Const)         => this.for_const(for_await),
+                TokenData::Semi                              => {
+                    this.reread(TokenData::Semi);
+                    this.more_for(None, for_await)
+                }
+                _                                            => this.for_expr(for_await)
+            }
+        })
+    }
+
+    // 'for' . 'await'
+    //
+    // Only allowed in async functions and at the top level of a module.
+    fn for_await_opt(&mut self) -> Result<Option<Span>> {
+        let token = self.read()?;
+        if token.value != TokenData::Identifier(Name::Atom(Atom::Await)) {
+            self.lexer.unread_token(token);
+            return Ok(None);
+        }
+        if !self.context.asynchronous {
+            if !self.top_level() {
+                return Err(Error::UnexpectedToken(token));
+            }
+            self.require_module(Error::ForAwaitInScript(token.location))?;
+        }
+        Ok(Some(token.location))
+    }
+
+    // Whether await is already known to be a keyword: in async functions,
+    // and outside of any function in a module.
+    fn await_keyword(&self) -> bool {
+        self.context.asynchronous || (self.context.top_level_await && self.goal == Goal::Module)
+    }
+
+    // 'await' .
+    //
+    // Outside of any function, await is a keyword if this is a module. When
+    // that isn't known yet, an await followed on the same line by the start
+    // of an operand can only be an AwaitExpression, so it makes this a
+    // module, and one followed by a newline, an arrow, "of" or the end of an
+    // expression can only be an identifier. An await followed by "(", "[",
+    // "/", "+", "-" or a template reads either way ("await(x)" is a call in a
+    // script), so it's read as an identifier and covered until the rest of
+    // the program decides.
+    fn top_level_await(&mut self, location: Span) -> Result<bool> {
+        if !self.context.top_level_await {
+            return Ok(false);
+        }
+        match self.goal {
+            Goal::Script => Ok(false),
+            Goal::Module => Ok(true),
+            Goal::Unknown => {
+                let (operand, ambiguous) = {
+                    let next = self.peek_op()?;
+                    let operand = !next.newline
+                        && !next.expression_continuation()
+                        && !next.follow_assignment_expression()
+                        && next.value != TokenData::Arrow
+                        && next.value != TokenData::Identifier(Name::Atom(Atom::Of));
+                    let ambiguous = !next.newline && match next.value {
+                        TokenData::LParen
+                      | TokenData::LBrack
+                      | TokenData::Slash
+                      | TokenData::SlashAssign
+                      | TokenData::Plus
+                      | TokenData::Minus
+                      | TokenData::NoSubstTemplate(_)
+                      | TokenData::TemplateHead(_) => true,
+                        _ => false
+                    };
+                    (operand, ambiguous)
+                };
+                if ambiguous {
+                    self.context.cover_await_goal = self.context.cover_await_goal.or(Some(location));
+                    return Ok(false);
+                }
+                if operand {
+                    self.force_deferred_module_validation()?;
+                    self.set_module()?;
+                }
+                Ok(operand)
+            }
+        }
+    }
+
+    // Not inside any function or class body.
+    fn top_level(&self) -> bool {
+        !self.context.function && self.private_scopes.is_empty()
+    }
+
+    // 'for' '(' 'var' .
+    fn for_var(&mut self, for_await: Option<Span>) -> Result<Stmt> {
+        let var_token = self.reread(TokenData::Reserved(Reserved::Var));
+        let var_location = Some(var_token.location);
+        let lhs = self.annotated_pattern()?;
+        match self.peek()?.value {
+            // 'for' '(' 'var' id   '=' .
+            // 'for' '(' 'var' patt '=' . ==> C-style
+            TokenData::Assign => {
+                self.reread(TokenData::Assign);
+                match lhs {
+                    Patt::Simple(id) => {
+                        let rhs = self.allow_in(false, |this| this.assignment_expression())?;
+                        match self.peek()?.value {
+                            // 'for' '(' 'var' id '=' expr ','  . ==> C-style
+                            // 'for' '(' 'var' id '=' expr ';'  . ==> C-style
+                            TokenData::Comma
+                          | TokenData::Semi => {
+                                self.more_for_head(&var_location, Dtor::from_simple_init(id, rhs), ForHead::Var, for_await)
+                            }
+                            // 'for' '(' 'var' id '=' expr 'in' . ==> legacy enumeration
+                            TokenData::Reserved(Reserved::In) => {
+                                self.reread(TokenData::Reserved(Reserved::In));
+                                let location = span(&var_location, &rhs);
+                                self.annex_b_check(Error::ForInVarInit(location))?;
+                                let head = Box::new(ForInHead::VarInit(location, id, rhs));
+                                self.more_for_in(head, for_await)
+                            }
+                            _ => self.unexpected()
+                        }
+                    }
+                    // 'for' '(' 'var' patt '=' . ==> C-style
+                    lhs => {
+                        let rhs = self.allow_in(false, |this| this.assignment_expression())?;
+                        let dtor = match Dtor::from_init(lhs, rhs) {
+                            Ok(dtor) => dtor,
+                            Err(_) => { return self.unexpected(); }
+                        };
+                        self.more_for_head(&var_location, dtor, ForHead::Var, for_await)
+                    }
+                }
+            }
+            TokenData::Comma
+          | TokenData::Semi => {
+                // 'for' '(' 'var' id   ',' . ==> C-style
+                // 'for' '(' 'var' id   ';' . ==> C-style
+                // 'for' '(' 'var' patt ',' . ==> syntax error
+                // 'for' '(' 'var' patt ';' . ==> syntax error
+                let dtor = match Dtor::from_init_opt(lhs, None) {
+                    Ok(dtor) => dtor,
+                    Err(_) => { return self.unexpected(); }
+                };
+                self.more_for_head(&var_location, dtor, ForHead::Var, for_await)
+            }
+            // 'for' '(' 'var' id   'in' . ==> enumeration
+            // 'for' '(' 'var' patt 'in' . ==> enumeration
+            TokenData::Reserved(Reserved::In) => {
+                self.untyped_for_head(&lhs)?;
+                self.reread(TokenData::Reserved(Reserved::In));
+                let head = Box::new(ForInHead::Var(span(&var_location, &lhs), lhs));
+                self.more_for_in(head, for_await)
+            }
+            // 'for' '(' 'var' id   'of' . ==> enumeration
+            // 'for' '(' 'var' patt 'of' . ==> enumeration
+            TokenData::Identifier(Name::Atom(Atom::Of)) => {
+                self.untyped_for_head(&lhs)?;
+                self.reread(TokenData::Identifier(Name::Atom(Atom::Of)));
+                let head = Box::new(ForOfHead::Var(span(&var_location, &lhs), lhs));
+                self.more_for_of(head, for_await)
+            }
+            _ => self.unexpected()
+        }
+    }
+
+    // 'for' '(' 'let' .
+    fn for_let(&mut self, for_await: Option<Span>) -> Result<Stmt> {
+        let let_token = self.reread(TokenData::Identifier(Name::Atom(Atom::Let)));
+        if !self.peek()?.first_binding() {
+            self.lexer.unread_token(let_token);
+            return self.for_expr(for_await);
+        }
+        let let_location = Some(let_token.location);
+        // 'for' '(' 'let' . !{id, patt} ==> error
+        let lhs = self.annotated_pattern()?;
+        match self.peek()?.value {
+            // 'for' '(' 'let' id   '=' . ==> C-style
+            // 'for' '(' 'let' patt '=' . ==> C-style
+            TokenData::Assign => {
+                self.reread(TokenData::Assign);
+                let rhs = self.allow_in(false, |this| this.assignment_expression())?;
+                let dtor = match Dtor::from_init(lhs, rhs) {
+                    Ok(dtor) => dtor,
+                    Err(_) => { return self.unexpected(); }
+                };
+                self.more_for_head(&let_location, dtor, ForHead::Let, for_await)
+            }
+            TokenData::Comma
+          | TokenData::Semi => {
+                // 'for' '(' 'let' id   ',' . ==> C-style
+                // 'for' '(' 'let' id   ';' . ==> C-style
+                // 'for' '(' 'let' patt ',' . ==> error
+                // 'for' '(' 'let' patt ';' . ==> error
+                let dtor = match Dtor::from_init_opt(lhs, None) {
+                    Ok(dtor) => dtor,
+                    Err(_) => { return self.unexpected(); }
+                };
+                self.more_for_head(&let_location, dtor, ForHead::Let, for_await)
+            }
+            // 'for' '(' 'let' id   'in' . ==> enumeration
+            // 'for' '(' 'let' patt 'in' . ==> enumeration
+            TokenData::Reserved(Reserved::In) => {
+                self.untyped_for_head(&lhs)?;
+                unique_names(lhs.bound_names(), Error::DuplicateBinding)?;
+                self.reread(TokenData::Reserved(Reserved::In));
+                let head = Box::new(ForInHead::Let(span(&let_location, &lhs), lhs));
+                self.more_for_in(head, for_await)
+            }
+            // 'for' '(' 'let' id   'of' . ==> enumeration
+            // 'for' '(' 'let' patt 'of' . ==> enumeration
+            TokenData::Identifier(Name::Atom(Atom::Of)) => {
+                self.untyped_for_head(&lhs)?;
+                unique_names(lhs.bound_names(), Error::DuplicateBinding)?;
+                self.reread(TokenData::Identifier(Name::Atom(Atom::Of)));
+                let head = Box::new(ForOfHead::Let(span(&let_location, &lhs), lhs));
+                self.more_for_of(head, for_await)
+            }
+            _ => self.unexpected()
+        }
+    }
+
+    // 'for' '(' 'const' .
+    fn for_const(&mut self, for_await: Option<Span>) -> Result<Stmt> {
+        let const_token = self.reread(TokenData::Reserved(Reserved::Const));
+        if !self.peek()?.first_binding() {
+            self.lexer.unread_token(const_token);
+            return self.for_expr(for_await);
+        }
+        let const_location = Some(const_token.location);
+        // 'for' '(' 'const' . !{id, patt} ==> error
+        let lhs = self.annotated_pattern()?;
+        match self.peek()?.value {
+            // 'for' '(' 'const' {id, patt}   '=' . ==> C-style
+            TokenData::Assign => {
+                self.reread(TokenData::Assign);
+                let dtors = self.allow_in(false, |this| {
+                    let rhs = this.assignment_expression()?;
+                    let dtor = match ConstDtor::from_init(lhs, rhs) {
+                        Ok(dtor) => dtor,
+                        Err(_) => { return this.unexpected(); }
+                    };
+                    this.more_comma(dtor, Self::const_declarator)
+                })?;
+                unique_names(dtors.iter().flat_map(|dtor| dtor.patt.bound_names()), Error::DuplicateBinding)?;
+                let semi_location = Some(self.expect(TokenData::Semi)?.location);
+                let head = Box::new(ForHead::Const(span(&const_location, &semi_location), dtors));
+                self.more_for(Some(head), for_await)
+            }
+            // 'for' '(' 'const' {id, patt}   'in' . ==> enumeration
+            TokenData::Reserved(Reserved::In) => {
+                self.untyped_for_head(&lhs)?;
+                unique_names(lhs.bound_names(), Error::DuplicateBinding)?;
+                self.reread(TokenData::Reserved(Reserved::In));
+                let head = Box::new(ForInHead::Const(span(&const_location, &lhs), lhs));
+                self.more_for_in(head, for_await)
+            }
+            // 'for' '(' 'const' {id, patt}   'of' . ==> enumeration
+            TokenData::Identifier(Name::Atom(Atom::Of)) => {
+                self.untyped_for_head(&lhs)?;
+                unique_names(lhs.bound_names(), Error::DuplicateBinding)?;
+                self.reread(TokenData::Identifier(Name::Atom(Atom::Of)));
+                let head = Box::new(ForOfHead::Const(span(&const_location, &lhs), lhs));
+                self.more_for_of(head, for_await)
+            }
+            _ => self.unexpected()
+        }
+    }
+
+    fn for_expr(&mut self, for_await: Option<Span>) -> Result<Stmt> {
+        let lhs = match self.for_await_async_opt(for_await)? {
+            Some(lhs) => lhs,
+            None => self.allow_in(false, |this| {
+                let first = this.cover_assignment_expression()?;
+                this.more_expressions(first)
+            })?
+        };
+        let cover_init = self.context.cover_init.take();
+        let cover_patt = self.context.cover_patt.take();
+        self.context.cover_paren = None;
+        match self.peek()?.value {
+            TokenData::Semi => {
+                if let Some(error) = cover_init {
+                    return Err(error);
+                }
+                let semi_location = Some(self.reread(TokenData::Semi).location);
+                let head = Some(Box::new(ForHead::Expr(span(&lhs, &semi_location), lhs)));
+                self.more_for(head, for_await)
+            }
+            TokenData::Reserved(Reserved::In) => {
+                self.reread(TokenData::Reserved(Reserved::In));
+                let lhs = self.cover_assign_patt(lhs, cover_patt)?;
+                let head = Box::new(ForInHead::Patt(lhs));
+                self.more_for_in(head, for_await)
+            }
+            TokenData::Identifier(Name::Atom(Atom::Of)) => {
+                self.reread(TokenData::Identifier(Name::Atom(Atom::Of)));
+                let lhs = self.cover_assign_patt(lhs, cover_patt)?;
+                let head = Box::new(ForOfHead::Patt(lhs));
+                self.more_for_of(head, for_await)
+            }
+            _ => self.unexpected()
+        }
+    }
+
+    // 'for' 'await' '(' . 'async' 'of'
+    //
+    // A plain for-of loop can't start with "async of", which would look like
+    // an async arrow function, but a for-await loop can.
+    fn for_await_async_opt(&mut self, for_await: Option<Span>) -> Result<Option<Expr>> {
+        if for_await.is_none() || self.peek()?.value != TokenData::Identifier(Name::Atom(Atom::Async)) {
+            return Ok(None);
+        }
+        let token = self.reread(TokenData::Identifier(Name::Atom(Atom::Async)));
+        if self.peek_op()?.value != TokenData::Identifier(Name::Atom(Atom::Of)) {
+            self.lexer.unread_token(token);
+            return Ok(None);
+        }
+        self.new_id_from_token(false, token).map(|id| Some(Expr::Id(id)))
+    }
+
+    // 'for' '(' dtor .
+    fn more_for_head<F>(&mut self, start: &Option<Span>, dtor: Dtor, op: F, for_await: Option<Span>) -> Result<Stmt>
+      where F: FnOnce(Option<Span>, Vec<Dtor>) -> ForHead
+    {
+        let dtors = self.allow_in(false, |this| {
+            this.more_comma(dtor, Self::declarator)
+        })?;
+        let semi_location = Some(self.expect(TokenData::Semi)?.location);
+        let head = Box::new(op(span(start, &semi_location), dtors));
+        if let ForHead::Let(_, ref dtors) = *head {
+            unique_names(dtors.iter().flat_map(|dtor| dtor.bound_names()), Error::DuplicateBinding)?;
+        }
+        self.more_for(Some(head), for_await)
+    }
+
+    // 'for' '(' head ';' .
+    fn more_for(&mut self, head: Option<Box<ForHead>>, for_await: Option<Span>) -> Result<Stmt> {
+        if let Some(location) = for_await {
+            return Err(Error::ForAwaitWithoutOf(location));
+        }
+        let test = self.expression_opt_semi()?;
+        let update = if self.matches(TokenData::RParen)? {
+            None
+        } else {
+            let node = Some(self.allow_in(true, |this| this.expression())?);
+            self.expect(TokenData::RParen)?;
+            node
+        };
+        let body = Box::new(self.iteration_body()?);
+        Ok(Stmt::For(None, head, test, update, body))
+    }
+
+    // 'for' '(' head 'in' .
+    fn more_for_in(&mut self, head: Box<ForInHead>, for_await: Option<Span>) -> Result<Stmt> {
+        if let Some(location) = for_await {
+            return Err(Error::ForAwaitWithoutOf(location));
+        }
+        let obj = self.allow_in(true, |this| this.assignment_expression())?;
+        self.expect(TokenData::RParen)?;
+        let body = Box::new(self.iteration_body()?);
+        Ok(Stmt::ForIn(None, head, obj, body))
+    }
+
+    // 'for' 'await'? '(' head 'of' .
+    fn more_for_of(&mut self, head: Box<ForOfHead>, for_await: Option<Span>) -> Result<Stmt> {
+        let obj = self.allow_in(true, |this| this.assignment_expression())?;
+        self.expect(TokenData::RParen)?;
+        let body = Box::new(self.iteration_body()?);
+        Ok(match for_await {
+            Some(_) => Stmt::ForAwait(None, head, obj, body),
+            None => Stmt::ForOf(None, head, obj, body)
+        })
+    }
+
+    fn expression_opt_semi(&mut self) -> Result<Option<Expr>> {
+        Ok(if self.matches(TokenData::Semi)? {
+            None
+        } else {
+            let expr = self.allow_in(true, |this| this.expression())?;
+            self.expect(TokenData::Semi)?;
+            Some(expr)
+        })
+    }
+
+    fn comma_separated<T, F>(&mut self, f: F) -> Result<Vec<T>>
+        where F: Fn(&mut Self) -> Result<T>
+    {
+        let first = f(self)?;
+        self.more_comma(first, f)
+    }
+
+    fn more_comma<T, F>(&mut self, first: T, f: F) -> Result<Vec<T>>
+        where F: Fn(&mut Self) -> Result<T>
+    {
+        let mut items = vec![first];
+        while self.matches(TokenData::Comma)? {
+            items.push(f(self)?);
+        }
+        Ok(items)
+    }
+
+    fn switch_statement(&mut self) -> Result<Stmt> {
+        self.span(&mut |this| {
+            this.reread(TokenData::Reserved(Reserved::Switch));
+            let disc = this.paren_expression()?;
+            let outer_switch = replace(&mut this.context.switch, true);
+            let cases = this.switch_cases();
+            replace(&mut this.context.switch, outer_switch);
+            Ok(Stmt::Switch(None, disc, cases?))
+        })
+    }
+
+    fn switch_cases(&mut self) -> Result<Vec<Case>> {
+        self.expect(TokenData::LBrace)?;
+        let mut cases = Vec::new();
+        let mut found_default = false;
+        loop {
+            match self.peek()?.value {
+                TokenData::Reserved(Reserved::Case) => { cases.push(self.case()?); }
+                TokenData::Reserved(Reserved::Default) => {
+                    if found_default {
+                        let token = self.reread(TokenData::Reserved(Reserved::Default));
+                        return Err(Error::DuplicateDefault(token));
+                    }
+                    found_default = true;
+                    cases.push(self.default()?);
+                }
+                _ => { break; }
+            }
+        }
+        self.expect(TokenData::RBrace)?;
+        Ok(cases)
+    }
+
+    fn case(&mut self) -> Result<Case> {
+        self.span(&mut |this| {
+            this.reread(TokenData::Reserved(Reserved::Case));
+            let test = this.allow_in(true, |this| this.expression())?;
+            this.expect(TokenData::Colon)?;
+            let body = this.statement_list()?;
+            Ok(Case { location: None, test: Some(test), body: body })
+        })
+    }
+
+    fn default(&mut self) -> Result<Case> {
+        self.span(&mut |this| {
+            this.reread(TokenData::Reserved(Reserved::Default));
+            this.expect(TokenData::Colon)?;
+            let body = this.statement_list()?;
+            Ok(Case { location: None, test: None, body: body })
+        })
+    }
+
+    fn break_statement(&mut self) -> Result<Stmt> {
+        let span = self.start();
+        let break_token = self.reread(TokenData::Reserved(Reserved::Break));
+        let arg = if self.has_arg_same_line()? {
+            let id = self.id(false)?;
+            if !self.context.labels.contains_key(&Rc::new(id.name.clone())) {
+                return Err(Error::InvalidLabel(id));
+            }
+            Some(id)
+        } else {
+            if !self.context.iteration && !self.context.switch {
+                return Err(Error::IllegalBreak(break_token));
+            }
+            None
+        };
+        span.end_with_auto_semi(self, Newline::Required, |semi| {
+            Stmt::Break(None, arg, semi)
+        })
+    }
+
+    fn continue_statement(&mut self) -> Result<Stmt> {
+        let span = self.start();
+        let continue_token = self.reread(TokenData::Reserved(Reserved::Continue));
+        let arg = if self.has_arg_same_line()? {
+            let id = self.id(false)?;
+            match self.context.labels.get(&Rc::new(id.name.clone())) {
+                None                        => { return Err(Error::InvalidLabel(id)); }
+                Some(&LabelType::Statement) => { return Err(Error::InvalidLabelType(id)); }
+                _                           => { }
+            }
+            Some(id)
+        } else {
+            if !self.context.iteration {
+                return Err(Error::IllegalContinue(continue_token));
+            }
+            None
+        };
+        span.end_with_auto_semi(self, Newline::Required, |semi| {
+            Stmt::Cont(None, arg, semi)
+        })
+    }
+
+    fn return_statement(&mut self) -> Result<Stmt> {
+        let span = self.start();
+        self.reread(TokenData::Reserved(Reserved::Return));
+        let arg = if self.has_arg_same_line()? {
+            Some(self.allow_in(true, |this| this.expression())?)
+        } else {
+            None
+        };
+        let result = span.end_with_auto_semi(self, Newline::Required, |semi| {
+            Stmt::Return(None, arg, semi)
+        })?;
+        if !self.context.function {
+            Err(Error::TopLevelReturn(result.tracking_ref().unwrap()))
+        } else {
+            Ok(result)
+        }
+    }
+
+    fn with_statement(&mut self) -> Result<Stmt> {
+        self.span(&mut |this| {
+            let token = this.reread(TokenData::Reserved(Reserved::With));
+            this.strict_check(|_| {
+                Some(Check::Strict(Error::StrictWith(token)))
+            })?;
+            let obj = this.paren_expression()?;
+            let body = Box::new(this.statement()?);
+            Ok(Stmt::With(None, obj, body))
+        })
+    }
+
+    fn throw_statement(&mut self) -> Result<Stmt> {
+        let span = self.start();
+        let token = self.reread(TokenData::Reserved(Reserved::Throw));
+        if !self.has_arg_same_line()? {
+            return Err(Error::ThrowArgument(token));
+        }
+        let arg = self.allow_in(true, |this| this.expression())?;
+        span.end_with_auto_semi(self, Newline::Required, |semi| {
+            Stmt::Throw(None, arg, semi)
+        })
+    }
+
+    fn try_statement(&mut self) -> Result<Stmt> {
+        self.span(&mut |this| {
+            this.reread(TokenData::Reserved(Reserved::Try));
+            let body = this.block()?;
+            match this.peek()?.value {
+                TokenData::Reserved(Reserved::Catch)
+              | TokenData::Reserved(Reserved::Finally) => { }
+                _ => {
+                    return Err(Error::OrphanTry(this.read()?));
+                }
+            }
+            let catch = this.catch_opt()?.map(Box::new);
+            let finally = this.finally_opt()?;
+            Ok(Stmt::Try(None, body, catch, finally))
+        })
+    }
+
+    fn catch_opt(&mut self) -> Result<Option<Catch>> {
+        match self.peek()?.value {
+            TokenData::Reserved(Reserved::Catch) => {
+                self.span(&mut |this| {
+                    this.reread(TokenData::Reserved(Reserved::Catch));
+                    let param = if this.matches(TokenData::LParen)? {
+                        let param = this.annotated_pattern()?;
+                        unique_names(param.bound_names(), Error::DuplicateBinding)?;
+                        this.expect(TokenData::RParen)?;
+                        Some(param)
+                    } else {
+                        None
+                    };
+
+                    let body = this.block()?;
+                    Ok(Catch { location: None, param: param, body: body })
+                }).map(Some)
+            }
+            _ => Ok(None)
+        }
+    }
+
+    fn finally_opt(&mut self) -> Result<Option<Block>> {
+        Ok(match self.peek()?.value {
+            TokenData::Reserved(Reserved::Finally) => {
+                self.reread(TokenData::Reserved(Reserved::Finally));
+                Some(self.block()?)
+            }
+            _ => None
+        })
+    }
+
+    fn debugger_statement(&mut self) -> Result<Stmt> {
+        let span = self.start();
+        self.reread(TokenData::Reserved(Reserved::Debugger));
+        Ok(span.end_with_auto_semi(self, Newline::Required, |semi| Stmt::Debugger(None, semi))?)
+    }
+
+    fn paren_expression(&mut self) -> Result<Expr> {
+        self.expect(TokenData::LParen)?;
+        let result = self.allow_in(true, |this| this.expression())?;
+        self.expect(TokenData::RParen)?;
+        Ok(result)
+    }
+
+    // PrimaryExpression ::=
+    //   "this"
+    //   IdentifierReference
+    //   Literal
+    //   ArrayLiteral
+    //   ObjectLiteral
+    //   FunctionExpression
+    //   ClassExpression
+    //   GeneratorExpression
+    //   AsyncFunctionExpression
+    //   RegularExpressionLiteral
+    //   TemplateLiteral
+    //   "(" Expression ")"
+    //   JSXElement                  (with the jsx option)
+    //   JSXFragment                 (with the jsx option)
+    fn primary_expression(&mut self) -> Result<Expr> {
+        let token = self.read()?;
+        self.legacy_literal_check(&token)?;
+        let location = token.location;
+        Ok(match token.value {
+            TokenData::Identifier(Name::Atom(Atom::Async)) if self.async_function_follows()? => {
+                // The name of a function expression is bound in the function's
+                // own context, so it can't be await here, or yield in an async
+                // generator.
+                return Ok(Expr::Fun(self.async_function(token, |this, generator| {
+                    this.in_generator(generator, |this| this.in_async(true, |this| this.id_opt(true)))
+                })?));
+            }
+            TokenData::Identifier(name)          => Expr::Id(self.new_id(false, name, location)?),
+            TokenData::Reserved(Reserved::Null)  => Expr::Null(Some(location)),
+            TokenData::Reserved(Reserved::This)  => Expr::This(Some(location)),
+            TokenData::Reserved(Reserved::True)  => Expr::True(Some(location)),
+            TokenData::Reserved(Reserved::False) => Expr::False(Some(location)),
+            TokenData::Number(literal)           => Expr::Number(Some(location), literal),
+            TokenData::BigInt(literal)           => Expr::BigInt(Some(location), literal),
+            TokenData::String(literal)           => Expr::String(Some(location), literal),
+            TokenData::RegExp(literal)           => Expr::RegExp(Some(location), literal),
+            TokenData::LBrack                    => { return self.array_literal(token); }
+            TokenData::LBrace                    => { return self.object_literal(token); }
+            TokenData::NoSubstTemplate(_)
+          | TokenData::TemplateHead(_)           => { return self.template_literal(token, false).map(Expr::Template); }
+            #[cfg(feature = "jsx")]
+            TokenData::LAngle if self.options.jsx => { return self.jsx_expression(token); }
+            TokenData::Reserved(Reserved::Function) => {
+                self.lexer.unread_token(token);
+                // The name of a generator expression can't be yield.
+                return Ok(Expr::Fun(self.function(|this, generator| {
+                    this.in_generator(generator, |this| this.id_opt(true))
+                })?));
+            }
+            TokenData::Reserved(Reserved::Class) | TokenData::At => {
+                self.lexer.unread_token(token);
+                let decorators = self.decorators()?;
+                return Ok(Expr::Class(self.class(decorators, |this| this.id_opt(true))?));
+            }
+            TokenData::LParen => {
+                self.lexer.unread_token(token);
+                return self.paren_expression();
+            }
+            // ES6: more cases
+            _ => { return Err(Error::UnexpectedToken(token)); }
+        })
+    }
+
+    // TemplateLiteral ::=
+    //   NoSubstitutionTemplate
+    //   TemplateHead Expression (TemplateMiddle Expression)* TemplateTail
+    fn template_literal(&mut self, start: Token, tagged: bool) -> Result<Template> {
+        let start_location = Some(start.location);
+        let mut strings = Vec::new();
+        let mut exprs = Vec::new();
+        let mut token = start;
+        loop {
+            let location = token.location;
+            let (value, done) = match token.value {
+                TokenData::NoSubstTemplate(value)
+              | TokenData::TemplateTail(value)   => (value, true),
+                TokenData::TemplateHead(value)
+              | TokenData::TemplateMiddle(value) => (value, false),
+                _ => { return Err(Error::UnexpectedToken(token)); }
+            };
+            // 12.2.9.1 Static Semantics: Early Errors
+            if !tagged && value.cooked.is_none() {
+                return Err(Error::InvalidTemplateEscape(location));
+            }
+            strings.push(TemplateString { location: Some(location), value: value });
+            if done {
+                return Ok(Template {
+                    location: span(&start_location, &Some(location)),
+                    strings: strings,
+                    exprs: exprs
+                });
+            }
+            exprs.push(self.allow_in(true, |this| this.expression())?);
+            let rbrace = self.expect(TokenData::RBrace)?;
+            token = self.lexer.read_template_continuation(rbrace).map_err(Error::LexError)?;
+        }
+    }
+
+    fn array_literal(&mut self, start: Token) -> Result<Expr> {
+        self.allow_in(true, |this| {
+            let start_location = Some(start.location);
+            let mut elts = Vec::new();
+            loop {
+                // Optional final comma does not count as an element.
+                if this.peek()?.value == TokenData::RBrack {
+                    break;
+                }
+                let elt = this.array_element()?;
+                let rest = match elt {
+                    Some(ExprListItem::Spread(..)) => true,
+                    _ => false
+                };
+                elts.push(elt);
+                if this.peek()?.value != TokenData::Comma {
+                    break;
+                }
+                let comma_location = Some(this.reread(TokenData::Comma).location);
+                // A rest element can't have a trailing comma, in case this
+                // is reinterpreted as an assignment pattern.
+                if rest && this.peek()?.value == TokenData::RBrack {
+                    this.cover_patt_error(cover::Error::RestTrailingComma(comma_location));
+                }
+            }
+            let end_location = Some(this.expect(TokenData::RBrack)?.location);
+            Ok(Expr::Arr(span(&start_location, &end_location), elts))
+        })
+    }
+
+    fn expr_list_item(&mut self) -> Result<ExprListItem> {
+        match self.peek()?.value {
+            TokenData::Ellipsis => {
+                self.span(&mut |this| {
+                    this.reread(TokenData::Ellipsis);
+                    let expr = this.assignment_expression()?;
+                    Ok(ExprListItem::Spread(None, expr))
+                })
+            }
+            _ => {
+                Ok(ExprListItem::Expr(self.assignment_expression()?))
+            }
+        }
+    }
+
+    fn array_element(&mut self) -> Result<Option<ExprListItem>> {
+        match self.peek()?.value {
+            TokenData::Comma => Ok(None),
+            TokenData::Ellipsis => {
+                self.span(&mut |this| {
+                    this.reread(TokenData::Ellipsis);
+                    let expr = this.element_expression()?;
+                    Ok(ExprListItem::Spread(None, expr))
+                }).map(Some)
+            }
+            _ => Ok(Some(ExprListItem::Expr(self.element_expression()?)))
+        }
+    }
+
+    fn object_literal(&mut self, start: Token) -> Result<Expr> {
+        let start_location = Some(start.location);
+        let mut props = Vec::new();
+        let mut proto = false;
+        loop {
+            if self.peek()?.value == TokenData::RBrace {
+                break;
+            }
+            let prop = self.object_property()?;
+            // B.3.1: at most one __proto__ property, unless the object literal
+            // is reinterpreted as an assignment pattern.
+            if let Prop::Regular(location, ref key, PropVal::Init(_)) = prop {
+                if self.options.annex_b && key.is_name("__proto__") {
+                    if proto {
+                        self.cover_error(Error::DuplicateProto(location));
+                    }
+                    proto = true;
+                }
+            }
+            let rest = match prop {
+                Prop::Spread(..) => true,
+                _ => false
+            };
+            props.push(prop);
+            if self.peek()?.value != TokenData::Comma {
+                break;
+            }
+            let comma_location = Some(self.reread(TokenData::Comma).location);
+            if rest && self.peek()?.value == TokenData::RBrace {
+                self.cover_patt_error(cover::Error::RestTrailingComma(comma_location));
+            }
+        }
+        let end_location = Some(self.expect(TokenData::RBrace)?.location);
+        Ok(Expr::Obj(span(&start_location, &end_location), props))
+    }
+
+    fn more_prop_init(&mut self, key: PropKey) -> Result<Prop> {
+        let types = self.options.types;
+        Ok(match self.peek()?.value {
+            TokenData::Colon => {
+                self.skip()?;
+                let val = self.allow_in(true, |this| this.element_expression())?;
+                Prop::Regular(span(key.tracking_ref(), val.tracking_ref()), key, PropVal::Init(val))
+            }
+            // CoverInitializedName ::= IdentifierReference Initializer
+            TokenData::Assign => {
+                if let PropKey::Id(location, name) = key {
+                    self.skip()?;
+                    let id = self.new_id(false, Name::from(name), location.unwrap())?;
+                    let init = self.allow_in(true, |this| this.assignment_expression())?;
+                    let location = span(&id, &init);
+                    self.cover_error(Error::CoverInitializedName(location));
+                    Prop::CoverInit(location, id, init)
+                } else {
+                    return self.unexpected();
+                }
+            }
+            TokenData::LParen => {
+                Prop::Method(self.more_method(key, false, false)?)
+            }
+            TokenData::LAngle if types => {
+                Prop::Method(self.more_method(key, false, false)?)
+            }
+            TokenData::Comma | TokenData::RBrace => {
+                if let PropKey::Id(location, name) = key {
+                    Prop::Shorthand(self.new_id(false, Name::from(name), location.unwrap())?)
+                } else {
+                    return self.unexpected();
+                }
+            }
+            _ => { return self.unexpected(); }
+        })
+    }
+
+    // PropertyName . "(" FormalParameters ")" "{" FunctionBody "}"
+    fn more_method(&mut self, key: PropKey, generator: bool, asynchronous: bool) -> Result<Fun<PropKey>> {
+        let type_params = self.type_parameters_opt()?;
+        let (this_type, params, ret, body) = self.in_method(generator, asynchronous, false, |this| {
+            let (this_type, params) = this.formal_parameters()?;
+            let ret = this.type_annotation_opt()?;
+            let body = this.function_body(&params.list, &params.rest)?;
+            Ok((this_type, params, ret, body))
+        })?;
+        Ok(Fun {
+            location: span(key.tracking_ref(), body.tracking_ref()),
+            id: key,
+            params: params,
+            body: body,
+            generator: generator,
+            asynchronous: asynchronous,
+            type_params: type_params,
+            this_type: this_type,
+            ret: ret
+        })
+    }
+
+    // PropertyName ::=
+    //   LiteralPropertyName
+    //   "[" AssignmentExpression "]"
+    fn property_key_opt(&mut self) -> Result<Option<PropKey>> {
+        let token = self.read()?;
+        self.legacy_literal_check(&token)?;
+        let location = Some(token.location);
+        Ok(Some(match token.value {
+            TokenData::LBrack => {
+                let expr = self.allow_in(true, |this| this.assignment_expression())?;
+                let end_location = Some(self.expect(TokenData::RBrack)?.location);
+                PropKey::Computed(span(&location, &end_location), Box::new(expr))
+            }
+            TokenData::Identifier(name) => PropKey::Id(location, name.into_string()),
+            TokenData::Reserved(word) => PropKey::Id(location, word.into_string()),
+            TokenData::String(s) => PropKey::String(location, s),
+            TokenData::Number(n) => PropKey::Number(location, n),
+            TokenData::BigInt(n) => PropKey::BigInt(location, n),
+            _ => {
+                self.lexer.unread_token(token);
+                return Ok(None);
+            }
+        }))
+    }
+
+    fn property_key(&mut self) -> Result<PropKey> {
+        match self.property_key_opt()? {
+            Some(key) => Ok(key),
+            None => self.unexpected()
+        }
+    }
+
+    fn object_property(&mut self) -> Result<Prop> {
+        let first = self.read()?;
+        match first.value {
+            TokenData::Ellipsis => {
+                let ellipsis_location = Some(first.location);
+                let expr = self.allow_in(true, |this| this.element_expression())?;
+                Ok(Prop::Spread(span(&ellipsis_location, &expr), expr))
+            }
+            TokenData::Identifier(Name::Atom(Atom::Get)) => {
+                if let Some(key) = self.property_key_opt()? {
+                    let paren_location = Some(self.expect(TokenData::LParen)?.location);
+                    self.expect(TokenData::RParen)?;
+                    let body = self.in_method(false, false, false, |this| this.function_body(&vec![], &None))?;
+                    let val_location = span(&paren_location, &body);
+                    let prop_location = span(&key, &body);
+                    return Ok(Prop::Regular(prop_location, key, PropVal::Get(val_location, body)));
+                }
+                let key_location = Some(first.location);
+                self.more_prop_init(PropKey::Id(key_location, "get".to_string()))
+            }
+            TokenData::Identifier(Name::Atom(Atom::Set)) => {
+                if let Some(key) = self.property_key_opt()? {
+                    let paren_location = Some(self.expect(TokenData::LParen)?.location);
+                    let (param, body) = self.in_method(false, false, false, |this| {
+                        let param = this.formal_parameter()?;
+                        this.expect(TokenData::RParen)?;
+                        let body = this.function_body(&[param.clone()], &None)?;
+                        Ok((param, body))
+                    })?;
+                    let val_location = span(&paren_location, &body);
+                    let prop_location = span(&key, &body);
+                    return Ok(Prop::Regular(prop_location, key, PropVal::Set(val_location, param, body)));
+                }
+                let key_location = Some(first.location);
+                self.more_prop_init(PropKey::Id(key_location, "set".to_string()))
+            }
+            TokenData::Star => {
+                let key = self.property_key()?;
+                Ok(Prop::Method(self.more_method(key, true, false)?))
+            }
+            TokenData::Identifier(Name::Atom(Atom::Async)) => {
+                let newline = self.peek()?.newline;
+                if !newline && self.matches(TokenData::Star)? {
+                    let key = self.property_key()?;
+                    return Ok(Prop::Method(self.more_method(key, true, true)?));
+                }
+                let key = if newline { None } else { self.property_key_opt()? };
+                if let Some(key) = key {
+                    return Ok(Prop::Method(self.more_method(key, false, true)?));
+                }
+                let key_location = Some(first.location);
+                self.more_prop_init(PropKey::Id(key_location, "async".to_string()))
+            }
+            TokenData::Reserved(_) => {
+                match self.peek()?.value {
+                    TokenData::Comma | TokenData::RBrace => {
+                        return Err(Error::UnexpectedToken(first));
+                    }
+                    _ => {
+                        self.lexer.unread_token(first);
+                        let key = self.property_key()?;
+                        self.more_prop_init(key)
+                    }
+                }
+            }
+            _ => {
+                self.lexer.unread_token(first);
+                let key = self.property_key()?;
+                self.more_prop_init(key)
+            }
+        }
+    }
+
+    // MemberBaseExpression ::=
+    //   PrimaryExpression
+    //   "new" "." "target"
+    //   "import" "." "meta"
+    fn member_base_expression(&mut self) -> Result<Expr> {
+        if let Some(new) = self.matches_token(TokenData::Reserved(Reserved::New))? {
+            self.expect(TokenData::Dot)?;
+            let target_location = Some(self.expect(TokenData::Identifier(Name::Atom(Atom::Target)))?.location);
+            return Ok(Expr::NewTarget(span(&Some(new.location), &target_location)));
+        }
+        if let Some(import) = self.matches_token(TokenData::Reserved(Reserved::Import))? {
+            return self.import_meta(import);
+        }
+        self.primary_expression()
+    }
+
+    // "import" . "." "meta"
+    fn import_meta(&mut self, import: Token) -> Result<Expr> {
+        self.expect(TokenData::Dot)?;
+        let meta_location = Some(self.expect(TokenData::Identifier(Name::Atom(Atom::Meta)))?.location);
+        let location = span(&Some(import.location), &meta_location);
+        self.require_module(Error::ImportMetaInScript(location))?;
+        Ok(Expr::ImportMeta(location))
+    }
+
+    // ImportCall ::= "import" . "(" AssignmentExpression ")"
+    fn import_call(&mut self, import: Token) -> Result<Expr> {
+        self.reread(TokenData::LParen);
+        let specifier = self.allow_in(true, |this| this.assignment_expression())?;
+        let end_location = Some(self.expect(TokenData::RParen)?.location);
+        Ok(Expr::Import(span(&Some(import.location), &end_location), Box::new(specifier)))
+    }
+
+    // "new"+n . (MemberBaseExpression | "super" Deref) Deref* Arguments<n Suffix*
+    fn new_expression(&mut self, news: Vec<Token>) -> Result<Expr> {
+        let base = if let Some(token) = self.matches_token(TokenData::Reserved(Reserved::Super))? {
+            self.super_property(token)?
+        } else {
+            self.member_base_expression()?
+        };
+        self.more_new_expression(news, base)
+    }
+
+    // "super" . Deref
+    fn super_property(&mut self, token: Token) -> Result<Expr> {
+        // 14.1.2 Static Semantics: Early Errors
+        if !self.context.super_property {
+            return Err(Error::IllegalSuperProperty(token.location));
+        }
+        let deref = match self.peek_op()?.value {
+            TokenData::LBrack => self.deref_brack()?,
+            TokenData::Dot    => self.deref_dot()?,
+            _ => { return self.unexpected(); }
+        };
+        Ok(deref.append_to(Expr::Super(Some(token.location))))
+    }
+
+    // "super" . Arguments
+    fn super_call(&mut self, token: Token) -> Result<Expr> {
+        // 14.1.2 Static Semantics: Early Errors
+        if !self.context.super_call {
+            return Err(Error::IllegalSuperCall(token.location));
+        }
+        Ok(self.arguments()?.append_to(Expr::Super(Some(token.location))))
+    }
+
+    // "new"+n MemberBaseExpression . Deref* Arguments<n Suffix*
+    fn more_new_expression(&mut self, news: Vec<Token>, mut base: Expr) -> Result<Expr> {
+        while let Some(deref) = self.deref_opt()? {
+            base = deref.append_to(base);
+        }
+        if let Some(args) = self.call_type_arguments_opt()? {
+            base = args.append_to(base);
+        }
+        let mut has_args = true;
+        for new in news.into_iter().rev() {
+            has_args = has_args && self.peek_op()?.value == TokenData::LParen;
+            base = if has_args {
+                self.arguments()?.append_to_new(new, base)
+            } else {
+                let location = span(&Some(new.location), &base);
+                Expr::New(location, Box::new(base), None)
+            };
+        }
+        // The constructor of a new expression can't be an optional chain.
+        if !has_args && self.peek_op()?.value == TokenData::QuestionDot {
+            return self.unexpected();
+        }
+        self.more_suffixes(base)
+    }
+
+    // CallExpression ::=
+    //   (MemberBaseExpression | "super" Suffix) Suffix*
+    fn call_expression(&mut self) -> Result<Expr> {
+        let base = if let Some(token) = self.matches_token(TokenData::Reserved(Reserved::Super))? {
+            if self.peek_op()?.value == TokenData::LParen {
+                self.super_call(token)?
+            } else {
+                self.super_property(token)?
+            }
+        } else if let Some(token) = self.matches_token(TokenData::Reserved(Reserved::Import))? {
+            if self.peek_op()?.value == TokenData::LParen {
+                self.import_call(token)?
+            } else {
+                self.import_meta(token)?
+            }
+        } else {
+            self.primary_expression()?
+        };
+        self.more_suffixes(base)
+    }
+
+    // Suffix ::=
+    //   Deref
+    //   Arguments
+    //   OptionalSuffix
+    //   TypeArguments        (in types mode, when followed by Arguments)
+    fn suffix_opt(&mut self) -> Result<Option<Suffix>> {
+        let types = self.options.types;
+        match self.peek_op()?.value {
+            TokenData::Dot    => self.deref_dot().map(|deref| Some(Suffix::Deref(deref))),
+            TokenData::LBrack => self.deref_brack().map(|deref| Some(Suffix::Deref(deref))),
+            TokenData::LParen => self.arguments().map(|args| Some(Suffix::Arguments(args))),
+            TokenData::QuestionDot => self.optional_suffix().map(Some),
+            TokenData::NoSubstTemplate(_)
+          | TokenData::TemplateHead(_) => self.deref_template().map(|deref| Some(Suffix::Deref(deref))),
+            TokenData::LAngle if types => self.call_type_arguments_opt().map(|args| args.map(Suffix::TypeArguments)),
+            _ => Ok(None)
+        }
+    }
+
+    // OptionalSuffix ::=
+    //   "?." "[" Expression "]"
+    //   "?." IdentifierName
+    //   "?." PrivateName
+    //   "?." Arguments
+    //   "?." TypeArguments Arguments (in types mode)
+    fn optional_suffix(&mut self) -> Result<Suffix> {
+        let types = self.options.types;
+        self.reread(TokenData::QuestionDot);
+        match self.peek_op()?.value {
+            TokenData::LBrack => self.deref_brack().map(Suffix::OptionalDeref),
+            TokenData::LParen => self.arguments().map(Suffix::OptionalArguments),
+            TokenData::LAngle if types => {
+                let (args, end) = self.type_arguments()?;
+                let type_args = TypeArguments { args: args, end: end };
+                Ok(Suffix::OptionalTypeArguments(type_args, self.arguments()?))
+            }
+            TokenData::NoSubstTemplate(_)
+          | TokenData::TemplateHead(_) => {
+                let location = self.lexer.repeek_token().location;
+                Err(Error::OptionalChainTemplate(Some(location)))
+            }
+            _ => self.dot_key().map(Suffix::OptionalDeref)
+        }
+    }
+
+    // Arguments ::= "(" Argument*[","] ","? ")"
+    fn arguments(&mut self) -> Result<Arguments> {
+        self.allow_in(true, |this| {
+            this.expect(TokenData::LParen)?;
+            let mut args = Vec::new();
+            if this.peek()?.value != TokenData::RParen {
+                loop {
+                    args.push(this.expr_list_item()?);
+                    if !this.matches(TokenData::Comma)? || this.peek()?.value == TokenData::RParen {
+                        break;
+                    }
+                }
+            }
+            let end = this.expect(TokenData::RParen)?;
+            Ok(Arguments { args: args, end: end })
+        })
+    }
+
+    // Deref ::=
+    //   "[" Expression "]"
+    //   "." IdentifierName
+    //   "." PrivateName
+    //   TemplateLiteral
+    fn deref_opt(&mut self) -> Result<Option<Deref>> {
+        match self.peek_op()?.value {
+            TokenData::LBrack => self.deref_brack().map(Some),
+            TokenData::Dot    => self.deref_dot().map(Some),
+            TokenData::NoSubstTemplate(_)
+          | TokenData::TemplateHead(_) => self.deref_template().map(Some),
+            _ => Ok(None)
+        }
+    }
+
+    fn deref_brack(&mut self) -> Result<Deref> {
+        self.reread(TokenData::LBrack);
+        let expr = self.allow_in(true, |this| this.expression())?;
+        let end = self.expect(TokenData::RBrack)?;
+        Ok(Deref::Brack(expr, end))
+    }
+
+    fn id_name(&mut self) -> Result<DotKey> {
+        let token = self.read()?;
+        Ok(DotKey {
+            location: Some(token.location),
+            value: match token.value {
+                TokenData::Identifier(name) => name.into_string(),
+                TokenData::Reserved(word) => word.into_string(),
+                _ => { return Err(Error::UnexpectedToken(token)); }
+            }
+        })
+    }
+
+    fn deref_dot(&mut self) -> Result<Deref> {
+        self.reread(TokenData::Dot);
+        self.dot_key()
+    }
+
+    fn dot_key(&mut self) -> Result<Deref> {
+        if let TokenData::PrivateName(_) = self.peek()?.value {
+            return self.private_reference().map(Deref::PrivateDot);
+        }
+        self.id_name().map(Deref::Dot)
+    }
+
+    fn deref_template(&mut self) -> Result<Deref> {
+        let start = self.read_op()?;
+        Ok(Deref::Template(self.template_literal(start, true)?))
+    }
+
+    // MemberBaseExpression . Suffix*
+    //
+    // A chain starting at the first OptionalSuffix extends to the end of the
+    // suffixes, and can't contain a TemplateLiteral.
+    fn more_suffixes(&mut self, mut result: Expr) -> Result<Expr> {
+        let mut chain = false;
+        while let Some(suffix) = self.suffix_opt()? {
+            match suffix {
+                Suffix::OptionalDeref(_)
+              | Suffix::OptionalArguments(_)
+              | Suffix::OptionalTypeArguments(_, _) => { chain = true; }
+                Suffix::Deref(Deref::Template(ref template)) if chain => {
+                    return Err(Error::OptionalChainTemplate(template.location));
+                }
+                _ => { }
+            }
+            result = suffix.append_to(result);
+        }
+        if chain {
+            let location = *result.tracking_ref();
+            result = Expr::Chain(location, Box::new(result));
+        }
+        Ok(result)
+    }
+
+    // LHSExpression ::=
+    //   NewExpression
+    //   CallExpression
+    fn lhs_expression(&mut self) -> Result<Expr> {
+        let mut news = Vec::new();
+        while self.peek()?.value == TokenData::Reserved(Reserved::New) {
+            news.push(self.reread(TokenData::Reserved(Reserved::New)));
+        }
+        if news.len() > 0 {
+            if self.matches_op(TokenData::Dot)? {
+                let target_location = Some(self.expect(TokenData::Identifier(Name::Atom(Atom::Target)))?.location);
+                let new = news.pop();
+                let new_location = new.map(|new| new.location);
+                let new_target = Expr::NewTarget(span(&new_location, &target_location));
+                if news.len() > 0 {
+                    self.more_new_expression(news, new_target)
+                } else {
+                    self.more_suffixes(new_target)
+                }
+            } else {
+                self.new_expression(news)
+            }
+        } else {
+            self.call_expression()
+        }
+    }
+
+    // UnaryExpression ::=
+    //   Prefix* LHSExpression PostfixOperator?
+    fn unary_expression(&mut self) -> Result<Expr> {
+        let mut prefixes = Vec::new();
+        while let Some(prefix) = self.match_prefix()? {
+            prefixes.push(prefix);
+        }
+        let unary = match prefixes.first() {
+            Some(&Prefix::Unop(_)) | Some(&Prefix::Await(_)) => true,
+            _ => false
+        };
+        let arg = self.lhs_expression()?;
+        let expr = self.more_unary(prefixes, arg)?;
+        // The base of an exponentiation can't be an unparenthesized unary expression.
+        if unary && self.peek_op()?.value == TokenData::StarStar {
+            return Err(Error::UnaryBeforeExponent(*expr.tracking_ref()));
+        }
+        Ok(expr)
+    }
+
+    // Prefix* LHSExpression . PostfixOperator?
+    fn more_unary(&mut self, prefixes: Vec<Prefix>, mut arg: Expr) -> Result<Expr> {
+        if let Some(postfix) = self.match_postfix_operator_opt()? {
+            let arg_location = *arg.tracking_ref();
+            arg = match arg.into_assign_target().map(Box::new) {
+                Ok(target) => {
+                    match postfix {
+                        Postfix::Inc(location) => Expr::PostInc(Some(location), target),
+                        Postfix::Dec(location) => Expr::PostDec(Some(location), target)
+                    }
+                }
+                Err(cover_err) => { return Err(Error::InvalidLHS(arg_location, cover_err)); }
+            };
+        }
+        for prefix in prefixes.into_iter().rev() {
+            match prefix {
+                Prefix::Unop(op)      => {
+                    if op.tag == UnopTag::Delete {
+                        // An optional chain is checked by its last link.
+                        let link = match arg {
+                            Expr::Chain(_, ref link) => &**link,
+                            ref arg => arg
+                        };
+                        match *link {
+                            Expr::PrivateDot(location, _, _)
+                          | Expr::OptionalPrivateDot(location, _, _) => {
+                                return Err(Error::DeletePrivateField(location));
+                            }
+                            _ => { }
+                        }
+                    }
+                    let location = span(&op, &arg);
+                    arg = Expr::Unop(location, op, Box::new(arg));
+                }
+                Prefix::Await(location) => {
+                    self.context.cover_await = self.context.cover_await.or(Some(location));
+                    let location = span(&Some(location), &arg);
+                    arg = Expr::Await(location, Box::new(arg));
+                }
+                _ => {
+                    let arg_location = *arg.tracking_ref();
+                    arg = match arg.into_assign_target().map(Box::new) {
+                        Ok(target) => {
+                            match prefix {
+                                Prefix::Inc(location) => Expr::PreInc(Some(location), target),
+                                Prefix::Dec(location) => Expr::PreDec(Some(location), target),
+                                Prefix::Unop(_) | Prefix::Await(_) => unreachable!()
+                            }
+                        }
+                        Err(cover_err) => { return Err(Error::InvalidLHS(arg_location, cover_err)); }
+                    };
+                }
+            }
+        }
+        Ok(arg)
+    }
+
+    // Prefix ::=
+    //   Unop
+    //   "++"
+    //   "--"
+    //   "await"
+    fn match_prefix(&mut self) -> Result<Option<Prefix>> {
+        let token = self.read()?;
+        Ok(match token.value {
+            TokenData::Inc => Some(Prefix::Inc(token.location)),
+            TokenData::Dec => Some(Prefix::Dec(token.location)),
+            TokenData::Identifier(Name::Atom(Atom::Await)) if self.context.asynchronous || self.top_level_await(token.location)? => {
+                // 14.7.1 Static Semantics: Early Errors
+                if self.context.params {
+                    return Err(Error::AwaitInParameter(token.location));
+                }
+                Some(Prefix::Await(token.location))
+            }
+            _ => {
+                self.lexer.unread_token(token);
+                self.match_unop()?.map(Prefix::Unop)
+            }
+        })
+    }
+
+    // Unop ::=
+    //   "delete"
+    //   "void"
+    //   "typeof"
+    //   "+"
+    //   "-"
+    //   "~"
+    //   "!"
+    fn match_unop(&mut self) -> Result<Option<Unop>> {
+        let token = self.read()?;
+        let tag = match token.value {
+            TokenData::Reserved(Reserved::Delete) => UnopTag::Delete,
+            TokenData::Reserved(Reserved::Void)   => UnopTag::Void,
+            TokenData::Reserved(Reserved::Typeof) => UnopTag::Typeof,
+            TokenData::Plus                       => UnopTag::Plus,
+            TokenData::Minus                      => UnopTag::Minus,
+            TokenData::Tilde                      => UnopTag::BitNot,
+            TokenData::Bang                       => UnopTag::Not,
+            _ => { self.lexer.unread_token(token); return Ok(None); }
+        };
+        Ok(Some(Op { location: Some(token.location), tag: tag }))
+    }
+
+    // PostfixOperator ::=
+    //   [no line terminator] "++"
+    //   [no line terminator] "--"
+    fn match_postfix_operator_opt(&mut self) -> Result<Option<Postfix>> {
+        let next = self.read_op()?;
+        if !next.newline {
+            match next.value {
+                TokenData::Inc => { return Ok(Some(Postfix::Inc(next.location))); }
+                TokenData::Dec => { return Ok(Some(Postfix::Dec(next.location))); }
+                _ => { }
+            }
+        }
+        self.lexer.unread_token(next);
+        Ok(None)
+    }
+
+    // ConditionalExpression ::=
+    //   InfixOperand (Infix InfixOperand)* ("?" AssignmentExpression ":" AssignmentExpression)?
+    fn conditional_expression(&mut self) -> Result<Expr> {
+        let mut stack = Stack::new();
+        let left = self.infix_operand(&mut stack)?;
+        let test = self.infix_expressions(stack, left)?;
+        self.more_conditional(test)
+    }
+
+    fn more_conditional(&mut self, left: Expr) -> Result<Expr> {
+        if self.matches_op(TokenData::Question)? {
+            let cons = self.allow_in(true, |this| this.assignment_expression())?;
+            self.expect(TokenData::Colon)?;
+            let alt = self.assignment_expression()?;
+            let location = span(&cons, &alt);
+            return Ok(Expr::Cond(location, Box::new(left), Box::new(cons), Box::new(alt)));
+        }
+        Ok(left)
+    }
+
+    // AssignmentExpression ::=
+    //   YieldPrefix* "yield"
+    //   YieldPrefix* ConditionalExpression (("=" | AssignmentOperator) AssignmentExpression)?
+    fn assignment_expression(&mut self) -> Result<Expr> {
+        let outer = self.context.cover_init.take();
+        let outer_paren = self.context.cover_paren.take();
+        let outer_patt = self.context.cover_patt.take();
+        let expr = self.cover_assignment_expression()?;
+        self.context.cover_paren = outer_paren;
+        self.context.cover_patt = outer_patt;
+        if let Some(error) = replace(&mut self.context.cover_init, outer) {
+            return Err(error);
+        }
+        Ok(expr)
+    }
+
+    // Records an error that only stands if the enclosing array or object
+    // literal isn't reinterpreted as an assignment pattern.
+    fn cover_error(&mut self, error: Error) {
+        if self.context.cover_init.is_none() {
+            self.context.cover_init = Some(error);
+        }
+    }
+
+    // Records an error that only stands if the enclosing array or object
+    // literal is reinterpreted as a pattern.
+    fn cover_patt_error(&mut self, error: cover::Error) {
+        if self.context.cover_patt.is_none() {
+            self.context.cover_patt = Some(error);
+        }
+    }
+
+    // An assignment expression in an element position of an array or object
+    // literal, which may still turn out to be part of an assignment pattern.
+    fn element_expression(&mut self) -> Result<Expr> {
+        let outer = self.context.cover_init.take();
+        let outer_paren = self.context.cover_paren.take();
+        let outer_patt = self.context.cover_patt.take();
+        let expr = self.cover_assignment_expression()?;
+        self.context.cover_init = outer.or(self.context.cover_init.take());
+        let (mut paren, mut patt) = (outer_paren, outer_patt);
+        if self.parenthesized(&expr) {
+            let location = *expr.tracking_ref();
+            paren = paren.or(location);
+            match expr {
+                Expr::Obj(..) | Expr::Arr(..) | Expr::Assign(..) => {
+                    patt = patt.or(Some(cover::Error::Parenthesized(location)));
+                }
+                _ => { }
+            }
+        }
+        self.context.cover_paren = paren.or(self.context.cover_paren.take());
+        self.context.cover_patt = patt.or(self.context.cover_patt.take());
+        Ok(expr)
+    }
+
+    fn cover_assignment_expression(&mut self) -> Result<Expr> {
+        let generator = self.context.generator;
+        let asynchronous = self.await_keyword();
+        let jsx = self.options.jsx;
+        match self.peek()?.value {
+            TokenData::LParen => {
+                return self.paren_expression_or_arrow_function(None);
+            }
+            // With the jsx option, a "<" here starts an element instead.
+            TokenData::LAngle if !jsx => {
+                if let Some(generic) = self.arrow_type_parameters_opt()? {
+                    return self.paren_expression_or_arrow_function(Some(generic));
+                }
+            }
+            TokenData::Identifier(Name::Atom(Atom::Yield)) if generator => {
+                return self.yield_expression();
+            }
+            TokenData::Identifier(Name::Atom(Atom::Await)) if asynchronous => { }
+            TokenData::Identifier(_) => {
+                let token = self.read()?;
+                if self.peek_op()?.value == TokenData::Arrow {
+                    let id = self.new_id_from_token(true, token)?;
+                    let params = Params {
+                        location: *id.tracking_ref(),
+                        list: vec![Patt::Simple(id)],
+                        rest: None
+                    };
+                    return self.arrow_function(*params.tracking_ref(), Vec::new(), params, false, None);
+                }
+                if token.value == TokenData::Identifier(Name::Atom(Atom::Async)) {
+                    if self.async_arrow_follows()? {
+                        return self.async_arrow_function_or_call(token, None);
+                    }
+                    if let Some(generic) = self.async_arrow_type_parameters_opt()? {
+                        return self.async_arrow_function_or_call(token, Some(generic));
+                    }
+                }
+                self.lexer.unread_token(token);
+            }
+            _ => { }
+        }
+        let left = self.conditional_expression()?;
+        self.more_assignment(left)
+    }
+
+    // YieldExpression ::=
+    //   "yield"
+    //   "yield" [no line terminator] "*"? AssignmentExpression
+    fn yield_expression(&mut self) -> Result<Expr> {
+        let start_location = Some(self.reread(TokenData::Identifier(Name::Atom(Atom::Yield))).location);
+        // 14.1.2 Static Semantics: Early Errors
+        if self.context.params {
+            return Err(Error::YieldInParameter(start_location.unwrap()));
+        }
+        self.context.cover_yield = self.context.cover_yield.or(start_location);
+        let no_arg = {
+            let next = self.peek()?;
+            next.newline || next.follow_assignment_expression()
+        };
+        if no_arg {
+            return Ok(Expr::Yield(start_location, None));
+        }
+        if self.matches(TokenData::Star)? {
+            let arg = self.assignment_expression()?;
+            return Ok(Expr::YieldDelegate(span(&start_location, &arg), Box::new(arg)));
+        }
+        let arg = self.assignment_expression()?;
+        Ok(Expr::Yield(span(&start_location, &arg), Some(Box::new(arg))))
+    }
+
+    // CoverParenthesizedExpressionAndArrowParameterList ::=
+    //   "(" Expression ","? ")"
+    //   "(" ")"
+    //   "(" (Expression ",")? "..." BindingTarget ")"
+    //
+    // Type parameters in front of the "(" make it an arrow function.
+    fn paren_expression_or_arrow_function(&mut self, generic: Option<(Span, Vec<TypeParam>)>) -> Result<Expr> {
+        let paren_location = Some(self.reread(TokenData::LParen).location);
+        let outer = self.context.cover_init.take();
+        let outer_await = self.context.cover_await.take();
+        let outer_yield = self.context.cover_yield.take();
+        let outer_paren = self.context.cover_paren.take();
+        let outer_patt = self.context.cover_patt.take();
+        let mut items = Vec::new();
+        let mut annotations = Vec::new();
+        let mut rest = None;
+        let mut trailing_comma = false;
+        self.allow_in(true, |this| {
+            loop {
+                match this.peek()?.value {
+                    TokenData::RParen => {
+                        break;
+                    }
+                    TokenData::Ellipsis => {
+                        rest = Some(this.rest_parameter()?);
+                        break;
+                    }
+                    _ => {
+                        let (item, question) = match this.cover_optional_param_opt()? {
+                            Some((item, question)) => (item, Some(question)),
+                            None => (this.element_expression()?, None)
+                        };
+                        annotations.push(this.cover_annotation_opt(&item, question)?);
+                        items.push(item);
+                        trailing_comma = this.matches(TokenData::Comma)?;
+                        if !trailing_comma {
+                            break;
+                        }
+                    }
+                }
+            }
+            Ok(())
+        })?;
+        let end = self.expect(TokenData::RParen)?;
+        let cover_init = replace(&mut self.context.cover_init, outer);
+        let cover_await = replace(&mut self.context.cover_await, outer_await);
+        let cover_yield = replace(&mut self.context.cover_yield, outer_yield);
+        let cover_paren = replace(&mut self.context.cover_paren, outer_paren);
+        let cover_patt = replace(&mut self.context.cover_patt, outer_patt);
+        let ret = self.arrow_return_type_opt()?;
+
+        if self.peek_op()?.value == TokenData::Arrow {
+            // 14.2.1 Static Semantics: Early Errors
+            if let Some(location) = cover_await {
+                return Err(Error::AwaitInParameter(location));
+            }
+            if let Some(location) = cover_yield {
+                return Err(Error::YieldInParameter(location));
+            }
+            if cover_paren.is_some() {
+                return Err(Error::InvalidParam(cover_paren, cover::Error::Parenthesized(cover_paren)));
+            }
+            if let Some(cover_err) = cover_patt {
+                return Err(Error::InvalidParam(span(&paren_location, &Some(end.location)), cover_err));
+            }
+            let location = span(&paren_location, &Some(end.location));
+            let params = self.cover_params(location, items, rest)?;
+            let (start_location, type_params) = match generic {
+                Some((location, type_params)) => (Some(location), type_params),
+                None => (location, Vec::new())
+            };
+            return self.arrow_function(start_location, type_params, annotate_params(params, annotations), false, ret);
+        }
+
+        // Only arrow parameters can have type annotations.
+        if generic.is_some() || annotations.iter().any(Option::is_some) {
+            return self.unexpected();
+        }
+        if items.is_empty() || rest.is_some() || trailing_comma {
+            return Err(Error::UnexpectedToken(end));
+        }
+        if let Some(error) = cover_init {
+            return Err(error);
+        }
+        self.context.cover_await = self.context.cover_await.or(cover_await);
+        self.context.cover_yield = self.context.cover_yield.or(cover_yield);
+        let expr = if items.len() == 1 {
+            items.pop().unwrap()
+        } else {
+            let location = self.vec_span(&items);
+            Expr::Seq(location, items)
+        };
+        self.context.paren = *expr.tracking_ref();
+        let left = self.more_conditional_expression(expr)?;
+        self.more_assignment(left)
+    }
+
+    // Reinterprets the items of a parenthesized expression as formal parameters.
+    fn cover_params(&mut self, location: Option<Span>, items: Vec<Expr>, rest: Option<RestPatt<Id>>) -> Result<Params> {
+        let mut list = Vec::with_capacity(items.len());
+        for item in items {
+            let location = *item.tracking_ref();
+            match item.into_binding_elt() {
+                Ok(patt) => { list.push(patt); }
+                Err(cover_err) => { return Err(Error::InvalidParam(location, cover_err)); }
+            }
+        }
+        let params = Params {
+            location: location,
+            list: list,
+            rest: rest
+        };
+        // The parameters were parsed as expressions, so they still need
+        // the checks for binding identifiers.
+        for id in params.bound_names() {
+            self.new_id(true, id.name.clone(), id.location.unwrap())?;
+        }
+        unique_names(params.bound_names(), Error::DuplicateParam)?;
+        Ok(params)
+    }
+
+    // "async" . [no line terminator] TypeParameters "("
+    fn async_arrow_type_parameters_opt(&mut self) -> Result<Option<(Span, Vec<TypeParam>)>> {
+        if self.peek_op()?.newline {
+            return Ok(None);
+        }
+        self.arrow_type_parameters_opt()
+    }
+
+    // "async" . [no line terminator] ("(" | BindingIdentifier)
+    fn async_arrow_follows(&mut self) -> Result<bool> {
+        let next = self.peek_op()?;
+        Ok(!next.newline && match next.value {
+            TokenData::LParen | TokenData::Identifier(_) => true,
+            _ => false
+        })
+    }
+
+    // AsyncArrowFunction ::=
+    //   "async" [no line terminator] BindingIdentifier "=>" AsyncConciseBody
+    //   CoverCallExpressionAndAsyncArrowHead "=>" AsyncConciseBody
+    // CoverCallExpressionAndAsyncArrowHead ::= "async" [no line terminator] Arguments
+    //
+    // In types mode, type parameters can come before the arguments. If it
+    // turns out to be a call, they were type arguments.
+    fn async_arrow_function_or_call(&mut self, async_token: Token, generic: Option<(Span, Vec<TypeParam>)>) -> Result<Expr> {
+        let start_location = Some(async_token.location);
+        if generic.is_none() && self.peek_op()?.value != TokenData::LParen {
+            let id = self.in_async(true, |this| this.id(true))?;
+            if self.peek_op()?.value != TokenData::Arrow {
+                return self.unexpected();
+            }
+            let params = Params {
+                location: *id.tracking_ref(),
+                list: vec![Patt::Simple(id)],
+                rest: None
+            };
+            return self.arrow_function(start_location, Vec::new(), params, true, None);
+        }
+
+        let paren_location = Some(self.reread(TokenData::LParen).location);
+        let outer = self.context.cover_init.take();
+        let outer_await = self.context.cover_await.take();
+        let outer_yield = self.context.cover_yield.take();
+        let outer_await_id = self.context.cover_await_id.take();
+        let outer_paren = self.context.cover_paren.take();
+        let outer_patt = self.context.cover_patt.take();
+        let mut args = Vec::new();
+        let mut annotations = Vec::new();
+        let mut trailing_comma = false;
+        self.allow_in(true, |this| {
+            loop {
+                let arg = match this.peek()?.value {
+                    TokenData::RParen => {
+                        break;
+                    }
+                    TokenData::Ellipsis => {
+                        let ellipsis_location = Some(this.reread(TokenData::Ellipsis).location);
+                        let expr = this.element_expression()?;
+                        // A rest parameter can't have an initializer.
+                        annotations.push(this.type_annotation_opt()?.map(|ty| CoverAnnotation {
+                            question: None,
+                            ty: Some(ty),
+                            init: None
+                        }));
+                        ExprListItem::Spread(span(&ellipsis_location, &expr), expr)
+                    }
+                    _ => {
+                        let (expr, question) = match this.cover_optional_param_opt()? {
+                            Some((expr, question)) => (expr, Some(question)),
+                            None => (this.element_expression()?, None)
+                        };
+                        annotations.push(this.cover_annotation_opt(&expr, question)?);
+                        ExprListItem::Expr(expr)
+                    }
+                };
+                args.push(arg);
+                trailing_comma = this.matches(TokenData::Comma)?;
+                if !trailing_comma {
+                    break;
+                }
+            }
+            Ok(())
+        })?;
+        let end = self.expect(TokenData::RParen)?;
+        let cover_init = replace(&mut self.context.cover_init, outer);
+        let cover_await = replace(&mut self.context.cover_await, outer_await);
+        let cover_yield = replace(&mut self.context.cover_yield, outer_yield);
+        let cover_await_id = replace(&mut self.context.cover_await_id, outer_await_id);
+        let cover_paren = replace(&mut self.context.cover_paren, outer_paren);
+        let cover_patt = replace(&mut self.context.cover_patt, outer_patt);
+        let ret = self.arrow_return_type_opt()?;
+
+        if self.peek_op()?.value == TokenData::Arrow {
+            // 14.7.1 Static Semantics: Early Errors
+            if let Some(location) = cover_await {
+                return Err(Error::AwaitInParameter(location));
+            }
+            if let Some(location) = cover_yield {
+                return Err(Error::YieldInParameter(location));
+            }
+            if let Some(location) = cover_await_id {
+                return Err(Error::ContextualKeyword(location, Atom::Await));
+            }
+            if cover_paren.is_some() {
+                return Err(Error::InvalidParam(cover_paren, cover::Error::Parenthesized(cover_paren)));
+            }
+            if let Some(cover_err) = cover_patt {
+                return Err(Error::InvalidParam(span(&paren_location, &Some(end.location)), cover_err));
+            }
+            let mut items = Vec::with_capacity(args.len());
+            let mut rest = None;
+            for arg in args {
+                if rest.is_some() {
+                    return Err(Error::UnexpectedToken(end));
+                }
+                match arg {
+                    ExprListItem::Expr(expr) => { items.push(expr); }
+                    ExprListItem::Spread(location, expr) => {
+                        let expr_location = *expr.tracking_ref();
+                        match expr.into_binding_patt() {
+                            Ok(patt) => { rest = Some(RestPatt { location: location, patt: patt }); }
+                            Err(cover_err) => { return Err(Error::InvalidParam(expr_location, cover_err)); }
+                        }
+                    }
+                }
+            }
+            if rest.is_some() && trailing_comma {
+                return Err(Error::UnexpectedToken(end));
+            }
+            let location = span(&paren_location, &Some(end.location));
+            let params = self.in_async(true, |this| this.cover_params(location, items, rest))?;
+            let type_params = generic.map_or_else(Vec::new, |(_, type_params)| type_params);
+            return self.arrow_function(start_location, type_params, annotate_params(params, annotations), true, ret);
+        }
+
+        if annotations.iter().any(Option::is_some) {
+            return self.unexpected();
+        }
+        if let Some(error) = cover_init {
+            return Err(error);
+        }
+        self.context.cover_await = self.context.cover_await.or(cover_await);
+        self.context.cover_yield = self.context.cover_yield.or(cover_yield);
+        self.context.cover_await_id = self.context.cover_await_id.or(cover_await_id);
+        let mut callee = Expr::Id(self.new_id_from_token(false, async_token)?);
+        if let Some((location, type_params)) = generic {
+            let args = type_params.into_iter().map(|param| match param {
+                TypeParam { id, bound: None, default: None, .. } => {
+                    let name = TypeName::Id(id);
+                    Ok(Type::Ref(*name.tracking_ref(), name, Vec::new()))
+                }
+                TypeParam { location, .. } => Err(Error::BoundedTypeArgument(location))
+            }).collect::<Result<Vec<_>>>()?;
+            callee = Expr::TypeArgs(span(&callee, &Some(location)), Box::new(callee), args);
+        }
+        let call = Expr::Call(span(&start_location, &Some(end.location)), Box::new(callee), args);
+        let left = self.more_conditional_expression(call)?;
+        self.more_assignment(left)
+    }
+
+    // ArrowFunction ::= ArrowParameters TypeAnnotation? "=>" ConciseBody
+    fn arrow_function(&mut self, start_location: Option<Span>, type_params: Vec<TypeParam>, params: Params, asynchronous: bool, ret: Option<Type>) -> Result<Expr> {
+        let arrow = self.reread(TokenData::Arrow);
+        if arrow.newline {
+            return Err(Error::ArrowNewline(arrow.location));
+        }
+        let (body, body_location) = self.in_arrow(asynchronous, |this| {
+            Ok(if this.peek()?.value == TokenData::LBrace {
+                let body = this.function_body(&params.list, &params.rest)?;
+                let location = *body.tracking_ref();
+                (ArrowBody::Block(body), location)
+            } else {
+                let expr = this.assignment_expression()?;
+                let location = *expr.tracking_ref();
+                (ArrowBody::Expr(Box::new(expr)), location)
+            })
+        })?;
+        Ok(Expr::Arrow(Arrow {
+            location: span(&start_location, &body_location),
+            params: params,
+            body: body,
+            asynchronous: asynchronous,
+            type_params: type_params,
+            ret: ret
+        }))
+    }
+
+    // Continues a ConditionalExpression after its leftmost PrimaryExpression.
+    fn more_conditional_expression(&mut self, primary: Expr) -> Result<Expr> {
+        let lhs = self.more_suffixes(primary)?;
+        let arg = self.more_unary(Vec::new(), lhs)?;
+        let test = self.more_infix_expressions(arg)?;
+        self.more_conditional(test)
+    }
+
+    fn more_assignment(&mut self, left: Expr) -> Result<Expr> {
+        let token = self.read_op()?;
+        let left_location = *left.tracking_ref();
+        if self.context.cover_init.is_some() {
+            match (&token.value, &left) {
+                (&TokenData::Assign, &Expr::Obj(..))
+              | (&TokenData::Assign, &Expr::Arr(..)) => { self.context.cover_init = None; }
+                (_, &Expr::Obj(..))
+              | (_, &Expr::Arr(..)) => { }
+                _ => { return Err(self.context.cover_init.take().unwrap()); }
+            }
+        }
+        if token.value == TokenData::Assign {
+            let cover_patt = self.context.cover_patt.take();
+            let left = self.cover_assign_patt(left, cover_patt)?;
+            let right = self.assignment_expression()?;
+            let location = span(&left, &right);
+            return Ok(Expr::Assign(location, left, Box::new(right)));
+        } else if let Some(op) = token.to_assop() {
+            let left = match left.into_assign_target() {
+                Ok(left) => left,
+                Err(cover_err) => { return Err(Error::InvalidLHS(left_location, cover_err)); }
+            };
+            let right = self.assignment_expression()?;
+            let location = span(&left, &right);
+            return Ok(Expr::BinAssign(location, op, left, Box::new(right)));
+        }
+        self.lexer.unread_token(token);
+        Ok(left)
     }
 
-    fn more_comma<T, F>(&mut self, first: T, f: F) -> Result<Vec<T>>
-        where F: Fn(&mut Self) -> Result<T>
-    {
-        let mut items = vec![first];
-        while self.matches(TokenData::Comma)? {
-            items.push(f(self)?);
+    // Reinterprets an expression as an assignment pattern. A literal
+    // pattern can't be parenthesized, nor have parenthesized literal or
+    // initializer elements.
+    fn cover_assign_patt(&mut self, left: Expr, cover_patt: Option<cover::Error>) -> Result<Patt<AssignTarget>> {
+        let left_location = *left.tracking_ref();
+        let error = match left {
+            Expr::Obj(..) | Expr::Arr(..) if self.parenthesized(&left) => Some(cover::Error::Parenthesized(left_location)),
+            Expr::Obj(..) | Expr::Arr(..) => cover_patt,
+            _ => None
+        };
+        if let Some(cover_err) = error {
+            return Err(Error::InvalidLHS(left_location, cover_err));
         }
-        Ok(items)
+        left.into_assign_patt().map_err(|cover_err| Error::InvalidLHS(left_location, cover_err))
     }
 
-    fn switch_statement(&mut self) -> Result<Stmt> {
-        self.span(&mut |this| {
-            this.reread(TokenData::Reserved(Reserved::Switch));
-            let disc = this.paren_expression()?;
-            let outer_switch = replace(&mut this.context.switch, true);
-            let cases = this.switch_cases();
-            replace(&mut this.context.switch, outer_switch);
-            Ok(Stmt::Switch(None, disc, cases?))
-        })
+    fn parenthesized(&self, expr: &Expr) -> bool {
+        self.context.paren.is_some() && *expr.tracking_ref() == self.context.paren
     }
 
-    fn switch_cases(&mut self) -> Result<Vec<Case>> {
-        self.expect(TokenData::LBrace)?;
-        let mut cases = Vec::new();
-        let mut found_default = false;
+    fn more_infix_expressions(&mut self, left: Expr) -> Result<Expr> {
+        self.infix_expressions(Stack::new(), left)
+    }
+
+    fn infix_expressions(&mut self, mut stack: Stack, left: Expr) -> Result<Expr> {
+        let mut operand = left;
+        let mut coalescing = None;
         loop {
-            match self.peek()?.value {
-                TokenData::Reserved(Reserved::Case) => { cases.push(self.case()?); }
-                TokenData::Reserved(Reserved::Default) => {
-                    if found_default {
-                        let token = self.reread(TokenData::Reserved(Reserved::Default));
-                        return Err(Error::DuplicateDefault(token));
-                    }
-                    found_default = true;
-                    cases.push(self.default()?);
+            // In types mode, "as" binds like a relational operator.
+            if let Some(ty) = self.as_type_opt()? {
+                operand = stack.reduce(operand, BinopTag::Lt.precedence());
+                let location = span(&operand, &ty);
+                operand = Expr::As(location, Box::new(operand), Box::new(ty));
+                continue;
+            }
+            let op = match self.match_infix()? {
+                Some(op) => op,
+                None => { break; }
+            };
+            // "??" can't be mixed with "||" or "&&" without parentheses.
+            if let Infix::Logop(ref logop) = op {
+                let coalesce = logop.tag == LogopTag::Coalesce;
+                if *coalescing.get_or_insert(coalesce) != coalesce {
+                    return Err(Error::MixedCoalesce(logop.location));
                 }
-                _ => { break; }
             }
+            stack.extend(operand, op);
+            //println!("{}\n", stack);
+            operand = self.infix_operand(&mut stack)?;
         }
-        self.expect(TokenData::RBrace)?;
-        Ok(cases)
+        Ok(stack.finish(operand))
     }
 
-    fn case(&mut self) -> Result<Case> {
-        self.span(&mut |this| {
-            this.reread(TokenData::Reserved(Reserved::Case));
-            let test = this.allow_in(true, |this| this.expression())?;
-            this.expect(TokenData::Colon)?;
-            let body = this.statement_list()?;
-            Ok(Case { location: None, test: Some(test), body: body })
-        })
+    // InfixOperand ::=
+    //   UnaryExpression
+    //   PrivateName "in" InfixOperand
+    //
+    // The private name is pushed straight onto the stack, since it can only
+    // be the left operand of "in".
+    fn infix_operand(&mut self, stack: &mut Stack) -> Result<Expr> {
+        while let TokenData::PrivateName(_) = self.peek()?.value {
+            let key = self.private_reference()?;
+            match self.match_infix()? {
+                Some(op @ Infix::Binop(Binop { tag: BinopTag::In, .. })) => {
+                    stack.extend_private(key, op).map_err(Error::UnexpectedPrivateName)?;
+                }
+                _ => { return Err(Error::UnexpectedPrivateName(key)); }
+            }
+        }
+        self.unary_expression()
     }
 
-    fn default(&mut self) -> Result<Case> {
-        self.span(&mut |this| {
-            this.reread(TokenData::Reserved(Reserved::Default));
-            this.expect(TokenData::Colon)?;
-            let body = this.statement_list()?;
-            Ok(Case { location: None, test: None, body: body })
-        })
+    fn match_infix(&mut self) -> Result<Option<Infix>> {
+        let token = self.read_op()?;
+        let result = token.to_binop(self.context.allow_in).map_or_else(|| {
+            token.to_logop().map(Infix::Logop)
+        }, |op| Some(Infix::Binop(op)));
+        if result.is_none() {
+            self.lexer.unread_token(token);
+        }
+        Ok(result)
     }
 
-    fn break_statement(&mut self) -> Result<Stmt> {
-        let span = self.start();
-        let break_token = self.reread(TokenData::Reserved(Reserved::Break));
-        let arg = if self.has_arg_same_line()? {
-            let id = self.id(false)?;
-            if !self.context.labels.contains_key(&Rc::new(id.name.clone())) {
-                return Err(Error::InvalidLabel(id));
-            }
-            Some(id)
-        } else {
-            if !self.context.iteration && !self.context.switch {
-                return Err(Error::IllegalBreak(break_token));
-            }
-            None
-        };
-        span.end_with_auto_semi(self, Newline::Required, |semi| {
-            Stmt::Break(None, arg, semi)
-        })
+    // Expression ::=
+    //   AssignmentExpression ("," AssignmentExpression)*
+    fn expression(&mut self) -> Result<Expr> {
+        let first = self.assignment_expression()?;
+        self.more_expressions(first)
     }
 
-    fn continue_statement(&mut self) -> Result<Stmt> {
-        let span = self.start();
-        let continue_token = self.reread(TokenData::Reserved(Reserved::Continue));
-        let arg = if self.has_arg_same_line()? {
-            let id = self.id(false)?;
-            match self.context.labels.get(&Rc::new(id.name.clone())) {
-                None                        => { return Err(Error::InvalidLabel(id)); }
-                Some(&LabelType::Statement) => { return Err(Error::InvalidLabelType(id)); }
-                _                           => { }
-            }
-            Some(id)
-        } else {
-            if !self.context.iteration {
-                return Err(Error::IllegalContinue(continue_token));
-            }
-            None
-        };
-        span.end_with_auto_semi(self, Newline::Required, |semi| {
-            Stmt::Cont(None, arg, semi)
-        })
+    fn more_expressions(&mut self, first: Expr) -> Result<Expr> {
+        if self.peek()?.value != TokenData::Comma {
+            return Ok(first);
+        }
+        let elts = self.more_comma(first, Self::assignment_expression)?;
+        let location = self.vec_span(&elts);
+        Ok(Expr::Seq(location, elts))
     }
+}
 
-    fn return_statement(&mut self) -> Result<Stmt> {
-        let span = self.start();
-        self.reread(TokenData::Reserved(Reserved::Return));
-        let arg = if self.has_arg_same_line()? {
-            Some(self.allow_in(true, |this| this.expression())?)
-        } else {
-            None
-        };
-        let result = span.end_with_auto_semi(self, Newline::Required, |semi| {
-            Stmt::Return(None, arg, semi)
-        })?;
-        if !self.context.function {
-            Err(Error::TopLevelReturn(result.tracking_ref().unwrap()))
-        } else {
-            Ok(result)
+// Fails on the first name that's bound more than once.
+fn unique_names<'a, I, F>(names: I, error: F) -> Result<()>
+  where I: IntoIterator<Item=&'a Id>,
+        F: FnOnce(Id) -> Error
+{
+    let mut seen = HashSet::new();
+    for id in names {
+        if !seen.insert(&id.name) {
+            return Err(error(id.clone()));
         }
     }
+    Ok(())
+}
 
-    fn with_statement(&mut self) -> Result<Stmt> {
-        self.span(&mut |this| {
-            let token = this.reread(TokenData::Reserved(Reserved::With));
-            this.strict_check(|_| {
-                Some(Check::Strict(Error::StrictWith(token)))
-            })?;
-            let obj = this.paren_expression()?;
-            let body = Box::new(this.statement()?);
-            Ok(Stmt::With(None, obj, body))
-        })
-    }
+fn typed_patt(patt: Patt<Id>, ty: Type) -> Patt<Id> {
+    Patt::Typed(span(&patt, &ty), Box::new(patt), Box::new(ty))
+}
 
-    fn throw_statement(&mut self) -> Result<Stmt> {
-        let span = self.start();
-        let token = self.reread(TokenData::Reserved(Reserved::Throw));
-        if !self.has_arg_same_line()? {
-            return Err(Error::ThrowArgument(token));
+fn optional_patt(patt: Patt<Id>, question: Span) -> Patt<Id> {
+    Patt::Optional(span(&patt, &Some(question)), Box::new(patt))
+}
+
+// The type annotation of an arrow parameter that was parsed as an item of a
+// parenthesized expression (or async call), with its "?" or initializer.
+struct CoverAnnotation {
+    question: Option<Span>,
+    ty: Option<Type>,
+    init: Option<Expr>
+}
+
+// Puts the type annotations of a parenthesized expression (or async call)
+// onto the arrow parameters it turned out to be. An extra annotation at the
+// end belongs to the rest parameter.
+fn annotate_params(params: Params, mut annotations: Vec<Option<CoverAnnotation>>) -> Params {
+    let Params { location, list, rest } = params;
+    let rest = match rest {
+        Some(RestPatt { location, patt }) if annotations.len() > list.len() => {
+            let patt = annotate_patt(patt, annotations.pop().unwrap());
+            Some(RestPatt { location: span(&location, &patt), patt: patt })
         }
-        let arg = self.allow_in(true, |this| this.expression())?;
-        span.end_with_auto_semi(self, Newline::Required, |semi| {
-            Stmt::Throw(None, arg, semi)
-        })
+        rest => rest
+    };
+    let list = list.into_iter().zip(annotations).map(|(patt, annotation)| {
+        annotate_patt(patt, annotation)
+    }).collect();
+    Params {
+        location: location,
+        list: list,
+        rest: rest
     }
+}
 
-    fn try_statement(&mut self) -> Result<Stmt> {
-        self.span(&mut |this| {
-            this.reread(TokenData::Reserved(Reserved::Try));
-            let body = this.block()?;
-            match this.peek()?.value {
-                TokenData::Reserved(Reserved::Catch)
-              | TokenData::Reserved(Reserved::Finally) => { }
-                _ => {
-                    return Err(Error::OrphanTry(this.read()?));
-                }
-            }
-            let catch = this.catch_opt()?.map(Box::new);
-            let finally = this.finally_opt()?;
-            Ok(Stmt::Try(None, body, catch, finally))
-        })
+fn annotate_patt(patt: Patt<Id>, annotation: Option<CoverAnnotation>) -> Patt<Id> {
+    let CoverAnnotation { question, ty, init } = match annotation {
+        Some(annotation) => annotation,
+        None => { return patt; }
+    };
+    let patt = match question {
+        Some(question) => optional_patt(patt, question),
+        None => patt
+    };
+    let patt = match ty {
+        Some(ty) => typed_patt(patt, ty),
+        None => patt
+    };
+    match init {
+        Some(init) => Patt::Default(span(&patt, &init), Box::new(patt), Box::new(init)),
+        None => patt
     }
+}
 
-    fn catch_opt(&mut self) -> Result<Option<Catch>> {
-        match self.peek()?.value {
-            TokenData::Reserved(Reserved::Catch) => {
-                self.span(&mut |this| {
-                    this.reread(TokenData::Reserved(Reserved::Catch));
-                    this.expect(TokenData::LParen)?;
-                    let param = this.pattern()?;
-                    this.expect(TokenData::RParen)?;
-
-                    let body = this.block()?;
-                    Ok(Catch { location: None, param: param, body: body })
-                }).map(Some)
+// Type annotations, interfaces and type aliases. These are only reached
+// when options.types is set, so plain JavaScript never gets here.
+impl<I: Iterator<Item=char>> Parser<I> {
+    // Runs a parse that may turn out to be the wrong one, giving back all the
+    // tokens it read and undoing its effects on the parser (the goal, the
+    // deferred checks, the context and the private names) if it fails.
+    // Lexical errors stand either way.
+    fn speculate<T, F>(&mut self, parse: F) -> Result<Option<T>>
+        where F: FnOnce(&mut Self) -> Result<T>
+    {
+        let goal = self.goal;
+        let deferred = self.deferred.clone();
+        let context = self.context.clone();
+        let private_scopes = self.private_scopes.clone();
+        self.lexer.checkpoint();
+        match parse(self) {
+            Ok(result) => {
+                self.lexer.commit();
+                Ok(Some(result))
+            }
+            Err(Error::LexError(error)) => {
+                self.lexer.commit();
+                Err(Error::LexError(error))
+            }
+            Err(_) => {
+                self.lexer.rewind();
+                self.goal = goal;
+                self.deferred = deferred;
+                self.context = context;
+                self.private_scopes = private_scopes;
+                Ok(None)
             }
-            _ => Ok(None)
         }
     }
 
-    fn finally_opt(&mut self) -> Result<Option<Block>> {
-        Ok(match self.peek()?.value {
-            TokenData::Reserved(Reserved::Finally) => {
-                self.reread(TokenData::Reserved(Reserved::Finally));
-                Some(self.block()?)
-            }
-            _ => None
+    // TypeAnnotation ::= ":" Type
+    fn type_annotation_opt(&mut self) -> Result<Option<Type>> {
+        if !self.options.types || !self.matches_op(TokenData::Colon)? {
+            return Ok(None);
+        }
+        self.ty().map(Some)
+    }
+
+    // BindingTarget . TypeAnnotation?
+    fn annotated(&mut self, patt: Patt<Id>) -> Result<Patt<Id>> {
+        Ok(match self.type_annotation_opt()? {
+            Some(ty) => typed_patt(patt, ty),
+            None => patt
         })
     }
 
-    fn debugger_statement(&mut self) -> Result<Stmt> {
-        let span = self.start();
-        self.reread(TokenData::Reserved(Reserved::Debugger));
-        Ok(span.end_with_auto_semi(self, Newline::Required, |semi| Stmt::Debugger(None, semi))?)
+    fn annotated_pattern(&mut self) -> Result<Patt<Id>> {
+        let patt = self.pattern()?;
+        self.annotated(patt)
     }
 
-    fn paren_expression(&mut self) -> Result<Expr> {
-        self.expect(TokenData::LParen)?;
-        let result = self.allow_in(true, |this| this.expression())?;
-        self.expect(TokenData::RParen)?;
-        Ok(result)
+    // BindingIdentifier . "?"
+    //
+    // Only a parameter can be optional, and only if it's a plain name.
+    fn optional_mark_opt(&mut self, patt: &Patt<Id>) -> Result<Option<Span>> {
+        if !self.options.types || !patt.is_simple() || self.peek_op()?.value != TokenData::Question {
+            return Ok(None);
+        }
+        Ok(Some(self.reread(TokenData::Question).location))
     }
 
-    // PrimaryExpression ::=
-    //   "this"
-    //   IdentifierReference
-    //   Literal
-    //   ArrayLiteral
-    //   ObjectLiteral
-    //   FunctionExpression
-    //   ClassExpression
-    //   GeneratorExpression
-    //   RegularExpressionLiteral
-    //   "(" Expression ")"
-    fn primary_expression(&mut self) -> Result<Expr> {
-        let token = self.read()?;
-        let location = token.location;
-        Ok(match token.value {
-            TokenData::Identifier(name)          => Expr::Id(self.new_id(false, name, location)?),
-            TokenData::Reserved(Reserved::Null)  => Expr::Null(Some(location)),
-            TokenData::Reserved(Reserved::This)  => Expr::This(Some(location)),
-            TokenData::Reserved(Reserved::True)  => Expr::True(Some(location)),
-            TokenData::Reserved(Reserved::False) => Expr::False(Some(location)),
-            TokenData::Number(literal)           => Expr::Number(Some(location), literal),
-            TokenData::String(literal)           => Expr::String(Some(location), literal),
-            TokenData::RegExp(literal)           => Expr::RegExp(Some(location), literal),
-            TokenData::LBrack                    => { return self.array_literal(token); }
-            TokenData::LBrace                    => { return self.object_literal(token); }
-            TokenData::Reserved(Reserved::Function) => {
-                self.lexer.unread_token(token);
-                return Ok(Expr::Fun(self.function(|this| this.id_opt(true))?));
-            }
-            TokenData::LParen => {
-                self.lexer.unread_token(token);
-                return self.paren_expression();
+    // ThisParameter ::= "this" TypeAnnotation ","?
+    //
+    // The comma can only be left out before the closing ")".
+    fn this_parameter_opt(&mut self) -> Result<Option<Type>> {
+        if !self.options.types || self.peek()?.value != TokenData::Reserved(Reserved::This) {
+            return Ok(None);
+        }
+        self.reread(TokenData::Reserved(Reserved::This));
+        self.expect(TokenData::Colon)?;
+        let ty = self.ty()?;
+        if self.peek()?.value != TokenData::RParen {
+            self.expect(TokenData::Comma)?;
+        }
+        Ok(Some(ty))
+    }
+
+    // The variable of a for-in or for-of loop can't be annotated.
+    fn untyped_for_head(&self, lhs: &Patt<Id>) -> Result<()> {
+        match *lhs {
+            Patt::Typed(location, _, _) => Err(Error::TypedForHead(location)),
+            _ => Ok(())
+        }
+    }
+
+    // An item of a parenthesized expression followed by ":" can only be an
+    // arrow parameter, so the annotation comes with its own initializer:
+    //
+    //   Item . ":" Type ("=" AssignmentExpression)?
+    //   Identifier "?" . (":" Type)?
+    fn cover_annotation_opt(&mut self, item: &Expr, question: Option<Span>) -> Result<Option<CoverAnnotation>> {
+        if !self.options.types || question.is_none() && self.peek_op()?.value != TokenData::Colon {
+            return Ok(None);
+        }
+        if let Expr::Assign(..) = *item {
+            return self.unexpected();
+        }
+        let ty = if self.matches_op(TokenData::Colon)? {
+            Some(self.ty()?)
+        } else {
+            None
+        };
+        let init = if question.is_none() && self.matches(TokenData::Assign)? {
+            Some(self.assignment_expression()?)
+        } else {
+            None
+        };
+        Ok(Some(CoverAnnotation {
+            question: question,
+            ty: ty,
+            init: init
+        }))
+    }
+
+    // Identifier "?" . (":" | "," | ")")
+    //
+    // An item of a parenthesized expression like this can't be the test of a
+    // conditional expression, so it's an optional arrow parameter.
+    fn cover_optional_param_opt(&mut self) -> Result<Option<(Expr, Span)>> {
+        if !self.options.types {
+            return Ok(None);
+        }
+        match self.peek()?.value {
+            TokenData::Identifier(_) => { }
+            _ => { return Ok(None); }
+        }
+        self.speculate(|this| {
+            let token = this.read()?;
+            let question = this.expect(TokenData::Question)?.location;
+            match this.peek_op()?.value {
+                TokenData::Colon | TokenData::Comma | TokenData::RParen => { }
+                _ => { return this.unexpected(); }
             }
-            // ES6: more cases
-            _ => { return Err(Error::UnexpectedToken(token)); }
+            let id = this.new_id_from_token(false, token)?;
+            Ok((Expr::Id(id), question))
         })
     }
 
-    fn array_literal(&mut self, start: Token) -> Result<Expr> {
-        self.allow_in(true, |this| {
-            let start_location = Some(start.location);
-            let mut elts = Vec::new();
-            loop {
-                // Optional final comma does not count as an element.
-                if this.peek()?.value == TokenData::RBrack {
-                    break;
-                }
-                elts.push(this.array_element()?);
-                if !this.matches(TokenData::Comma)? {
-                    break;
-                }
+    // TypeParameters . "("
+    //
+    // The "<" may be a less-than operator instead, so this backs off unless
+    // the type parameters are followed by "(". Returns the span from "<" to
+    // ">" along with the parameters.
+    fn arrow_type_parameters_opt(&mut self) -> Result<Option<(Span, Vec<TypeParam>)>> {
+        if !self.type_parameters_follow()? {
+            return Ok(None);
+        }
+        self.speculate(|this| {
+            let start = this.reread(TokenData::LAngle).location.start;
+            let (params, end) = this.more_type_parameters()?;
+            if this.peek()?.value != TokenData::LParen {
+                return this.unexpected();
             }
-            let end_location = Some(this.expect(TokenData::RBrack)?.location);
-            Ok(Expr::Arr(span(&start_location, &end_location), elts))
+            Ok((Span { start: start, end: end.location.end }, params))
         })
     }
 
-    fn expr_list_item(&mut self) -> Result<ExprListItem> {
-        match self.peek()?.value {
-            TokenData::Ellipsis => {
-                self.span(&mut |this| {
-                    this.reread(TokenData::Ellipsis);
-                    let expr = this.assignment_expression()?;
-                    Ok(ExprListItem::Spread(None, expr))
-                })
-            }
-            _ => {
-                Ok(ExprListItem::Expr(self.assignment_expression()?))
-            }
+    // ArrowParameters . ":" Type "=>"
+    //
+    // The ":" may belong to an enclosing conditional expression instead, so
+    // this backs off unless the type is followed by "=>".
+    fn arrow_return_type_opt(&mut self) -> Result<Option<Type>> {
+        if !self.options.types || self.peek_op()?.value != TokenData::Colon {
+            return Ok(None);
         }
+        self.speculate(|this| {
+            this.reread(TokenData::Colon);
+            let ty = this.ty()?;
+            if this.peek_op()?.value != TokenData::Arrow {
+                return this.unexpected();
+            }
+            Ok(ty)
+        })
     }
 
-    fn array_element(&mut self) -> Result<Option<ExprListItem>> {
-        if self.peek()?.value == TokenData::Comma {
+    // MemberExpression . TypeArguments Arguments
+    //
+    // The "<" may be a less-than operator instead, so this backs off unless
+    // the type arguments are followed by "(".
+    fn call_type_arguments_opt(&mut self) -> Result<Option<TypeArguments>> {
+        if !self.options.types || self.peek_op()?.value != TokenData::LAngle {
             return Ok(None);
         }
-        self.expr_list_item().map(Some)
+        self.speculate(|this| {
+            let (args, end) = this.type_arguments()?;
+            if this.peek()?.value != TokenData::LParen {
+                return this.unexpected();
+            }
+            Ok(TypeArguments { args: args, end: end })
+        })
     }
 
-    fn object_literal(&mut self, start: Token) -> Result<Expr> {
-        let start_location = Some(start.location);
-        let mut props = Vec::new();
-        loop {
-            if self.peek()?.value == TokenData::RBrace {
-                break;
-            }
-            props.push(self.object_property()?);
-            if !self.matches(TokenData::Comma)? {
-                break;
+    // InfixOperand . [no line terminator] "as" (Type | "const")
+    fn as_type_opt(&mut self) -> Result<Option<Type>> {
+        if !self.options.types {
+            return Ok(None);
+        }
+        {
+            let next = self.peek_op()?;
+            if next.newline || next.value != TokenData::Identifier(Name::Atom(Atom::As)) {
+                return Ok(None);
             }
         }
-        let end_location = Some(self.expect(TokenData::RBrace)?.location);
-        Ok(Expr::Obj(span(&start_location, &end_location), props))
+        self.reread(TokenData::Identifier(Name::Atom(Atom::As)));
+        if let Some(token) = self.matches_token(TokenData::Reserved(Reserved::Const))? {
+            return Ok(Some(Type::Const(Some(token.location))));
+        }
+        self.ty().map(Some)
     }
 
-    fn more_prop_init(&mut self, key: PropKey) -> Result<Prop> {
-        Ok(match self.peek()?.value {
-            TokenData::Colon => {
-                self.skip()?;
-                let val = self.allow_in(true, |this| this.assignment_expression())?;
-                Prop::Regular(span(key.tracking_ref(), val.tracking_ref()), key, PropVal::Init(val))
+    // Type ::=
+    //   FunctionType
+    //   "|"? IntersectionType+["|"]
+    fn ty(&mut self) -> Result<Type> {
+        match self.peek()?.value {
+            TokenData::LAngle => {
+                return self.fun_type().map(Type::Fun);
             }
+            // A "(" can also start a parenthesized type.
             TokenData::LParen => {
-                let params = self.formal_parameters()?;
-                let body = self.function_body(&params.list)?;
-                Prop::Method(Fun {
-                    location: span(key.tracking_ref(), body.tracking_ref()),
-                    id: key,
-                    params: params,
-                    body: body,
-                    generator: false
-                })
-            }
-            TokenData::Comma | TokenData::RBrace => {
-                if let PropKey::Id(location, name) = key {
-                    Prop::Shorthand(self.new_id(false, Name::from(name), location.unwrap())?)
-                } else {
-                    return self.unexpected();
+                if let Some(fun) = self.speculate(Self::fun_type)? {
+                    return Ok(Type::Fun(fun));
                 }
             }
-            _ => { return self.unexpected(); }
-        })
+            _ => { }
+        }
+        self.matches(TokenData::BitOr)?;
+        let first = self.intersection_type()?;
+        if self.peek_op()?.value != TokenData::BitOr {
+            return Ok(first);
+        }
+        let types = self.more_types(first, TokenData::BitOr, Self::intersection_type)?;
+        Ok(Type::Union(self.vec_span(&types), types))
     }
 
-    fn property_key_opt(&mut self) -> Result<Option<PropKey>> {
-        let token = self.read()?;
-        let location = Some(token.location);
-        Ok(Some(match token.value {
-            TokenData::Identifier(name) => PropKey::Id(location, name.into_string()),
-            TokenData::Reserved(word) => PropKey::Id(location, word.into_string()),
-            TokenData::String(s) => PropKey::String(location, s),
-            TokenData::Number(n) => PropKey::Number(location, n),
-            _ => {
-                self.lexer.unread_token(token);
-                return Ok(None);
-            }
-        }))
+    // IntersectionType ::= "&"? PostfixType+["&"]
+    fn intersection_type(&mut self) -> Result<Type> {
+        self.matches(TokenData::BitAnd)?;
+        let first = self.postfix_type()?;
+        if self.peek_op()?.value != TokenData::BitAnd {
+            return Ok(first);
+        }
+        let types = self.more_types(first, TokenData::BitAnd, Self::postfix_type)?;
+        Ok(Type::Intersection(self.vec_span(&types), types))
     }
 
-    fn property_key(&mut self) -> Result<PropKey> {
-        match self.property_key_opt()? {
-            Some(key) => Ok(key),
-            None => self.unexpected()
+    fn more_types<F>(&mut self, first: Type, separator: TokenData, f: F) -> Result<Vec<Type>>
+        where F: Fn(&mut Self) -> Result<Type>
+    {
+        let mut types = vec![first];
+        while self.matches_op(separator.clone())? {
+            types.push(f(self)?);
         }
+        Ok(types)
     }
 
-    fn object_property(&mut self) -> Result<Prop> {
-        let first = self.read()?;
-        match first.value {
-            TokenData::Identifier(Name::Atom(Atom::Get)) => {
-                if let Some(key) = self.property_key_opt()? {
-                    let paren_location = Some(self.expect(TokenData::LParen)?.location);
-                    self.expect(TokenData::RParen)?;
-                    let body = self.function_body(&vec![])?;
-                    let val_location = span(&paren_location, &body);
-                    let prop_location = span(&key, &body);
-                    return Ok(Prop::Regular(prop_location, key, PropVal::Get(val_location, body)));
+    // PostfixType ::= PrimaryType ([no line terminator] "[" "]")*
+    fn postfix_type(&mut self) -> Result<Type> {
+        let mut ty = self.primary_type()?;
+        loop {
+            {
+                let next = self.peek_op()?;
+                if next.newline || next.value != TokenData::LBrack {
+                    break;
                 }
-                let key_location = Some(first.location);
-                self.more_prop_init(PropKey::Id(key_location, "get".to_string()))
             }
-            TokenData::Identifier(Name::Atom(Atom::Set)) => {
-                if let Some(key) = self.property_key_opt()? {
-                    let paren_location = Some(self.expect(TokenData::LParen)?.location);
-                    let param = self.pattern()?;
-                    self.expect(TokenData::RParen)?;
-                    let body = self.function_body(&[param.clone()])?;
-                    let val_location = span(&paren_location, &body);
-                    let prop_location = span(&key, &body);
-                    return Ok(Prop::Regular(prop_location, key, PropVal::Set(val_location, param, body)));
+            self.reread(TokenData::LBrack);
+            let end_location = Some(self.expect(TokenData::RBrack)?.location);
+            ty = Type::Arr(span(&ty, &end_location), Box::new(ty));
+        }
+        Ok(ty)
+    }
+
+    // PrimaryType ::=
+    //   TypeName TypeArguments?
+    //   "typeof" TypeName
+    //   "void" | "null" | "this" | "true" | "false"
+    //   StringLiteral
+    //   NumericLiteral
+    //   "(" Type ")"
+    //   "[" Type*[","] "]"
+    //   "{" TypeMember* "}"
+    fn primary_type(&mut self) -> Result<Type> {
+        let token = self.read()?;
+        let location = Some(token.location);
+        Ok(match token.value {
+            TokenData::Identifier(_) => {
+                self.lexer.unread_token(token);
+                let name = self.type_name()?;
+                if self.peek_op()?.value != TokenData::LAngle {
+                    return Ok(Type::Ref(*name.tracking_ref(), name, Vec::new()));
                 }
-                let key_location = Some(first.location);
-                self.more_prop_init(PropKey::Id(key_location, "set".to_string()))
+                let (args, end) = self.type_arguments()?;
+                Type::Ref(span(&name, &Some(end.location)), name, args)
             }
-            TokenData::Star => {
-                let key = self.property_key()?;
-                let params = self.formal_parameters()?;
-                let body = self.function_body(&params.list)?;
-                Ok(Prop::Method(Fun {
-                    location: span(key.tracking_ref(), body.tracking_ref()),
-                    id: key,
-                    params: params,
-                    body: body,
-                    generator: true
-                }))
+            TokenData::Reserved(Reserved::Typeof) => {
+                let name = self.type_name()?;
+                Type::Query(span(&location, &name), name)
             }
-            TokenData::Reserved(_) => {
-                match self.peek()?.value {
-                    TokenData::Comma | TokenData::RBrace => {
-                        return Err(Error::UnexpectedToken(first));
-                    }
-                    _ => {
-                        self.lexer.unread_token(first);
-                        let key = self.property_key()?;
-                        self.more_prop_init(key)
-                    }
+            TokenData::Reserved(Reserved::Void)  => Type::Void(location),
+            TokenData::Reserved(Reserved::Null)  => Type::Null(location),
+            TokenData::Reserved(Reserved::This)  => Type::This(location),
+            TokenData::Reserved(Reserved::True)  => Type::True(location),
+            TokenData::Reserved(Reserved::False) => Type::False(location),
+            TokenData::String(literal)           => Type::String(location, literal),
+            TokenData::Number(literal)           => Type::Number(location, literal),
+            TokenData::LParen => {
+                let ty = self.ty()?;
+                self.expect(TokenData::RParen)?;
+                ty
+            }
+            TokenData::LBrack => {
+                let mut types = Vec::new();
+                if self.peek()?.value != TokenData::RBrack {
+                    types = self.comma_separated(Self::ty)?;
                 }
+                let end_location = Some(self.expect(TokenData::RBrack)?.location);
+                Type::Tuple(span(&location, &end_location), types)
             }
-            _ => {
-                self.lexer.unread_token(first);
-                let key = self.property_key()?;
-                self.more_prop_init(key)
+            TokenData::LBrace => {
+                let (members, end) = self.type_members()?;
+                Type::Obj(span(&location, &Some(end.location)), members)
             }
+            _ => { return Err(Error::UnexpectedToken(token)); }
+        })
+    }
+
+    // TypeName ::= Identifier ("." IdentifierName)*
+    fn type_name(&mut self) -> Result<TypeName> {
+        let mut name = TypeName::Id(self.type_id()?);
+        while self.matches_op(TokenData::Dot)? {
+            let key = self.id_name()?;
+            name = TypeName::Qualified(span(&name, &key), Box::new(name), key);
         }
+        Ok(name)
     }
 
-    // MemberBaseExpression ::=
-    //   PrimaryExpression
-    //   "new" "." "target"
-    fn member_base_expression(&mut self) -> Result<Expr> {
-        if let Some(new) = self.matches_token(TokenData::Reserved(Reserved::New))? {
-            self.expect(TokenData::Dot)?;
-            let target_location = Some(self.expect(TokenData::Identifier(Name::Atom(Atom::Target)))?.location);
-            return Ok(Expr::NewTarget(span(&Some(new.location), &target_location)));
+    // A name in a type. Types have their own namespace, so these aren't
+    // checked like the program's identifiers.
+    fn type_id(&mut self) -> Result<Id> {
+        let token = self.read()?;
+        match token.value {
+            TokenData::Identifier(name) => Ok(Id::new(name, Some(token.location))),
+            _ => Err(Error::UnexpectedToken(token))
         }
-        self.primary_expression()
     }
 
-    // "new"+n . (MemberBaseExpression | "super" Deref) Deref* Arguments<n Suffix*
-    fn new_expression(&mut self, news: Vec<Token>) -> Result<Expr> {
-        // ES6: if let Some(super) = self.match_token(TokenData::Reserved(Reserved::Super))? {
-        let base = self.member_base_expression()?;
-        self.more_new_expression(news, base)
+    // "{" . (TypeMember (";" | ",")?)* "}"
+    //
+    // A line break can also separate members. Returns the "}" token.
+    fn type_members(&mut self) -> Result<(Vec<TypeMember>, Token)> {
+        let mut members = Vec::new();
+        loop {
+            if let Some(end) = self.matches_token(TokenData::RBrace)? {
+                return Ok((members, end));
+            }
+            members.push(self.type_member()?);
+            let separated = {
+                let next = self.peek_op()?;
+                next.newline || next.value == TokenData::RBrace
+            };
+            if !self.matches_op(TokenData::Semi)? && !self.matches_op(TokenData::Comma)? && !separated {
+                return self.unexpected();
+            }
+        }
     }
 
-    // "new"+n MemberBaseExpression . Deref* Arguments<n Suffix*
-    fn more_new_expression(&mut self, news: Vec<Token>, mut base: Expr) -> Result<Expr> {
-        while let Some(deref) = self.deref_opt()? {
-            base = deref.append_to(base);
+    // TypeMember ::=
+    //   CallSignature
+    //   "[" Identifier ":" Type "]" TypeAnnotation
+    //   PropertyName "?"? CallSignature
+    //   PropertyName "?"? TypeAnnotation
+    fn type_member(&mut self) -> Result<TypeMember> {
+        match self.peek()?.value {
+            TokenData::LParen | TokenData::LAngle => {
+                return self.signature(TokenData::Colon).map(TypeMember::Call);
+            }
+            TokenData::LBrack => {
+                let start_location = Some(self.reread(TokenData::LBrack).location);
+                let id = self.type_id()?;
+                self.expect(TokenData::Colon)?;
+                let key = self.ty()?;
+                self.expect(TokenData::RBrack)?;
+                self.expect(TokenData::Colon)?;
+                let ty = self.ty()?;
+                return Ok(TypeMember::Index(span(&start_location, &ty), id, key, ty));
+            }
+            _ => { }
         }
-        let mut has_args = true;
-        for new in news.into_iter().rev() {
-            has_args = has_args && self.peek_op()?.value == TokenData::LParen;
-            base = if has_args {
-                self.arguments()?.append_to_new(new, base)
-            } else {
-                let location = span(&Some(new.location), &base);
-                Expr::New(location, Box::new(base), None)
-            };
+        let key = self.property_key()?;
+        let optional = self.matches_op(TokenData::Question)?;
+        match self.peek_op()?.value {
+            TokenData::LParen | TokenData::LAngle => {
+                let fun = self.signature(TokenData::Colon)?;
+                Ok(TypeMember::Method(span(&key, &fun), key, optional, fun))
+            }
+            _ => {
+                self.expect(TokenData::Colon)?;
+                let ty = self.ty()?;
+                Ok(TypeMember::Prop(span(&key, &ty), key, optional, ty))
+            }
         }
-        self.more_suffixes(base)
     }
 
-    // CallExpression ::=
-    //   (MemberBaseExpression | "super" Suffix) Suffix*
-    fn call_expression(&mut self) -> Result<Expr> {
-        // ES6: super
-        let base = self.primary_expression()?;
-        self.more_suffixes(base)
+    // FunctionType ::= TypeParameters? FunctionTypeParameters "=>" Type
+    fn fun_type(&mut self) -> Result<FunType> {
+        self.signature(TokenData::Arrow)
     }
 
-    // Suffix ::=
-    //   Deref
-    //   Arguments
-    fn suffix_opt(&mut self) -> Result<Option<Suffix>> {
-        match self.peek_op()?.value {
-            TokenData::Dot    => self.deref_dot().map(|deref| Some(Suffix::Deref(deref))),
-            TokenData::LBrack => self.deref_brack().map(|deref| Some(Suffix::Deref(deref))),
-            TokenData::LParen => self.arguments().map(|args| Some(Suffix::Arguments(args))),
-            _ => Ok(None)
-        }
+    // CallSignature ::= TypeParameters? FunctionTypeParameters ":" Type
+    fn signature(&mut self, separator: TokenData) -> Result<FunType> {
+        let start_location = Some(self.peek()?.location);
+        let type_params = self.type_parameters_opt()?;
+        let (params, rest) = self.fun_type_params()?;
+        self.expect(separator)?;
+        let ret = self.ty()?;
+        Ok(FunType {
+            location: span(&start_location, &ret),
+            type_params: type_params,
+            params: params,
+            rest: rest.map(Box::new),
+            ret: Box::new(ret)
+        })
     }
 
-    // Arguments ::= "(" Argument*[","] ")"
-    fn arguments(&mut self) -> Result<Arguments> {
-        self.allow_in(true, |this| {
-            this.expect(TokenData::LParen)?;
-            let mut args = Vec::new();
-            if this.peek()?.value != TokenData::RParen {
-                loop {
-                    args.push(this.expr_list_item()?);
-                    if !this.matches(TokenData::Comma)? {
+    // FunctionTypeParameters ::=
+    //   "(" FunctionTypeParameter*[","] ","? ")"
+    //   "(" (FunctionTypeParameter ",")* "..." FunctionTypeParameter ")"
+    fn fun_type_params(&mut self) -> Result<(Vec<FunTypeParam>, Option<FunTypeParam>)> {
+        self.expect(TokenData::LParen)?;
+        let mut params = Vec::new();
+        let mut rest = None;
+        loop {
+            match self.peek()?.value {
+                TokenData::RParen => {
+                    break;
+                }
+                TokenData::Ellipsis => {
+                    self.reread(TokenData::Ellipsis);
+                    rest = Some(self.fun_type_param()?);
+                    break;
+                }
+                _ => {
+                    params.push(self.fun_type_param()?);
+                    if !self.matches(TokenData::Comma)? {
                         break;
                     }
                 }
             }
-            let end = this.expect(TokenData::RParen)?;
-            Ok(Arguments { args: args, end: end })
+        }
+        self.expect(TokenData::RParen)?;
+        Ok((params, rest))
+    }
+
+    // FunctionTypeParameter ::= Identifier "?"? TypeAnnotation?
+    fn fun_type_param(&mut self) -> Result<FunTypeParam> {
+        let id = self.type_id()?;
+        let optional = self.matches_op(TokenData::Question)?;
+        let annotation = if self.matches_op(TokenData::Colon)? {
+            Some(self.ty()?)
+        } else {
+            None
+        };
+        let location = match annotation {
+            Some(ref ty) => span(&id, ty),
+            None => *id.tracking_ref()
+        };
+        Ok(FunTypeParam {
+            location: location,
+            id: id,
+            optional: optional,
+            annotation: annotation
         })
     }
 
-    // Deref ::=
-    //   "[" Expression "]"
-    //   "." IdentifierName
-    fn deref_opt(&mut self) -> Result<Option<Deref>> {
-        match self.peek_op()?.value {
-            TokenData::LBrack => self.deref_brack().map(Some),
-            TokenData::Dot    => self.deref_dot().map(Some),
-            _ => Ok(None)
-        }
+    fn type_parameters_follow(&mut self) -> Result<bool> {
+        Ok(self.options.types && self.peek_op()?.value == TokenData::LAngle)
     }
 
-    fn deref_brack(&mut self) -> Result<Deref> {
-        self.reread(TokenData::LBrack);
-        let expr = self.allow_in(true, |this| this.expression())?;
-        let end = self.expect(TokenData::RBrack)?;
-        Ok(Deref::Brack(expr, end))
+    // TypeParameters ::= "<" TypeParameter+[","] ","? ">"
+    fn type_parameters_opt(&mut self) -> Result<Vec<TypeParam>> {
+        if !self.type_parameters_follow()? {
+            return Ok(Vec::new());
+        }
+        self.reread(TokenData::LAngle);
+        self.more_type_parameters().map(|(params, _)| params)
     }
 
-    fn id_name(&mut self) -> Result<DotKey> {
-        let token = self.read()?;
-        Ok(DotKey {
-            location: Some(token.location),
-            value: match token.value {
-                TokenData::Identifier(name) => name.into_string(),
-                TokenData::Reserved(word) => word.into_string(),
-                _ => { return Err(Error::UnexpectedToken(token)); }
+    // "<" . TypeParameter+[","] ","? ">"
+    //
+    // Returns the closing ">" token.
+    fn more_type_parameters(&mut self) -> Result<(Vec<TypeParam>, Token)> {
+        let mut params = vec![self.type_parameter()?];
+        while self.matches(TokenData::Comma)? {
+            if self.peek_op()?.value == TokenData::RAngle {
+                break;
             }
+            params.push(self.type_parameter()?);
+        }
+        let end = self.type_rangle()?;
+        Ok((params, end))
+    }
+
+    // TypeParameter ::= Identifier ("extends" Type)? ("=" Type)?
+    fn type_parameter(&mut self) -> Result<TypeParam> {
+        let id = self.type_id()?;
+        let bound = if self.matches(TokenData::Reserved(Reserved::Extends))? {
+            Some(self.ty()?)
+        } else {
+            None
+        };
+        let default = if self.matches(TokenData::Assign)? {
+            Some(self.ty()?)
+        } else {
+            None
+        };
+        let location = match (&bound, &default) {
+            (_, &Some(ref ty))
+          | (&Some(ref ty), &None) => span(&id, ty),
+            (&None, &None) => *id.tracking_ref()
+        };
+        Ok(TypeParam {
+            location: location,
+            id: id,
+            bound: bound,
+            default: default
         })
     }
 
-    fn deref_dot(&mut self) -> Result<Deref> {
-        self.reread(TokenData::Dot);
-        Ok(Deref::Dot(self.id_name()?))
+    // TypeArguments ::= "<" Type+[","] ">"
+    //
+    // Returns the closing ">" token.
+    fn type_arguments(&mut self) -> Result<(Vec<Type>, Token)> {
+        self.reread(TokenData::LAngle);
+        let args = self.comma_separated(Self::ty)?;
+        let end = self.type_rangle()?;
+        Ok((args, end))
     }
 
-    // MemberBaseExpression . Suffix*
-    fn more_suffixes(&mut self, mut result: Expr) -> Result<Expr> {
-        while let Some(suffix) = self.suffix_opt()? {
-            result = suffix.append_to(result);
+    // The ">" that closes type parameters or arguments may have been lexed
+    // as the start of a longer operator, as in "A<B<C>>", so this splits
+    // the rest off into a token of its own.
+    fn type_rangle(&mut self) -> Result<Token> {
+        let token = self.read_op()?;
+        let rest = match token.value {
+            TokenData::RAngle        => { return Ok(token); }
+            TokenData::RShift        => TokenData::RAngle,
+            TokenData::URShift       => TokenData::RShift,
+            TokenData::GEq           => TokenData::Assign,
+            TokenData::RShiftAssign  => TokenData::GEq,
+            TokenData::URShiftAssign => TokenData::RShiftAssign,
+            _ => { return Err(Error::UnexpectedToken(token)); }
+        };
+        let Span { start, end } = token.location;
+        let split = Posn { offset: start.offset + 1, line: start.line, column: start.column + 1 };
+        self.lexer.unread_token(Token {
+            location: Span { start: split, end: end },
+            newline: false,
+            value: rest
+        });
+        Ok(Token {
+            location: Span { start: start, end: split },
+            newline: token.newline,
+            value: TokenData::RAngle
+        })
+    }
+
+    // "interface" or "type" . [no line terminator] Identifier
+    //
+    // Both words are ordinary identifiers anywhere else.
+    fn type_declaration_follows(&mut self, token: &Token) -> Result<bool> {
+        match token.value {
+            TokenData::Identifier(Name::Atom(Atom::Interface)) => { }
+            TokenData::Identifier(ref name) if name.as_ref() == "type" => { }
+            _ => { return Ok(false); }
         }
-        Ok(result)
+        let next = self.peek_op()?;
+        Ok(!next.newline && match next.value {
+            TokenData::Identifier(_) => true,
+            _ => false
+        })
     }
 
-    // LHSExpression ::=
-    //   NewExpression
-    //   CallExpression
-    fn lhs_expression(&mut self) -> Result<Expr> {
-        let mut news = Vec::new();
-        while self.peek()?.value == TokenData::Reserved(Reserved::New) {
-            news.push(self.reread(TokenData::Reserved(Reserved::New)));
+    // InterfaceDeclaration ::=
+    //   "interface" Identifier TypeParameters? ("extends" Type+[","])? "{" TypeMember* "}"
+    // TypeAliasDeclaration ::=
+    //   "type" Identifier TypeParameters? "=" Type ";"
+    fn type_declaration(&mut self, token: Token) -> Result<Decl> {
+        let start_location = Some(token.location);
+        let id = self.type_id()?;
+        let type_params = self.type_parameters_opt()?;
+        if token.value != TokenData::Identifier(Name::Atom(Atom::Interface)) {
+            self.expect(TokenData::Assign)?;
+            let ty = self.ty()?;
+            let tracker = SpanTracker::new(token.location.start);
+            return tracker.end_with_auto_semi(self, Newline::Required, |semi| Decl::TypeAlias(TypeAlias {
+                location: None,
+                id: id,
+                type_params: type_params,
+                ty: ty,
+                semi: semi
+            }));
         }
-        if news.len() > 0 {
-            if self.matches_op(TokenData::Dot)? {
-                let target_location = Some(self.expect(TokenData::Identifier(Name::Atom(Atom::Target)))?.location);
-                let new = news.pop();
-                let new_location = new.map(|new| new.location);
-                let new_target = Expr::NewTarget(span(&new_location, &target_location));
-                if news.len() > 0 {
-                    self.more_new_expression(news, new_target)
-                } else {
-                    self.more_suffixes(new_target)
-                }
-            } else {
-                self.new_expression(news)
-            }
+        let extends = if self.matches(TokenData::Reserved(Reserved::Extends))? {
+            self.comma_separated(Self::ty)?
         } else {
-            self.call_expression()
-        }
+            Vec::new()
+        };
+        self.expect(TokenData::LBrace)?;
+        let (body, end) = self.type_members()?;
+        Ok(Decl::Interface(Interface {
+            location: span(&start_location, &Some(end.location)),
+            id: id,
+            type_params: type_params,
+            extends: extends,
+            body: body
+        }))
     }
+}
 
-    // UnaryExpression ::=
-    //   Prefix* LHSExpression PostfixOperator?
-    fn unary_expression(&mut self) -> Result<Expr> {
-        let mut prefixes = Vec::new();
-        while let Some(prefix) = self.match_prefix()? {
-            prefixes.push(prefix);
-        }
-        let mut arg = self.lhs_expression()?;
-        if let Some(postfix) = self.match_postfix_operator_opt()? {
-            let arg_location = *arg.tracking_ref();
-            arg = match arg.into_assign_target().map(Box::new) {
-                Ok(target) => {
-                    match postfix {
-                        Postfix::Inc(location) => Expr::PostInc(Some(location), target),
-                        Postfix::Dec(location) => Expr::PostDec(Some(location), target)
-                    }
-                }
-                Err(cover_err) => { return Err(Error::InvalidLHS(arg_location, cover_err)); }
-            };
-        }
-        for prefix in prefixes.into_iter().rev() {
-            match prefix {
-                Prefix::Unop(op)      => {
-                    let location = span(&op, &arg);
-                    arg = Expr::Unop(location, op, Box::new(arg));
-                }
-                _ => {
-                    let arg_location = *arg.tracking_ref();
-                    arg = match arg.into_assign_target().map(Box::new) {
-                        Ok(target) => {
-                            match prefix {
-                                Prefix::Inc(location) => Expr::PreInc(Some(location), target),
-                                Prefix::Dec(location) => Expr::PreDec(Some(location), target),
-                                Prefix::Unop(_) => unreachable!()
-                            }
-                        }
-                        Err(cover_err) => { return Err(Error::InvalidLHS(arg_location, cover_err)); }
-                    };
-                }
-            }
-        }
-        Ok(arg)
+// JSX is lexed in its own modes (see joker::Lexer::read_jsx_tag_token), so
+// these functions read tokens straight from the lexer, and only go through
+// the ordinary token helpers for the braced JavaScript expressions.
+#[cfg(feature = "jsx")]
+impl<I: Iterator<Item=char>> Parser<I> {
+    fn jsx_peek_tag(&mut self) -> Result<&Token> {
+        self.lexer.peek_jsx_tag_token().map_err(Error::LexError)
     }
 
-    // Prefix ::=
-    //   Unop
-    //   "++"
-    //   "--"
-    fn match_prefix(&mut self) -> Result<Option<Prefix>> {
-        let token = self.read()?;
-        Ok(match token.value {
-            TokenData::Inc => Some(Prefix::Inc(token.location)),
-            TokenData::Dec => Some(Prefix::Dec(token.location)),
-            _ => {
-                self.lexer.unread_token(token);
-                self.match_unop()?.map(Prefix::Unop)
-            }
-        })
+    fn jsx_read_tag(&mut self) -> Result<Token> {
+        self.lexer.read_jsx_tag_token().map_err(Error::LexError)
     }
 
-    // Unop ::=
-    //   "delete"
-    //   "void"
-    //   "typeof"
-    //   "+"
-    //   "-"
-    //   "~"
-    //   "!"
-    fn match_unop(&mut self) -> Result<Option<Unop>> {
-        let token = self.read()?;
-        let tag = match token.value {
-            TokenData::Reserved(Reserved::Delete) => UnopTag::Delete,
-            TokenData::Reserved(Reserved::Void)   => UnopTag::Void,
-            TokenData::Reserved(Reserved::Typeof) => UnopTag::Typeof,
-            TokenData::Plus                       => UnopTag::Plus,
-            TokenData::Minus                      => UnopTag::Minus,
-            TokenData::Tilde                      => UnopTag::BitNot,
-            TokenData::Bang                       => UnopTag::Not,
-            _ => { self.lexer.unread_token(token); return Ok(None); }
-        };
-        Ok(Some(Op { location: Some(token.location), tag: tag }))
+    fn jsx_expect_tag(&mut self, expected: TokenData) -> Result<Token> {
+        let token = self.jsx_read_tag()?;
+        if token.value != expected {
+            return Err(Error::UnexpectedToken(token));
+        }
+        Ok(token)
     }
 
-    // PostfixOperator ::=
-    //   [no line terminator] "++"
-    //   [no line terminator] "--"
-    fn match_postfix_operator_opt(&mut self) -> Result<Option<Postfix>> {
-        let next = self.read_op()?;
-        if !next.newline {
-            match next.value {
-                TokenData::Inc => { return Ok(Some(Postfix::Inc(next.location))); }
-                TokenData::Dec => { return Ok(Some(Postfix::Dec(next.location))); }
-                _ => { }
-            }
+    fn jsx_matches_tag(&mut self, expected: TokenData) -> Result<Option<Token>> {
+        if self.jsx_peek_tag()?.value == expected {
+            return self.jsx_read_tag().map(Some);
         }
-        self.lexer.unread_token(next);
         Ok(None)
     }
 
-    // ConditionalExpression ::=
-    //   UnaryExpression (Infix UnaryExpression)* ("?" AssignmentExpression ":" AssignmentExpression)?
-    fn conditional_expression(&mut self) -> Result<Expr> {
-        let left = self.unary_expression()?;
-        let test = self.more_infix_expressions(left)?;
-        self.more_conditional(test)
+    fn jsx_expression(&mut self, langle: Token) -> Result<Expr> {
+        Ok(match self.jsx_element_or_fragment(langle)? {
+            JsxChild::Element(element) => Expr::JsxElement(Box::new(element)),
+            JsxChild::Fragment(fragment) => Expr::JsxFragment(Box::new(fragment)),
+            _ => unreachable!()
+        })
     }
 
-    fn more_conditional(&mut self, left: Expr) -> Result<Expr> {
-        if self.matches_op(TokenData::Question)? {
-            let cons = self.allow_in(true, |this| this.assignment_expression())?;
-            self.expect(TokenData::Colon)?;
-            let alt = self.assignment_expression()?;
-            let location = span(&cons, &alt);
-            return Ok(Expr::Cond(location, Box::new(left), Box::new(cons), Box::new(alt)));
+    // JSXElement ::=
+    //   "<" JSXElementName JSXAttribute* "/" ">"
+    //   "<" JSXElementName JSXAttribute* ">" JSXChild* "<" "/" JSXElementName ">"
+    // JSXFragment ::= "<" ">" JSXChild* "<" "/" ">"
+    fn jsx_element_or_fragment(&mut self, langle: Token) -> Result<JsxChild> {
+        let start_location = Some(langle.location);
+
+        if let Some(rangle) = self.jsx_matches_tag(TokenData::RAngle)? {
+            let opening = span(&start_location, &Some(rangle.location));
+            let (children, closing_start) = self.jsx_children()?;
+            let name = if self.jsx_peek_tag()?.value == TokenData::RAngle {
+                None
+            } else {
+                Some(self.jsx_element_name()?)
+            };
+            let closing = span(&closing_start, &Some(self.jsx_expect_tag(TokenData::RAngle)?.location));
+            if name.is_some() {
+                return Err(Error::MismatchedJsxTag(opening, closing));
+            }
+            return Ok(JsxChild::Fragment(JsxFragment {
+                location: span(&start_location, &closing),
+                opening: opening,
+                children: children,
+                closing: closing
+            }));
         }
-        Ok(left)
+
+        let name = self.jsx_element_name()?;
+        let attrs = self.jsx_attributes()?;
+        let self_closing = self.jsx_matches_tag(TokenData::Slash)?.is_some();
+        let end_location = Some(self.jsx_expect_tag(TokenData::RAngle)?.location);
+        let opening = JsxOpeningElement {
+            location: span(&start_location, &end_location),
+            name: name,
+            attrs: attrs,
+            self_closing: self_closing
+        };
+        if self_closing {
+            return Ok(JsxChild::Element(JsxElement {
+                location: opening.location,
+                opening: opening,
+                children: Vec::new(),
+                closing: None
+            }));
+        }
+
+        let (children, closing_start) = self.jsx_children()?;
+        if let Some(rangle) = self.jsx_matches_tag(TokenData::RAngle)? {
+            return Err(Error::MismatchedJsxTag(opening.location, span(&closing_start, &Some(rangle.location))));
+        }
+        let closing_name = self.jsx_element_name()?;
+        let end_location = Some(self.jsx_expect_tag(TokenData::RAngle)?.location);
+        let closing = JsxClosingElement {
+            location: span(&closing_start, &end_location),
+            name: closing_name
+        };
+        if !opening.name.matches(&closing.name) {
+            return Err(Error::MismatchedJsxTag(opening.location, closing.location));
+        }
+        Ok(JsxChild::Element(JsxElement {
+            location: span(&start_location, &closing),
+            opening: opening,
+            children: children,
+            closing: Some(closing)
+        }))
     }
 
-    // AssignmentExpression ::=
-    //   YieldPrefix* "yield"
-    //   YieldPrefix* ConditionalExpression (("=" | AssignmentOperator) AssignmentExpression)?
-    fn assignment_expression(&mut self) -> Result<Expr> {
-        let left = self.conditional_expression()?;
-        self.more_assignment(left)
+    // Reads children up to and including the "<" "/" of the closing tag,
+    // returning the location of the "<".
+    fn jsx_children(&mut self) -> Result<(Vec<JsxChild>, Option<Span>)> {
+        let mut children = Vec::new();
+        loop {
+            let token = self.lexer.read_jsx_child_token().map_err(Error::LexError)?;
+            let location = Some(token.location);
+            children.push(match token.value {
+                TokenData::JsxText(text) => JsxChild::Text(location, text),
+                TokenData::LBrace => self.jsx_child_expression(token)?,
+                TokenData::LAngle => {
+                    if self.jsx_matches_tag(TokenData::Slash)?.is_some() {
+                        return Ok((children, location));
+                    }
+                    self.jsx_element_or_fragment(token)?
+                }
+                _ => { return Err(Error::UnexpectedToken(token)); }
+            });
+        }
     }
 
-    fn more_assignment(&mut self, left: Expr) -> Result<Expr> {
-        let token = self.read_op()?;
-        let left_location = *left.tracking_ref();
-        if token.value == TokenData::Assign {
-            let left = match left.into_assign_patt() {
-                Ok(left) => left,
-                Err(cover_err) => { return Err(Error::InvalidLHS(left_location, cover_err)); }
-            };
-            let right = self.assignment_expression()?;
-            let location = span(&left, &right);
-            return Ok(Expr::Assign(location, left, Box::new(right)));
-        } else if let Some(op) = token.to_assop() {
-            let left = match left.into_assign_target() {
-                Ok(left) => left,
-                Err(cover_err) => { return Err(Error::InvalidLHS(left_location, cover_err)); }
-            };
-            let right = self.assignment_expression()?;
-            let location = span(&left, &right);
-            return Ok(Expr::BinAssign(location, op, left, Box::new(right)));
+    // JSXChild ::= ... | "{" Expression? "}" | "{" "..." AssignmentExpression "}"
+    fn jsx_child_expression(&mut self, lbrace: Token) -> Result<JsxChild> {
+        let start_location = Some(lbrace.location);
+        if self.matches(TokenData::Ellipsis)? {
+            let expr = self.allow_in(true, |this| this.assignment_expression())?;
+            let end_location = Some(self.expect(TokenData::RBrace)?.location);
+            return Ok(JsxChild::Spread(span(&start_location, &end_location), expr));
         }
-        self.lexer.unread_token(token);
-        Ok(left)
+        let expr = if self.peek()?.value == TokenData::RBrace {
+            None
+        } else {
+            Some(self.allow_in(true, |this| this.assignment_expression())?)
+        };
+        let end_location = Some(self.expect(TokenData::RBrace)?.location);
+        Ok(JsxChild::Expr(JsxExprContainer {
+            location: span(&start_location, &end_location),
+            expr: expr
+        }))
     }
 
-    fn more_infix_expressions(&mut self, left: Expr) -> Result<Expr> {
-        let mut stack = Stack::new();
-        let mut operand = left;
-        while let Some(op) = self.match_infix()? {
-            stack.extend(operand, op);
-            //println!("{}\n", stack);
-            operand = self.unary_expression()?;
+    fn jsx_id(&mut self) -> Result<JsxId> {
+        let token = self.jsx_read_tag()?;
+        match token.value {
+            TokenData::JsxName(name) => Ok(JsxId { location: Some(token.location), name: name }),
+            _ => Err(Error::UnexpectedToken(token))
         }
-        Ok(stack.finish(operand))
     }
 
-    fn match_infix(&mut self) -> Result<Option<Infix>> {
-        let token = self.read_op()?;
-        let result = token.to_binop(self.context.allow_in).map_or_else(|| {
-            token.to_logop().map(Infix::Logop)
-        }, |op| Some(Infix::Binop(op)));
-        if result.is_none() {
-            self.lexer.unread_token(token);
+    // JSXElementName ::=
+    //   JSXIdentifier
+    //   JSXIdentifier ":" JSXIdentifier
+    //   JSXIdentifier ("." JSXIdentifier)+
+    fn jsx_element_name(&mut self) -> Result<JsxElementName> {
+        let id = self.jsx_id()?;
+        if self.jsx_matches_tag(TokenData::Colon)?.is_some() {
+            let name = self.jsx_id()?;
+            return Ok(JsxElementName::Namespaced(span(&id, &name), id, name));
+        }
+        let mut result = JsxElementName::Id(id);
+        while self.jsx_matches_tag(TokenData::Dot)?.is_some() {
+            let property = self.jsx_id()?;
+            result = JsxElementName::Member(span(&result, &property), Box::new(result), property);
         }
         Ok(result)
     }
 
-    // Expression ::=
-    //   AssignmentExpression ("," AssignmentExpression)*
-    fn expression(&mut self) -> Result<Expr> {
-        let first = self.assignment_expression()?;
-        self.more_expressions(first)
+    // JSXAttribute ::=
+    //   JSXAttributeName ("=" JSXAttributeValue)?
+    //   "{" "..." AssignmentExpression "}"
+    fn jsx_attributes(&mut self) -> Result<Vec<JsxAttr>> {
+        let mut attrs = Vec::new();
+        loop {
+            let attr = match self.jsx_peek_tag()?.value {
+                TokenData::JsxName(_) => {
+                    let name = self.jsx_attribute_name()?;
+                    if self.jsx_matches_tag(TokenData::Assign)?.is_some() {
+                        let value = self.jsx_attribute_value()?;
+                        JsxAttr::Attr(span(&name, &value), name, Some(value))
+                    } else {
+                        JsxAttr::Attr(*name.tracking_ref(), name, None)
+                    }
+                }
+                TokenData::LBrace => {
+                    let start_location = Some(self.jsx_read_tag()?.location);
+                    self.expect(TokenData::Ellipsis)?;
+                    let expr = self.allow_in(true, |this| this.assignment_expression())?;
+                    let end_location = Some(self.expect(TokenData::RBrace)?.location);
+                    JsxAttr::Spread(span(&start_location, &end_location), expr)
+                }
+                _ => { break; }
+            };
+            attrs.push(attr);
+        }
+        Ok(attrs)
     }
 
-    fn more_expressions(&mut self, first: Expr) -> Result<Expr> {
-        if self.peek()?.value != TokenData::Comma {
-            return Ok(first);
+    // JSXAttributeName ::= JSXIdentifier (":" JSXIdentifier)?
+    fn jsx_attribute_name(&mut self) -> Result<JsxAttrName> {
+        let id = self.jsx_id()?;
+        if self.jsx_matches_tag(TokenData::Colon)?.is_some() {
+            let name = self.jsx_id()?;
+            return Ok(JsxAttrName::Namespaced(span(&id, &name), id, name));
         }
-        let elts = self.more_comma(first, Self::assignment_expression)?;
-        let location = self.vec_span(&elts);
-        Ok(Expr::Seq(location, elts))
+        Ok(JsxAttrName::Id(id))
+    }
+
+    // JSXAttributeValue ::=
+    //   JSXString
+    //   "{" AssignmentExpression "}"
+    //   JSXElement
+    //   JSXFragment
+    fn jsx_attribute_value(&mut self) -> Result<JsxAttrValue> {
+        let token = self.jsx_read_tag()?;
+        let start_location = Some(token.location);
+        Ok(match token.value {
+            TokenData::JsxString(value) => JsxAttrValue::String(start_location, value),
+            TokenData::LBrace => {
+                let expr = self.allow_in(true, |this| this.assignment_expression())?;
+                let end_location = Some(self.expect(TokenData::RBrace)?.location);
+                JsxAttrValue::Expr(JsxExprContainer {
+                    location: span(&start_location, &end_location),
+                    expr: Some(expr)
+                })
+            }
+            TokenData::LAngle => {
+                match self.jsx_element_or_fragment(token)? {
+                    JsxChild::Element(element) => JsxAttrValue::Element(element),
+                    JsxChild::Fragment(fragment) => JsxAttrValue::Fragment(fragment),
+                    _ => unreachable!()
+                }
+            }
+            _ => { return Err(Error::UnexpectedToken(token)); }
+        })
     }
 }
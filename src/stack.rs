@@ -3,7 +3,8 @@ use std::fmt::{Display, Formatter};
 use std::{cmp, usize};
 use joker::track::span;
 use easter::expr::Expr;
-use easter::punc::{Binop, Logop, Precedence};
+use easter::obj::PrivateKey;
+use easter::punc::{Binop, BinopTag, Logop, Precedence};
 
 #[derive(Debug)]
 pub enum Infix {
@@ -13,7 +14,16 @@ pub enum Infix {
 
 impl Infix {
     fn groups_left(&self, right: &Infix) -> bool {
-        self.precedence() >= right.precedence()
+        let (left_prec, right_prec) = (self.precedence(), right.precedence());
+        left_prec > right_prec || (left_prec == right_prec && !self.is_right_associative())
+    }
+
+    // Exponentiation is the only right-associative infix operator.
+    fn is_right_associative(&self) -> bool {
+        match *self {
+            Infix::Binop(ref op) => op.tag == BinopTag::Exp,
+            Infix::Logop(_) => false
+        }
     }
 }
 
@@ -35,9 +45,15 @@ impl Precedence for Infix {
     }
 }
 
+#[derive(Debug)]
+enum Operand {
+    Expr(Expr),
+    Private(PrivateKey) // only as the left operand of "in"
+}
+
 #[derive(Debug)]
 struct Frame {
-    left: Expr,
+    left: Operand,
     op: Infix
 }
 
@@ -49,23 +65,39 @@ impl Precedence for Frame {
 
 impl Frame {
     fn fill(self, right: Expr) -> Expr {
-        let location = span(&self.left, &right);
-        match self.op {
-            Infix::Binop(op) => Expr::Binop(location, op, Box::new(self.left), Box::new(right)),
-            Infix::Logop(op) => Expr::Logop(location, op, Box::new(self.left), Box::new(right))
+        match (self.left, self.op) {
+            (Operand::Expr(left), Infix::Binop(op)) => {
+                let location = span(&left, &right);
+                Expr::Binop(location, op, Box::new(left), Box::new(right))
+            }
+            (Operand::Expr(left), Infix::Logop(op)) => {
+                let location = span(&left, &right);
+                Expr::Logop(location, op, Box::new(left), Box::new(right))
+            }
+            (Operand::Private(key), _) => {
+                let location = span(&key, &right);
+                Expr::PrivateIn(location, key, Box::new(right))
+            }
         }
     }
 }
 
 impl Frame {
     fn width(&self) -> usize {
-        FrameExpr(&self.left).width() + 1 + self.op.to_string().len() + 1 + 2
+        let left = match self.left {
+            Operand::Expr(ref left) => FrameExpr(left).width(),
+            Operand::Private(ref key) => 1 + key.value.len()
+        };
+        left + 1 + self.op.to_string().len() + 1 + 2
     }
 }
 
 impl Display for Frame {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
-        fmt.write_fmt(format_args!("{} {} []", FrameExpr(&self.left), self.op))
+        match self.left {
+            Operand::Expr(ref left) => fmt.write_fmt(format_args!("{} {} []", FrameExpr(left), self.op)),
+            Operand::Private(ref key) => fmt.write_fmt(format_args!("#{} {} []", key.value, self.op))
+        }
     }
 }
 
@@ -129,7 +161,26 @@ impl Stack {
         while { len = self.frames.len(); len > 0 } && self.frames[len - 1].op.groups_left(&op) {
             left = self.frames.pop().unwrap().fill(left);
         }
-        self.frames.push(Frame { left: left, op: op });
+        self.frames.push(Frame { left: Operand::Expr(left), op: op });
+    }
+
+    // A private name can't be the right operand of any pending frame, so
+    // this fails (giving back the name) if one would group it to the left.
+    pub fn extend_private(&mut self, key: PrivateKey, op: Infix) -> Result<(), PrivateKey> {
+        if self.frames.last().map_or(false, |frame| frame.op.groups_left(&op)) {
+            return Err(key);
+        }
+        self.frames.push(Frame { left: Operand::Private(key), op: op });
+        Ok(())
+    }
+
+    // Fills every pending frame that binds at least as tightly as the
+    // given precedence, leaving the rest for later operators.
+    pub fn reduce(&mut self, mut right: Expr, precedence: u32) -> Expr {
+        while self.frames.last().map_or(false, |frame| frame.precedence() >= precedence) {
+            right = self.frames.pop().unwrap().fill(right);
+        }
+        right
     }
 
     pub fn finish(mut self, mut right: Expr) -> Expr {
@@ -9,6 +9,7 @@ pub trait First {
 
 pub trait Follows {
     fn follow_statement_list(&self) -> bool;
+    fn follow_assignment_expression(&self) -> bool;
     fn expression_continuation(&self) -> bool;
 }
 
@@ -75,6 +76,22 @@ impl Follows for Token {
         }
     }
 
+    // follow(AssignmentExpression) =
+    //   { ')', ']', '}', ',', ';', ':', EOF }
+    // (plus anything after an automatically inserted semicolon)
+    fn follow_assignment_expression(&self) -> bool {
+        match self.value {
+              TokenData::RParen
+            | TokenData::RBrack
+            | TokenData::RBrace
+            | TokenData::Comma
+            | TokenData::Semi
+            | TokenData::Colon
+            | TokenData::EOF => true,
+            _ => false
+        }
+    }
+
     fn expression_continuation(&self) -> bool {
         match self.value {
             // 1. Common non-continuations.
@@ -100,7 +117,9 @@ impl Follows for Token {
           | TokenData::Tilde
           | TokenData::Colon
           | TokenData::Arrow
+          | TokenData::At
           | TokenData::Number(_)
+          | TokenData::BigInt(_)
           | TokenData::String(_)
           | TokenData::RegExp(_)
           | TokenData::Identifier(_) => false,
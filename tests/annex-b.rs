@@ -0,0 +1,27 @@
+extern crate esprit;
+
+use esprit::{script, module, Options};
+
+fn strict_options() -> Options {
+    Options { annex_b: false, ..Options::default() }
+}
+
+#[test]
+fn extended_regexps() {
+    for source in ["/{/;", "/]/;", "/\\c/;", "/\\1/;", "/(?=a){2}/;", "/[\\d-a]/;", "/\\a/;"].iter() {
+        assert!(script(source).is_ok(), "{}", source);
+        assert!(module(source).is_ok(), "{}", source);
+        assert!(strict_options().script(source).is_err(), "{}", source);
+        assert!(strict_options().module(source).is_err(), "{}", source);
+    }
+}
+
+#[test]
+fn standard_regexps() {
+    for source in ["/a{2}/;", "/(a)\\1/;", "/\\cJ/;", "/\\0/;", "/\\$/;", "/[\\d]/;"].iter() {
+        assert!(script(source).is_ok(), "{}", source);
+        assert!(strict_options().script(source).is_ok(), "{}", source);
+    }
+    assert!(script("/{/u;").is_err());
+    assert!(script("/\\1/u;").is_err());
+}
@@ -0,0 +1,95 @@
+extern crate esprit;
+extern crate joker;
+
+use esprit::{script, module};
+use esprit::error::Error;
+use joker::word::Atom;
+
+#[test]
+fn async_functions() {
+    assert!(script("async function f() { await x; }").is_ok());
+    assert!(script("(async function() { await x; })").is_ok());
+    assert!(script("({ async f() { await x; } })").is_ok());
+    assert!(script("class A { async f() { await x; } }").is_ok());
+    assert!(script("async x => await x;").is_ok());
+    assert!(script("async (x, y) => { await x; };").is_ok());
+    assert!(module("export async function f() {}").is_ok());
+    assert!(module("export default async function() {}").is_ok());
+}
+
+#[test]
+fn async_identifier() {
+    assert!(script("var async; async = 1; async(x); async\nfunction f() {}").is_ok());
+    assert!(script("async (x, ...y);").is_ok());
+    assert!(script("async\n(x) => x;").is_err());
+    assert!(script("async x\n=> x;").is_err());
+}
+
+#[test]
+fn trailing_commas() {
+    assert!(script("f(a,); new F(a, b,); async(a,); async function g(a,) {} (async (a,) => a);").is_ok());
+    assert!(script("f(,);").is_err());
+    assert!(script("f(a,,);").is_err());
+    assert!(script("async (...a,) => a;").is_err());
+}
+
+#[test]
+fn await_in_async_function() {
+    match script("async function f() { var await; }") {
+        Err(Error::ContextualKeyword(_, Atom::Await)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    assert!(script("async function f(await) {}").is_err());
+    assert!(script("async function f(x = await y) {}").is_err());
+    assert!(script("async function await() {}").is_ok());
+    assert!(script("(async function await() {})").is_err());
+    assert!(script("async function f() { function g() { var await; } }").is_ok());
+}
+
+#[test]
+fn await_in_async_arrow_params() {
+    match script("async (x = await) => 1;") {
+        Err(Error::ContextualKeyword(_, Atom::Await)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    match script("async function f() { async (x = await y) => 1; }") {
+        Err(Error::AwaitInParameter(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    assert!(script("async (await) => 1;").is_err());
+    assert!(script("async ({ await }) => 1;").is_err());
+    assert!(script("async (x = async (y = await)) => 1;").is_err());
+    assert!(script("async await => 1;").is_err());
+
+    assert!(script("async (x = await);").is_ok());
+    assert!(script("async (x = function(await) {}) => 1;").is_ok());
+    assert!(script("async (x = () => await) => 1;").is_ok());
+}
+
+#[test]
+fn for_await() {
+    assert!(script("async function f() { for await (x of xs); for await (var x of xs); for await (let x of xs); }").is_ok());
+    assert!(script("async function f() { for await (const [a, b] of xs) {} for await ({ a } of xs); }").is_ok());
+    assert!(script("async () => { for await (x of xs); };").is_ok());
+    assert!(module("for await (x of xs);").is_ok());
+    assert!(script("function f() { for await (x of xs); }").is_err());
+    match script("for await (x of xs);") {
+        Err(Error::ForAwaitInScript(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    match script("async function f() { for await (x in xs); }") {
+        Err(Error::ForAwaitWithoutOf(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    assert!(script("async function f() { for await (;;); }").is_err());
+    assert!(script("async function f() { for await (let of xs); }").is_err());
+}
+
+#[test]
+fn for_await_async_of() {
+    assert!(script("async function f() { for await (async of x); }").is_ok());
+    assert!(script("async function f() { for await (async.x of y); }").is_ok());
+    assert!(script("for (async of x);").is_err());
+    assert!(script("for (async.x of y);").is_ok());
+    assert!(script("for ((async) of x);").is_ok());
+}
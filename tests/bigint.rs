@@ -0,0 +1,30 @@
+extern crate easter;
+extern crate esprit;
+
+use easter::expr::Expr;
+use easter::obj::{Prop, PropKey};
+use easter::stmt::{Stmt, StmtListItem};
+use esprit::script;
+
+#[test]
+fn bigint_literals() {
+    assert!(script("1n; 0x1fn; 0o7n; 0b1n; 1_000n;").is_ok());
+    assert!(script("1.5n;").is_err());
+    assert!(script("1e3n;").is_err());
+    assert!(script("01n;").is_err());
+}
+
+#[test]
+fn bigint_property_keys() {
+    let body = script("({1n: 1});").unwrap();
+    match body.items[0] {
+        StmtListItem::Stmt(Stmt::Expr(_, Expr::Obj(_, ref props), _)) => match props[0] {
+            Prop::Regular(_, PropKey::BigInt(_, ref lit), _) => { assert_eq!(lit.digits, "1"); }
+            ref prop => panic!("expected a bigint key, got {:?}", prop)
+        },
+        ref item => panic!("expected an object literal, got {:?}", item)
+    }
+    assert!(script("class A { 1n() {} static 0x1fn = 2; get 2n() {} }").is_ok());
+    assert!(script("var { 1n: x } = o; ({ 0n: a } = o);").is_ok());
+    assert!(script("({ 1n });").is_err());
+}
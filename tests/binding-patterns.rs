@@ -0,0 +1,55 @@
+extern crate esprit;
+
+use esprit::script;
+use esprit::error::Error;
+
+fn duplicate_param(src: &str, name: &str) -> bool {
+    match script(src) {
+        Err(Error::DuplicateParam(ref id)) => id.name.as_ref() == name,
+        _ => false
+    }
+}
+
+fn duplicate_binding(src: &str, name: &str) -> bool {
+    match script(src) {
+        Err(Error::DuplicateBinding(ref id)) => id.name.as_ref() == name,
+        _ => false
+    }
+}
+
+#[test]
+fn binding_patterns() {
+    assert!(script("var [a, , [b], ...c] = x, { d, e: { f }, g = 1, ...h } = y;").is_ok());
+    assert!(script("let [a = 1, { b }] = x; const { c: [d] } = y;").is_ok());
+    assert!(script("function f([a, b], { c }, ...[d]) {} (function ({ a } = {}) {});").is_ok());
+    assert!(script("try {} catch ([a, { b }]) {}").is_ok());
+    assert!(script("for (var [a, b] of x); for (let { a } in x); for (const [a] of x);").is_ok());
+    assert!(script("var [a];").is_err());
+    assert!(script("let { a };").is_err());
+    assert!(script("var [...a, b] = x;").is_err());
+    assert!(script("var { ...{ a } } = x;").is_err());
+}
+
+#[test]
+fn duplicate_params() {
+    assert!(duplicate_param("function f(a, [a]) {}", "a"));
+    assert!(duplicate_param("function f({ a }, { b: a }) {}", "a"));
+    assert!(duplicate_param("function f(a = 1, a) {}", "a"));
+    assert!(duplicate_param("function f(a, ...a) {}", "a"));
+    assert!(duplicate_param("({ m([a, a]) {} });", "a"));
+    assert!(duplicate_param("([a, a]) => 1;", "a"));
+    // Only a simple parameter list can bind the same name twice.
+    assert!(script("function f(a, a) {}").is_ok());
+}
+
+#[test]
+fn duplicate_bindings() {
+    assert!(duplicate_binding("let [a, a] = x;", "a"));
+    assert!(duplicate_binding("let a, { b: a } = x;", "a"));
+    assert!(duplicate_binding("const { a, b: [a] } = x;", "a"));
+    assert!(duplicate_binding("for (let [a, a] of x);", "a"));
+    assert!(duplicate_binding("for (const { a, a } in x);", "a"));
+    assert!(duplicate_binding("for (let [a, a] = x;;);", "a"));
+    assert!(duplicate_binding("try {} catch ([a, a]) {}", "a"));
+    assert!(script("var [a, a] = x; var { b, c: b } = y; for (var [d, d] of z);").is_ok());
+}
@@ -0,0 +1,178 @@
+extern crate easter;
+extern crate esprit;
+extern crate joker;
+
+use easter::stmt::Script;
+use esprit::script;
+use esprit::error::Error;
+use esprit::result::Result;
+use joker::word::Atom;
+
+fn reserved_await(result: Result<Script>) -> bool {
+    match result {
+        Err(Error::ContextualKeyword(_, Atom::Await)) => true,
+        _ => false
+    }
+}
+
+fn class_init_arguments(result: Result<Script>) -> bool {
+    match result {
+        Err(Error::ArgumentsInClassInit(_)) => true,
+        _ => false
+    }
+}
+
+#[test]
+fn class_declarations_and_expressions() {
+    assert!(script("class A {} class B extends A {} (class {}); (class C extends (f()) {});").is_ok());
+    assert!(script("class A { m() {} static n() {} get x() {} set x(v) {} *g() {} async a() {} ['k']() {} }").is_ok());
+    assert!(script("class A { ; m() {}; ; }").is_ok());
+    assert!(script("class {}").is_err());
+    assert!(script("class A extends B, C {}").is_err());
+}
+
+#[test]
+fn constructors() {
+    assert!(script("class A { constructor() {} }").is_ok());
+    assert!(script("class A { 'constructor'() {} }").is_ok());
+    assert!(script("class A { static constructor() {} constructor() {} }").is_ok());
+    assert!(script("class A extends B { constructor() { super(); } }").is_ok());
+    match script("class A { constructor() {} constructor() {} }") {
+        Err(Error::DuplicateConstructor(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    match script("class A { *constructor() {} }") {
+        Err(Error::GeneratorConstructor(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    match script("class A { async constructor() {} }") {
+        Err(Error::AsyncConstructor(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    match script("class A { get constructor() {} }") {
+        Err(Error::AccessorConstructor(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    match script("class A { set constructor(v) {} }") {
+        Err(Error::AccessorConstructor(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    match script("class A { static prototype() {} }") {
+        Err(Error::StaticPrototype(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+}
+
+#[test]
+fn super_calls() {
+    assert!(script("class A extends B { constructor() { () => super(); } }").is_ok());
+    assert!(script("class A { m() { super.m(); } }").is_ok());
+    match script("class A { constructor() { super(); } }") {
+        Err(Error::IllegalSuperCall(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    assert!(script("class A extends B { m() { super(); } }").is_err());
+    assert!(script("class A extends B { constructor() { function f() { super(); } } }").is_err());
+}
+
+#[test]
+fn strict_class_bodies() {
+    match script("class A { m() { with (x); } }") {
+        Err(Error::StrictWith(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    match script("class A { m() { var eval; } }") {
+        Err(Error::IllegalStrictBinding(_, Atom::Eval)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    assert!(script("class A { m() { 010; } }").is_err());
+    assert!(script("class A extends (function() { with (x); }) {}").is_err());
+    assert!(script("class arguments {}").is_err());
+    assert!(script("class A { [yield]() {} }").is_err());
+    assert!(script("class A { m() {} } with (x);").is_ok());
+}
+
+#[test]
+fn fields() {
+    assert!(script("class A { x; y = 1; static z = 2; 'w' = 3; [k] = 4; }").is_ok());
+    assert!(script("class A { x = this; static y = super.y; }").is_ok());
+    assert!(script("class A { x\ny }").is_ok());
+    assert!(script("class A { x y }").is_err());
+    match script("class A { constructor = 1; }") {
+        Err(Error::ConstructorField(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    match script("class A { static prototype; }") {
+        Err(Error::StaticPrototype(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+}
+
+#[test]
+fn private_names() {
+    assert!(script("class A { #x; #y = 1; static #z; #m() {} get #a() {} set #a(v) {} }").is_ok());
+    assert!(script("class A { #x; m(o) { return #x in o && o.#x; } }").is_ok());
+    assert!(script("class A { m() { this.#x; } #x; }").is_ok());
+    assert!(script("class A { #x; m() { class B { n() { this.#x; } } } }").is_ok());
+    match script("class A { m() { this.#y; } }") {
+        Err(Error::UndeclaredPrivateName(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    match script("this.#x;") {
+        Err(Error::UndeclaredPrivateName(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    match script("class A { #x; #x; }") {
+        Err(Error::DuplicatePrivateName(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    match script("class A { #constructor() {} }") {
+        Err(Error::PrivateConstructor(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    for src in &["class A { #x; m() { delete this.#x; } }",
+                 "class A { #x; m() { delete this?.#x; } }",
+                 "class A { #x; m(a) { delete a?.b.#x; } }"] {
+        match script(src) {
+            Err(Error::DeletePrivateField(_)) => { }
+            result => panic!("unexpected result: {:?}", result)
+        }
+    }
+    assert!(script("class A { #x; m(a) { delete a?.#x.y; delete this.#x?.y; } }").is_ok());
+    assert!(script("class A { get #x() {} get #x() {} }").is_err());
+    assert!(script("class A { #x; static #x() {} }").is_err());
+}
+
+#[test]
+fn static_blocks() {
+    assert!(script("class A { static { var x = this; super.y; } static {} }").is_ok());
+    assert!(script("class A { static { function f() { return arguments; } } }").is_ok());
+    assert!(script("class A { static { async () => await x; } }").is_ok());
+    assert!(script("class A { static { return; } }").is_err());
+    assert!(script("class A extends B { static { super(); } }").is_err());
+}
+
+#[test]
+fn arguments_in_class_init() {
+    assert!(class_init_arguments(script("class A { x = arguments; }")));
+    assert!(class_init_arguments(script("class A { x = () => arguments; }")));
+    assert!(class_init_arguments(script("class A { static { arguments; } }")));
+    assert!(class_init_arguments(script("class A { static { () => arguments; } }")));
+
+    assert!(script("class A { x = function() { arguments; }; }").is_ok());
+    assert!(script("class A { x = { m() { arguments; } }; }").is_ok());
+    assert!(script("class A { [arguments] = 1; }").is_ok());
+    assert!(script("function f() { class A { [arguments]() {} } }").is_ok());
+}
+
+#[test]
+fn await_in_static_blocks() {
+    assert!(reserved_await(script("class A { static { await; } }")));
+    assert!(reserved_await(script("class A { static { var await; } }")));
+    assert!(reserved_await(script("class A { static { () => await; } }")));
+    assert!(reserved_await(script("class A { static { await x; } }")));
+    assert!(reserved_await(script("class A { static { class B { [await]() {} } } }")));
+
+    assert!(script("class A { static { function f() { var await; } } }").is_ok());
+    assert!(script("class A { x = await; }").is_ok());
+}
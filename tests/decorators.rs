@@ -0,0 +1,124 @@
+extern crate easter;
+extern crate esprit;
+extern crate joker;
+
+use easter::class::{Class, ClassMember};
+use easter::decl::{Decl, Export};
+use easter::expr::Expr;
+use easter::id::Id;
+use easter::stmt::{Script, Module, ModItem, StmtListItem};
+use esprit::{Options, script};
+use esprit::error::Error;
+use esprit::result::Result;
+use joker::track::Span;
+
+fn decorated(src: &str) -> Result<Script> {
+    Options { decorators: true, ..Default::default() }.script(src)
+}
+
+fn decorated_module(src: &str) -> Result<Module> {
+    Options { decorators: true, ..Default::default() }.module(src)
+}
+
+fn class_decl(script: Script) -> Class<Id> {
+    match script.items.into_iter().next() {
+        Some(StmtListItem::Decl(Decl::Class(class))) => class,
+        item => panic!("expected a class declaration, got {:?}", item)
+    }
+}
+
+// The start and end offsets of a span.
+fn offsets(location: Option<Span>) -> (u32, u32) {
+    let Span { start, end } = location.unwrap();
+    (start.offset, end.offset)
+}
+
+#[test]
+fn decorators_are_off_by_default() {
+    match script("@a class A {}") {
+        Err(Error::LexError(joker::error::Error::IllegalChar('@'))) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    assert!(script("class A { @a m() {} }").is_err());
+}
+
+#[test]
+fn class_decorators() {
+    let class = class_decl(decorated("@a @b.c @d(1) @(e, f) class A {}").unwrap());
+    assert_eq!(offsets(class.location), (0, 32));
+    let exprs: Vec<&str> = class.decorators.iter().map(|decorator| match decorator.expr {
+        Expr::Id(_) => "id",
+        Expr::Dot(..) => "dot",
+        Expr::Call(..) => "call",
+        Expr::Seq(..) => "seq",
+        ref expr => panic!("unexpected decorator expression: {:?}", expr)
+    }).collect();
+    assert_eq!(exprs, vec!["id", "dot", "call", "seq"]);
+    let locations: Vec<(u32, u32)> = class.decorators.iter().map(|decorator| offsets(decorator.location)).collect();
+    assert_eq!(locations, vec![(0, 2), (3, 7), (8, 13), (14, 21)]);
+
+    assert!(decorated("(@a class {}); x = @a.b(c) class A {};").is_ok());
+    assert!(decorated("@a\nclass A {}").is_ok());
+    assert!(decorated("@a.b.c(d) class A {}").is_ok());
+    assert!(decorated("@(a[0]) class A {}").is_ok());
+}
+
+#[test]
+fn member_decorators() {
+    let class = class_decl(decorated("class A { @a @b m() {} }").unwrap());
+    match class.body[0] {
+        ClassMember::Decorated(location, ref decorators, ref member) => {
+            assert_eq!(offsets(location), (10, 22));
+            assert_eq!(decorators.len(), 2);
+            match **member {
+                ClassMember::Method(..) => { }
+                ref member => panic!("expected a method, got {:?}", member)
+            }
+        }
+        ref member => panic!("expected a decorated member, got {:?}", member)
+    }
+    assert!(decorated("class A { @a static x = 1; @b get y() {} @c set y(v) {} @d #p; @e #q() {} }").is_ok());
+    assert!(decorated("class A { @a static async *[x]() {} @b\nm() {} }").is_ok());
+}
+
+#[test]
+fn decorator_expressions() {
+    assert!(decorated("@a[0] class A {}").is_err());
+    assert!(decorated("@a.b(c).d class A {}").is_err());
+    assert!(decorated("@a(b)(c) class A {}").is_err());
+    assert!(decorated("@1 class A {}").is_err());
+    assert!(decorated("@ class A {}").is_err());
+}
+
+#[test]
+fn decorator_placement() {
+    assert!(decorated("@a function f() {}").is_err());
+    assert!(decorated("@a var x;").is_err());
+    assert!(decorated("@a;").is_err());
+    assert!(decorated("({ @a m() {} });").is_err());
+    assert!(decorated("class A { @a; }").is_err());
+    assert!(decorated("class A { @a static {} }").is_err());
+    match decorated("class A { @a constructor() {} }") {
+        Err(Error::DecoratedConstructor(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+}
+
+#[test]
+fn exported_class_decorators() {
+    for src in &["export @a class A {}", "@a export class A {}"] {
+        match decorated_module(src).unwrap().items.remove(0) {
+            ModItem::Export(Export::Decl(_, Decl::Class(ref class))) => { assert_eq!(class.decorators.len(), 1); }
+            item => panic!("expected an exported class, got {:?}", item)
+        }
+    }
+    for src in &["export default @a class {}", "@a export default class {}"] {
+        match decorated_module(src).unwrap().items.remove(0) {
+            ModItem::Export(Export::DefaultClass(_, ref class)) => { assert_eq!(class.decorators.len(), 1); }
+            item => panic!("expected a default class export, got {:?}", item)
+        }
+    }
+    assert!(decorated_module("@a export @b class A {}").is_err());
+    assert!(decorated_module("@a export var x;").is_err());
+    assert!(decorated_module("@a export { x };").is_err());
+}
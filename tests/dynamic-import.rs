@@ -0,0 +1,66 @@
+extern crate easter;
+extern crate esprit;
+extern crate joker;
+
+use easter::expr::Expr;
+use easter::stmt::{ModItem, Stmt, StmtListItem};
+use esprit::{script, module};
+use esprit::error::Error;
+use joker::track::Span;
+
+fn expr_stmt(item: ModItem) -> Expr {
+    match item {
+        ModItem::StmtListItem(StmtListItem::Stmt(Stmt::Expr(_, expr, _))) => expr,
+        item => panic!("expected an expression statement, got {:?}", item)
+    }
+}
+
+// The start and end offsets of a span.
+fn offsets(location: Option<Span>) -> (u32, u32) {
+    let Span { start, end } = location.unwrap();
+    (start.offset, end.offset)
+}
+
+#[test]
+fn import_meta() {
+    match expr_stmt(module("import.meta;").unwrap().items.remove(0)) {
+        Expr::ImportMeta(location) => { assert_eq!(offsets(location), (0, 11)); }
+        expr => panic!("expected import.meta, got {:?}", expr)
+    }
+    assert!(module("import.meta.url; function f() { return import . meta; } new import.meta.C();").is_ok());
+    assert!(module("import.met;").is_err());
+    assert!(module("import;").is_err());
+    assert!(module("import.meta = 1;").is_err());
+}
+
+#[test]
+fn import_meta_outside_module() {
+    match script("import.meta;") {
+        Err(Error::ImportMetaInScript(location)) => { assert_eq!(offsets(location), (0, 11)); }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    match script("function f() { return (import.meta).url; }") {
+        Err(Error::ImportMetaInScript(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+}
+
+#[test]
+fn dynamic_import() {
+    match expr_stmt(module("import(\"m\");").unwrap().items.remove(0)) {
+        Expr::Import(location, ref arg) => {
+            assert_eq!(offsets(location), (0, 11));
+            match **arg {
+                Expr::String(_, ref lit) => { assert_eq!(lit.value, "m"); }
+                ref arg => panic!("expected a string, got {:?}", arg)
+            }
+        }
+        expr => panic!("expected a dynamic import, got {:?}", expr)
+    }
+    assert!(script("import(a).then(f); x = import(a + b); typeof import(a);").is_ok());
+    assert!(script("new (import(a))();").is_ok());
+    assert!(script("import();").is_err());
+    assert!(script("import(...a);").is_err());
+    assert!(script("new import(a);").is_err());
+    assert!(module("new import(a);").is_err());
+}
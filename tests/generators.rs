@@ -0,0 +1,55 @@
+extern crate esprit;
+extern crate joker;
+
+use esprit::script;
+use esprit::error::Error;
+use joker::word::Atom;
+
+fn yield_in_parameter(src: &str) -> bool {
+    match script(src) {
+        Err(Error::YieldInParameter(_)) => true,
+        _ => false
+    }
+}
+
+fn reserved_yield(src: &str) -> bool {
+    match script(src) {
+        Err(Error::ContextualKeyword(_, Atom::Yield)) => true,
+        _ => false
+    }
+}
+
+#[test]
+fn yield_expressions() {
+    assert!(script("function* g() { yield; yield x; yield* xs; var y = yield\nx; }").is_ok());
+    assert!(script("function* g() { (yield, 1); (a = yield); x => yield; }").is_ok());
+    assert!(script("function g() { var yield; yield = 1; }").is_ok());
+    assert!(script("function* g() { var yield; }").is_err());
+}
+
+#[test]
+fn yield_in_generator_params() {
+    assert!(yield_in_parameter("function* g(a = yield) {}"));
+    assert!(yield_in_parameter("function* g() { function* h(a = yield) {} }"));
+}
+
+#[test]
+fn yield_in_arrow_params() {
+    assert!(yield_in_parameter("function* g() { (a = yield) => 1; }"));
+    assert!(yield_in_parameter("function* g() { (a, b = yield x) => 1; }"));
+    assert!(yield_in_parameter("function* g() { ({ a = yield }) => 1; }"));
+    assert!(yield_in_parameter("function* g() { ([a = yield]) => 1; }"));
+    assert!(yield_in_parameter("function* g() { async (a = yield) => 1; }"));
+    assert!(script("function* g() { (a) => { (yield); }; }").is_ok());
+    assert!(script("function* g() { async (a = yield); }").is_ok());
+}
+
+#[test]
+fn generator_expression_names() {
+    assert!(reserved_yield("var g = function* yield() {};"));
+    assert!(reserved_yield("function* g() { (function* yield() {}); }"));
+    assert!(reserved_yield("var g = async function* yield() {};"));
+    assert!(script("var g = function yield() {};").is_ok());
+    assert!(script("function* yield() {}").is_ok());
+    assert!(script("function* g() { (function yield() {}); (async function yield() {}); }").is_ok());
+}
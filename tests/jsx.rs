@@ -0,0 +1,123 @@
+#![cfg(feature = "jsx")]
+
+extern crate easter;
+extern crate esprit;
+extern crate joker;
+
+use easter::expr::Expr;
+use easter::jsx::{JsxChild, JsxElementName};
+use easter::stmt::{Script, Stmt, StmtListItem};
+use esprit::{Options, script};
+use esprit::error::Error;
+use esprit::result::Result;
+use joker::track::Span;
+
+fn jsx(src: &str) -> Result<Script> {
+    Options { jsx: true, ..Default::default() }.script(src)
+}
+
+fn jsx_expr(src: &str) -> Expr {
+    match jsx(src).unwrap().items.into_iter().next() {
+        Some(StmtListItem::Stmt(Stmt::Expr(_, expr, _))) => expr,
+        item => panic!("expected an expression statement, got {:?}", item)
+    }
+}
+
+// The start and end offsets of a span.
+fn offsets(location: Option<Span>) -> (u32, u32) {
+    let Span { start, end } = location.unwrap();
+    (start.offset, end.offset)
+}
+
+#[test]
+fn jsx_is_off_by_default() {
+    assert!(script("<div></div>;").is_err());
+    assert!(script("x < y; x<y>z;").is_ok());
+}
+
+#[test]
+fn elements() {
+    assert!(jsx("<div></div>; <br/>; <a.b.c></a.b.c>; <ns:a></ns:a>; <a-b></a-b>;").is_ok());
+    assert!(jsx("<a b=\"c\" d={1} {...e} f>text {x} <br/></a>;").is_ok());
+    assert!(jsx("<a b=<c/> d=<></>/>;").is_ok());
+    assert!(jsx("<a>{/* comment */}{}</a>;").is_ok());
+    assert!(jsx("x < y; x<y>z;").is_ok());
+    assert!(jsx("<a>{</a>;").is_err());
+    assert!(jsx("<a b=1/>;").is_err());
+}
+
+#[test]
+fn element_names() {
+    match jsx_expr("<a.b.c/>;") {
+        Expr::JsxElement(element) => match element.opening.name {
+            JsxElementName::Member(_, ref obj, ref prop) => {
+                assert_eq!(prop.name, "c");
+                match **obj {
+                    JsxElementName::Member(_, _, ref prop) => { assert_eq!(prop.name, "b"); }
+                    ref name => panic!("expected a member name, got {:?}", name)
+                }
+            }
+            ref name => panic!("expected a member name, got {:?}", name)
+        },
+        expr => panic!("expected an element, got {:?}", expr)
+    }
+    assert!(jsx("<a:b></a:b>; <a.b></a.b>;").is_ok());
+    assert!(jsx("<a:b></a:c>;").is_err());
+    assert!(jsx("<a.b></a>;").is_err());
+    assert!(jsx("<a.b:c/>;").is_err());
+}
+
+#[test]
+fn text_and_entities() {
+    match jsx_expr("<a>x &amp; y</a>;") {
+        Expr::JsxElement(element) => match element.children[0] {
+            JsxChild::Text(_, ref text) => { assert_eq!(text.value, "x & y"); }
+            ref child => panic!("expected text, got {:?}", child)
+        },
+        expr => panic!("expected an element, got {:?}", expr)
+    }
+}
+
+#[test]
+fn fragments() {
+    match jsx_expr("<><a/>{x}</>;") {
+        Expr::JsxFragment(fragment) => {
+            assert_eq!(fragment.children.len(), 2);
+            assert_eq!(offsets(fragment.opening), (0, 2));
+            assert_eq!(offsets(fragment.closing), (9, 12));
+        }
+        expr => panic!("expected a fragment, got {:?}", expr)
+    }
+}
+
+#[test]
+fn mismatched_tags() {
+    match jsx("<a>x</b>;") {
+        Err(Error::MismatchedJsxTag(opening, closing)) => {
+            assert_eq!(offsets(opening), (0, 3));
+            assert_eq!(offsets(closing), (4, 8));
+        }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    match jsx("<a.b>\n</a.c>;") {
+        Err(Error::MismatchedJsxTag(opening, closing)) => {
+            assert_eq!(offsets(opening), (0, 5));
+            assert_eq!(offsets(closing), (6, 12));
+        }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    match jsx("<a></>;") {
+        Err(Error::MismatchedJsxTag(opening, closing)) => {
+            assert_eq!(offsets(opening), (0, 3));
+            assert_eq!(offsets(closing), (3, 6));
+        }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    match jsx("<></a>;") {
+        Err(Error::MismatchedJsxTag(opening, closing)) => {
+            assert_eq!(offsets(opening), (0, 2));
+            assert_eq!(offsets(closing), (2, 6));
+        }
+        result => panic!("unexpected result: {:?}", result)
+    }
+}
@@ -0,0 +1,201 @@
+extern crate easter;
+extern crate esprit;
+extern crate joker;
+
+use easter::decl::{Import, Export};
+use easter::stmt::{Module, ModItem};
+use esprit::{script, module};
+use esprit::error::Error;
+use esprit::result::Result;
+use joker::track::Span;
+use joker::word::Atom;
+
+fn first_import(module: Module) -> Import {
+    match module.items.into_iter().next() {
+        Some(ModItem::Import(import)) => import,
+        item => panic!("expected an import, got {:?}", item)
+    }
+}
+
+fn exports(module: Module) -> Vec<Export> {
+    module.items.into_iter().filter_map(|item| match item {
+        ModItem::Export(export) => Some(export),
+        _ => None
+    }).collect()
+}
+
+// The start and end offsets of a span.
+fn offsets(location: Option<Span>) -> (u32, u32) {
+    let Span { start, end } = location.unwrap();
+    (start.offset, end.offset)
+}
+
+fn duplicate_export(src: &str, name: &str) -> bool {
+    match module(src) {
+        Err(Error::DuplicateExport(ref key)) => key.value == name,
+        _ => false
+    }
+}
+
+fn duplicate_default(src: &str) -> bool {
+    match module(src) {
+        Err(Error::DuplicateDefaultExport(_)) => true,
+        _ => false
+    }
+}
+
+fn reserved_await(result: Result<Module>) -> bool {
+    match result {
+        Err(Error::ContextualKeyword(_, Atom::Await)) => true,
+        _ => false
+    }
+}
+
+#[test]
+fn import_declarations() {
+    match first_import(module("import \"m\";").unwrap()) {
+        Import::ForEffect(_, ref source) => { assert_eq!(source.value, "m"); }
+        import => panic!("expected an import for effect, got {:?}", import)
+    }
+    match first_import(module("import a from \"m\";").unwrap()) {
+        Import::Default(_, ref id, _) => { assert_eq!(id.name.as_ref(), "a"); }
+        import => panic!("expected a default import, got {:?}", import)
+    }
+    match first_import(module("import a, * as ns from \"m\";").unwrap()) {
+        Import::Namespace(_, Some(ref default), ref ns, _) => {
+            assert_eq!(default.name.as_ref(), "a");
+            assert_eq!(ns.name.as_ref(), "ns");
+        }
+        import => panic!("expected a namespace import, got {:?}", import)
+    }
+    match first_import(module("import { b as c, d, default as e, if as f } from \"m\";").unwrap()) {
+        Import::Named(_, None, ref specs, _) => {
+            let names: Vec<(&str, &str)> = specs.iter()
+                .map(|spec| (&spec.imported.value[..], spec.local.name.as_ref()))
+                .collect();
+            assert_eq!(names, vec![("b", "c"), ("d", "d"), ("default", "e"), ("if", "f")]);
+        }
+        import => panic!("expected a named import, got {:?}", import)
+    }
+    assert!(module("import a, { b } from \"m\"; import {} from \"m\"; import { b, } from \"m\";").is_ok());
+}
+
+#[test]
+fn import_bindings() {
+    assert!(module("import { if } from \"m\";").is_err());
+    assert!(module("import { default } from \"m\";").is_err());
+    assert!(module("import { a as 1 } from \"m\";").is_err());
+    assert!(module("import { a as b.c } from \"m\";").is_err());
+    assert!(module("import class from \"m\";").is_err());
+    assert!(module("import * as yield from \"m\";").is_err());
+    assert!(reserved_await(module("import { await } from \"m\";")));
+    assert!(reserved_await(module("import await from \"m\";")));
+    match module("import eval from \"m\";") {
+        Err(Error::IllegalStrictBinding(_, Atom::Eval)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    assert!(module("import * from \"m\";").is_err());
+    assert!(module("import a from m;").is_err());
+}
+
+#[test]
+fn import_at_top_level() {
+    assert!(module("{ import a from \"m\"; }").is_err());
+    assert!(module("function f() { import a from \"m\"; }").is_err());
+    assert!(module("if (x) import a from \"m\";").is_err());
+}
+
+#[test]
+fn import_in_script() {
+    match script("import a from \"m\";") {
+        Err(Error::ImportInScript(Import::Default(_, _, _))) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    match script("import \"m\";") {
+        Err(Error::ImportInScript(Import::ForEffect(_, _))) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+}
+
+#[test]
+fn export_declarations() {
+    let shapes: Vec<&str> = exports(module("var a, b; export var c; export let d; export const e = 1; \
+                                            export function f() {} export class G {} \
+                                            export { a, b as h }; export { x as y } from \"m\"; \
+                                            export * from \"n\"; export default 1;").unwrap())
+        .iter()
+        .map(|export| match *export {
+            Export::Var(..) => "var",
+            Export::Decl(..) => "decl",
+            Export::DefaultExpr(..) => "default expr",
+            Export::DefaultFun(..) => "default fun",
+            Export::DefaultClass(..) => "default class",
+            Export::Named(..) => "named",
+            Export::NamedFrom(..) => "named from",
+            Export::All(..) => "all"
+        })
+        .collect();
+    assert_eq!(shapes, vec!["var", "decl", "decl", "decl", "decl", "named", "named from", "all", "default expr"]);
+    match exports(module("export default function () {}").unwrap()).remove(0) {
+        Export::DefaultFun(_, ref fun) => { assert!(fun.id.is_none()); }
+        export => panic!("expected a default function, got {:?}", export)
+    }
+    match exports(module("export default class A {}").unwrap()).remove(0) {
+        Export::DefaultClass(_, ref class) => { assert!(class.id.is_some()); }
+        export => panic!("expected a default class, got {:?}", export)
+    }
+    match exports(module("export default (function () {});").unwrap()).remove(0) {
+        Export::DefaultExpr(..) => { }
+        export => panic!("expected a default expression, got {:?}", export)
+    }
+    assert!(module("export default async function () {} export { if } from \"m\";").is_ok());
+    assert!(module("export { if };").is_err());
+    assert!(module("export var;").is_err());
+    assert!(module("export default var a;").is_err());
+    assert!(module("export * from m;").is_err());
+    assert!(module("{ export var a; }").is_err());
+}
+
+#[test]
+fn export_locations() {
+    let mut list = exports(module("export default 1; export { a as b };\nvar a;").unwrap());
+    match list.remove(0) {
+        Export::DefaultExpr(location, _, _) => { assert_eq!(offsets(location), (0, 17)); }
+        export => panic!("expected a default expression, got {:?}", export)
+    }
+    match list.remove(0) {
+        Export::Named(location, ref specs, _) => {
+            assert_eq!(offsets(location), (18, 36));
+            assert_eq!(offsets(specs[0].location), (27, 33));
+            assert_eq!(specs[0].local.value, "a");
+            assert_eq!(specs[0].exported.value, "b");
+        }
+        export => panic!("expected a named export, got {:?}", export)
+    }
+}
+
+#[test]
+fn duplicate_exports() {
+    assert!(duplicate_export("export var a; export { b as a };", "a"));
+    assert!(duplicate_export("export var a, b; export { b as a };", "a"));
+    assert!(duplicate_export("export { a, a };", "a"));
+    assert!(duplicate_export("export function a() {} export class a {}", "a"));
+    assert!(duplicate_export("export { a } from \"m\"; export let a;", "a"));
+    assert!(module("export { a, a as b }; export * from \"m\"; export * from \"n\";").is_ok());
+}
+
+#[test]
+fn duplicate_default_exports() {
+    assert!(duplicate_default("export default 1; export default 2;"));
+    assert!(duplicate_default("export default function () {} export default class {}"));
+    assert!(duplicate_default("export default 1; export { a as default };"));
+    assert!(duplicate_default("export { a as default } from \"m\"; export default 1;"));
+}
+
+#[test]
+fn export_in_script() {
+    match script("export var a;") {
+        Err(Error::ExportInScript(Export::Var(..))) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+}
@@ -17,8 +17,8 @@ extern crate unjson;
 use easter::expr::Expr;
 use easter::patt::{AssignTarget, Patt};
 use easter::stmt::{Stmt, StmtListItem};
-use esprit::script;
-use estree::IntoScript;
+use esprit::{Options, script};
+use estree::{IntoScript, IntoModule};
 use glob::glob;
 use joker::track::Untrack;
 use serde_json::value::Value;
@@ -26,6 +26,7 @@ use std::fs::{File, read_dir};
 use std::io::Read;
 use std::path::Path;
 use std::env;
+use std::fmt::Debug;
 use test::{TestDesc, TestDescAndFn, TestName, TestFn, test_main};
 use test::ShouldPanic::No;
 use unjson::{ExtractField, Unjson};
@@ -54,6 +55,21 @@ fn as_ref_test(tests: &mut Vec<TestDescAndFn>) {
     });
 }
 
+fn check<T: Debug + PartialEq + Untrack>(actual: esprit::result::Result<T>, expected: Result<T, Value>) {
+    match (actual, expected) {
+        (Ok(mut actual_ast), expected) => {
+            actual_ast.untrack();
+            assert!(Ok(&actual_ast) == expected.as_ref(), "unit test got wrong result\n\
+            expected: {:#?}\n\
+            actual AST: {:#?}", expected, actual_ast);
+        }
+        (Err(actual_err), Ok(_)) => {
+            panic!("unit test failed to parse:\n{:#?}", actual_err);
+        }
+        (Err(_), Err(_)) => {}
+    }
+}
+
 fn unit_tests(target: &mut Vec<TestDescAndFn>) {
     let root = Path::new(env!("CARGO_MANIFEST_DIR"));
 
@@ -84,44 +100,46 @@ fn unit_tests(target: &mut Vec<TestDescAndFn>) {
                 tree_path.with_file_name(source_file_name)
             };
             if source_path.exists() {
-                let ignore = {
-                    let local_test_path = source_path.strip_prefix(&fixtures).unwrap().with_extension("");
-                    testignore.iter().any(|ignore| ignore.matches_path(&local_test_path))
-                };
-                Some((tree_path, source_path, ignore))
+                let local_test_path = source_path.strip_prefix(&fixtures).unwrap().with_extension("");
+                // The JSX tests can only run with the jsx feature.
+                let jsx = local_test_path.starts_with("JSX");
+                let ignore = (jsx && !cfg!(feature = "jsx")) ||
+                             testignore.iter().any(|ignore| ignore.matches_path(&local_test_path));
+                let options = Options { jsx: jsx, ..Default::default() };
+                Some((tree_path, source_path, ignore, options))
             } else {
                 None
             }
         });
 
-    for (tree_path, source_path, ignore) in files {
+    for (tree_path, source_path, ignore, options) in files {
         add_test(target, source_path.strip_prefix(&root).unwrap().to_str().unwrap().to_string(), ignore, move || {
             let v: Value = serde_json::de::from_reader(File::open(tree_path).unwrap()).unwrap();
             let mut obj = v.into_object().unwrap();
-            let expected = match obj.extract_array("errors") {
-                Ok(errors) => {
-                    Err(errors[0].as_object().unwrap()["message"].clone())
-                }
-                Err(unjson::error::Error::MissingField(_)) => {
-                    Ok(obj.into_script().map_err(|err| {
-                        format!("failed to deserialize script: {}", err)
-                    }).unwrap())
-                }
+            let is_module = obj.get("sourceType").and_then(|ty| ty.as_str()) == Some("module");
+            let errors = match obj.extract_array("errors") {
+                Ok(errors) => Some(errors[0].as_object().unwrap()["message"].clone()),
+                Err(unjson::error::Error::MissingField(_)) => None,
                 Err(err) => panic!(err)
             };
             let mut source = String::new();
             File::open(source_path).unwrap().read_to_string(&mut source).unwrap();
-            match (script(&source[..]), expected) {
-                (Ok(mut actual_ast), expected) => {
-                    actual_ast.untrack();
-                    assert!(Ok(&actual_ast) == expected.as_ref(), "unit test got wrong result\n\
-                    expected: {:#?}\n\
-                    actual AST: {:#?}", expected, actual_ast);
-                }
-                (Err(actual_err), Ok(_)) => {
-                    panic!("unit test failed to parse:\n{:#?}", actual_err);
-                }
-                (Err(_), Err(_)) => {}
+            if is_module {
+                let expected = match errors {
+                    Some(message) => Err(message),
+                    None => Ok(obj.into_module().map_err(|err| {
+                        format!("failed to deserialize module: {}", err)
+                    }).unwrap())
+                };
+                check(options.module(&source[..]), expected);
+            } else {
+                let expected = match errors {
+                    Some(message) => Err(message),
+                    None => Ok(obj.into_script().map_err(|err| {
+                        format!("failed to deserialize script: {}", err)
+                    }).unwrap())
+                };
+                check(options.script(&source[..]), expected);
             }
         });
     }
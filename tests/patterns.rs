@@ -0,0 +1,81 @@
+extern crate easter;
+extern crate esprit;
+
+use easter::cover;
+use esprit::script;
+use esprit::error::Error;
+
+fn parenthesized_lhs(src: &str) -> bool {
+    match script(src) {
+        Err(Error::InvalidLHS(_, cover::Error::Parenthesized(_))) => true,
+        _ => false
+    }
+}
+
+#[test]
+fn assignment_patterns() {
+    assert!(script("[a, b] = o;").is_ok());
+    assert!(script("[a = 1, [b], ...c] = o;").is_ok());
+    assert!(script("({ a, b: c = 1, d: { e } } = o);").is_ok());
+    assert!(script("[a.b, c[0]] = o;").is_ok());
+    assert!(script("for ([a, b] of o);").is_ok());
+    assert!(script("[a + 1] = o;").is_err());
+    assert!(script("({ a: 1 } = o);").is_err());
+}
+
+#[test]
+fn parenthesized_assignment_targets() {
+    assert!(script("(a) = 1;").is_ok());
+    assert!(script("[(a)] = o;").is_ok());
+    assert!(script("[((a))] = o;").is_ok());
+    assert!(script("[(a.b)] = o;").is_ok());
+    assert!(script("[(a) = 1] = o;").is_ok());
+    assert!(script("[...(a)] = o;").is_ok());
+    assert!(script("({ a: (b) } = o);").is_ok());
+    assert!(script("({ a: (b) = 1 } = o);").is_ok());
+    assert!(script("({ ...(a) } = o);").is_ok());
+    assert!(script("([(a = 1)], [b] = o);").is_ok());
+    assert!(script("for ([(a = 1)];;); [b] = o;").is_ok());
+}
+
+#[test]
+fn parenthesized_assignment_patterns() {
+    assert!(parenthesized_lhs("({ a }) = o;"));
+    assert!(parenthesized_lhs("([a]) = o;"));
+    assert!(parenthesized_lhs("[(a = 1)] = o;"));
+    assert!(parenthesized_lhs("[([a])] = o;"));
+    assert!(parenthesized_lhs("[...([a])] = o;"));
+    assert!(parenthesized_lhs("({ a: (b = 1) } = o);"));
+    assert!(parenthesized_lhs("({ a: ({ b }) } = o);"));
+    assert!(parenthesized_lhs("for (({ a }) of o);"));
+    assert!(parenthesized_lhs("for ([(a = 1)] in o);"));
+}
+
+fn parenthesized_param(src: &str) -> bool {
+    match script(src) {
+        Err(Error::InvalidParam(_, cover::Error::Parenthesized(_))) => true,
+        _ => false
+    }
+}
+
+#[test]
+fn arrow_params() {
+    assert!(script("(a, [b], { c }, d = 1, ...e) => 1;").is_ok());
+    assert!(script("(a = (b)) => 1;").is_ok());
+    assert!(script("(a = [(b)]) => 1;").is_ok());
+    assert!(script("async (a = (b)) => 1;").is_ok());
+    assert!(script("((a), (b));").is_ok());
+    assert!(script("async ((a), (b));").is_ok());
+}
+
+#[test]
+fn parenthesized_arrow_params() {
+    assert!(parenthesized_param("((a)) => 1;"));
+    assert!(parenthesized_param("(a, (b)) => 1;"));
+    assert!(parenthesized_param("([(a)]) => 1;"));
+    assert!(parenthesized_param("({ a: (b) }) => 1;"));
+    assert!(parenthesized_param("({ ...(a) }) => 1;"));
+    assert!(parenthesized_param("([(a)] = o) => 1;"));
+    assert!(parenthesized_param("async ((a)) => 1;"));
+    assert!(parenthesized_param("async (...(a)) => 1;"));
+}
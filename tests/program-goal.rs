@@ -0,0 +1,103 @@
+extern crate easter;
+extern crate esprit;
+extern crate joker;
+
+use easter::stmt::Module;
+use esprit::{script, module, program, Program};
+use esprit::error::Error;
+use esprit::result::Result;
+use joker::word::Atom;
+
+fn goal(src: &str) -> &'static str {
+    match program(src) {
+        Ok(Program::Ambiguous(_, _)) => "ambiguous",
+        Ok(Program::Script(_, _)) => "script",
+        Ok(Program::Module(_)) => "module",
+        Err(err) => panic!("failed to parse {:?}: {:?}", src, err)
+    }
+}
+
+fn reserved_await(result: Result<Module>) -> bool {
+    match result {
+        Err(Error::ContextualKeyword(_, Atom::Await)) => true,
+        _ => false
+    }
+}
+
+#[test]
+fn module_top_level_await() {
+    assert!(module("await x;").is_ok());
+    assert!(module("await /x/;").is_ok());
+    assert!(module("await (x);").is_ok());
+    assert!(module("{ await x; }").is_ok());
+    assert!(module("for await (x of xs);").is_ok());
+    assert!(module("class C extends (await x) {}").is_ok());
+    assert!(module("class C { [await x]() {} }").is_ok());
+    assert!(module("async function f() { await x; }").is_ok());
+}
+
+#[test]
+fn module_reserved_await() {
+    assert!(reserved_await(module("var await;")));
+    assert!(reserved_await(module("function f() { await x; }")));
+    assert!(module("() => await x;").is_err());
+    assert!(module("class C { x = await y; }").is_err());
+    assert!(module("(x = await y) => x;").is_err());
+}
+
+#[test]
+fn script_await_identifier() {
+    assert!(script("var await; await(x); await / 2;").is_ok());
+    assert!(script("await x;").is_err());
+    assert!(script("for await (x of xs);").is_err());
+}
+
+#[test]
+fn program_goal() {
+    assert_eq!(goal("x = 1;"), "ambiguous");
+    assert_eq!(goal("async function f() { await x; }"), "ambiguous");
+
+    assert_eq!(goal("await x;"), "module");
+    assert_eq!(goal("f(await x);"), "module");
+    assert_eq!(goal("{ await new Promise(f); }"), "module");
+    assert_eq!(goal("import.meta; await(x);"), "module");
+
+    assert_eq!(goal("var await;"), "script");
+    assert_eq!(goal("await(x);"), "script");
+    assert_eq!(goal("await\nx;"), "script");
+    assert_eq!(goal("await /x/g;"), "script");
+    assert_eq!(goal("for (await of xs);"), "script");
+    assert_eq!(goal("function f() { var await; }"), "script");
+}
+
+#[test]
+fn program_goal_ambiguous_await() {
+    // Each of these reads as an identifier in a script and as an operator in
+    // a module, so the rest of the program decides.
+    for src in &["await(x);", "await [x];", "await /x/g;", "await +x;", "await `x`;"] {
+        assert_eq!(goal(src), "script");
+        for evidence in &["export {};", "import.meta;", "await y;"] {
+            let src = format!("{} {}", src, evidence);
+            assert_eq!(program(&src).unwrap(), Program::Module(module(&src).unwrap()));
+        }
+    }
+    assert_eq!(goal("await(x); var await;"), "script");
+    assert!(program("await(x); var await; export {};").is_err());
+}
+
+#[test]
+fn program_goal_conflicts() {
+    assert!(program("var await; await x;").is_err());
+    assert!(program("await x; var await;").is_err());
+    assert!(program("var await; export {};").is_err());
+    assert!(program("(x = await y) => x;").is_err());
+}
+
+#[test]
+fn script_program_as_module() {
+    match program("await(x);").unwrap().module() {
+        Err(Error::ContextualKeyword(_, Atom::Await)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    assert!(program("await(x);").unwrap().script().is_ok());
+}
@@ -0,0 +1,67 @@
+extern crate easter;
+extern crate esprit;
+
+use easter::cover;
+use easter::stmt::Script;
+use esprit::script;
+use esprit::error::Error;
+use esprit::result::Result;
+
+fn trailing_comma(result: Result<Script>) -> bool {
+    match result {
+        Err(Error::InvalidLHS(_, cover::Error::RestTrailingComma(_))) => true,
+        Err(Error::InvalidParam(_, cover::Error::RestTrailingComma(_))) => true,
+        _ => false
+    }
+}
+
+#[test]
+fn object_spread() {
+    assert!(script("({ ...a });").is_ok());
+    assert!(script("({ a, ...b, c: 1, ...d });").is_ok());
+    assert!(script("({ ...a, });").is_ok());
+    assert!(script("({ ...a.b, ...f() });").is_ok());
+    assert!(script("[...a, ];").is_ok());
+}
+
+#[test]
+fn object_rest() {
+    assert!(script("var { a, ...b } = o;").is_ok());
+    assert!(script("({ a, ...b } = o);").is_ok());
+    assert!(script("({ ...a.b } = o);").is_ok());
+    assert!(script("({ a, ...b }) => 1;").is_ok());
+    assert!(script("function f({ ...a }) {}").is_ok());
+    assert!(script("for (var { ...a } of o);").is_ok());
+
+    assert!(script("var { ...a, b } = o;").is_err());
+    assert!(script("var { ...{ a } } = o;").is_err());
+    assert!(script("({ ...a, b } = o);").is_err());
+    assert!(script("({ ...{ a } } = o);").is_err());
+    assert!(script("({ ...[a] } = o);").is_err());
+    assert!(script("({ ...a.b }) => 1;").is_err());
+}
+
+#[test]
+fn rest_trailing_comma() {
+    assert!(script("var { ...a, } = o;").is_err());
+    assert!(script("var [...a, ] = o;").is_err());
+    assert!(trailing_comma(script("({ ...a, } = o);")));
+    assert!(trailing_comma(script("[...a, ] = o;")));
+    assert!(trailing_comma(script("[[...a, ]] = o;")));
+    assert!(trailing_comma(script("for ([...a, ] of o);")));
+    assert!(trailing_comma(script("({ ...a, }) => 1;")));
+    assert!(trailing_comma(script("([...a, ]) => 1;")));
+    assert!(trailing_comma(script("async ([...a, ]) => 1;")));
+    assert!(script("(...a, ) => 1;").is_err());
+}
+
+#[test]
+fn rest_param_with_use_strict() {
+    match script("function f(...a) { \"use strict\" }") {
+        Err(Error::NonSimpleParamWithUseStrict(_)) => { }
+        result => panic!("unexpected result: {:?}", result)
+    }
+    assert!(script("({ m(...a) { \"use strict\" } });").is_err());
+    assert!(script("(...a) => { \"use strict\" };").is_err());
+    assert!(script("function f(a) { \"use strict\" }").is_ok());
+}
@@ -0,0 +1,148 @@
+extern crate easter;
+extern crate esprit;
+extern crate joker;
+
+use easter::expr::Expr;
+use easter::stmt::{Script, StmtListItem, Stmt};
+use easter::strip::StripTypes;
+use easter::types::Type;
+use esprit::{Options, script};
+use esprit::result::Result;
+use joker::track::Untrack;
+
+fn typed(src: &str) -> Result<Script> {
+    Options { types: true, ..Default::default() }.script(src)
+}
+
+// Parses src in types mode, strips the types, and checks that the result is
+// the same tree as the plain parse of stripped.
+fn strips_to(src: &str, stripped: &str) {
+    let mut actual = typed(src).unwrap().strip_types();
+    let mut expected = script(stripped).unwrap();
+    actual.untrack();
+    expected.untrack();
+    assert_eq!(actual, expected, "stripping {:?}", src);
+}
+
+fn expr_stmt(script: Script) -> Expr {
+    match script.items.into_iter().next() {
+        Some(StmtListItem::Stmt(Stmt::Expr(_, expr, _))) => expr,
+        item => panic!("expected an expression statement, got {:?}", item)
+    }
+}
+
+#[test]
+fn types_are_off_by_default() {
+    assert!(script("var x: number;").is_err());
+    assert!(script("function f(a?: number) {}").is_err());
+    assert!(script("x as const;").is_err());
+    assert!(script("class A<T> {}").is_err());
+}
+
+#[test]
+fn annotations() {
+    strips_to("var x: number = 1, y: string[];", "var x = 1, y;");
+    strips_to("function f(a: A, ...b: B[]): void {}", "function f(a, ...b) {}");
+    strips_to("(a: number, b = 1) => a", "(a, b = 1) => a");
+    strips_to("(a: number = 1): number => a", "(a = 1) => a");
+    assert!(typed("for (var x: number in o);").is_err());
+}
+
+#[test]
+fn optional_parameters() {
+    strips_to("function f(a?: number, b?) {}", "function f(a, b) {}");
+    strips_to("(a?: number) => a", "(a) => a");
+    strips_to("(a, b?) => a", "(a, b) => a");
+    strips_to("async (a?: number) => a", "async (a) => a");
+    strips_to("({ m(a?: number) {} })", "({ m(a) {} })");
+    assert!(typed("(a ? b : c)").is_ok());
+    assert!(typed("(a?.b)").is_ok());
+    assert!(typed("function f(a? = 1) {}").is_err());
+    assert!(typed("function f({ a }?) {}").is_err());
+    assert!(typed("(a?: number = 1) => a").is_err());
+    assert!(typed("(a?)").is_err());
+}
+
+#[test]
+fn this_parameters() {
+    strips_to("function f(this: A) {}", "function f() {}");
+    strips_to("function f(this: A, b: B) {}", "function f(b) {}");
+    strips_to("class C { m(this: C, x) {} }", "class C { m(x) {} }");
+    assert!(typed("function f(this) {}").is_err());
+    assert!(typed("function f(a, this: A) {}").is_err());
+    assert!(typed("(this: A) => 1").is_err());
+}
+
+#[test]
+fn type_parameters() {
+    strips_to("function f<T, U extends T = T>(a: T): U {}", "function f(a) {}");
+    strips_to("class A<T> extends B<T> {}", "class A extends B {}");
+    strips_to("(class<T,> {})", "(class {})");
+    strips_to("class A extends B<C<D>> { m<T>(a: T) {} }", "class A extends B { m(a) {} }");
+    assert!(typed("class A extends B<T>, C {}").is_err());
+}
+
+#[test]
+fn generic_arrow_functions() {
+    strips_to("async <T>(x: T) => x", "async (x) => x");
+    strips_to("async<T>(x)", "async(x)");
+    assert!(typed("async<T extends U>(x)").is_err());
+    assert!(typed("async\n<T>(x: T) => x").is_err());
+}
+
+#[test]
+fn generic_arrow_functions_without_jsx() {
+    strips_to("<T>(a: T) => a", "(a) => a");
+    strips_to("<T, U extends T>(a: T, b: U): T => a", "(a, b) => a");
+    assert!(typed("<T>(a)").is_err());
+    assert!(typed("<T>(x => x)").is_err());
+}
+
+#[cfg(feature = "jsx")]
+#[test]
+fn generic_arrow_functions_with_jsx() {
+    let options = Options { types: true, jsx: true, ..Default::default() };
+    assert!(options.script("<T>(a: T) => a").is_err());
+    assert!(options.script("<T>(a: T)</T>;").is_ok());
+    assert!(options.script("async <T>(a: T) => a").is_ok());
+}
+
+#[test]
+fn type_arguments() {
+    strips_to("f<T>(x);", "f(x);");
+    strips_to("new C<T>(x);", "new C(x);");
+    strips_to("f?.<T>(x);", "f?.(x);");
+    strips_to("a.f?.<T, U>(x).g;", "a.f?.(x).g;");
+    strips_to("a < b > c;", "a < b > c;");
+}
+
+#[test]
+fn type_queries() {
+    strips_to("var x: typeof y;", "var x;");
+    strips_to("var x: typeof y.z[];", "var x;");
+    assert!(typed("var x: typeof 1;").is_err());
+}
+
+#[test]
+fn as_expressions() {
+    strips_to("x as T;", "x;");
+    strips_to("f(x as const);", "f(x);");
+    match expr_stmt(typed("x as const;").unwrap()) {
+        Expr::As(_, _, ty) => match *ty {
+            Type::Const(_) => { }
+            ty => panic!("expected a const type, got {:?}", ty)
+        },
+        expr => panic!("expected an as expression, got {:?}", expr)
+    }
+    // "as" can't follow a line terminator.
+    let script = typed("x\nas\ny;").unwrap();
+    assert_eq!(script.items.len(), 3);
+    assert!(typed("x\nas T;").is_err());
+}
+
+#[test]
+fn type_declarations() {
+    strips_to("interface I<T> extends J { a?: T; m(x: number): void }\nvar i;", "var i;");
+    strips_to("type T = A | B & C;\nvar t;", "var t;");
+    strips_to("var type = 1, interface = 2;", "var type = 1, interface = 2;");
+}